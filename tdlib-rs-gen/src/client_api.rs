@@ -0,0 +1,108 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to generate the `ClientApi` trait from TL definitions, with a
+//! method for each function.
+
+use crate::functions;
+use crate::rustifier;
+use std::io::{self, Write};
+use tdlib_rs_parser::tl::{Category, Definition};
+
+/// Defines the method corresponding to the definition, sending its request
+/// with the `send` method of the trait:
+///
+/// ```ignore
+/// fn name(&self, field: Type) -> impl Future<Output = Result> + Send {
+///     let request = json!({ "@type": "name", "field": field });
+///     async move { crate::client_api::decode(self.send(request).await?) }
+/// }
+/// ```
+fn write_method<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
+    if rustifier::definitions::is_for_bots_only(def) && !gen_bots_only_api {
+        return Ok(());
+    }
+
+    functions::write_documentation(file, def, "        ", true, gen_bots_only_api)?;
+    writeln!(file, "        #[allow(clippy::too_many_arguments)]")?;
+    write!(
+        file,
+        "        fn {}(&self, ",
+        rustifier::definitions::function_name(def)
+    )?;
+    functions::write_params(file, def, gen_bots_only_api, gen_typed_ids)?;
+    writeln!(
+        file,
+        ") -> impl Future<Output = Result<{}, crate::types::Error>> + Send {{",
+        rustifier::types::qual_name(&def.ty, false)
+    )?;
+    functions::write_request(file, def, "            ", gen_bots_only_api)?;
+    if rustifier::types::is_ok(&def.ty) {
+        writeln!(
+            file,
+            "            async move {{ self.send(request).await.map(|_| ()) }}"
+        )?;
+    } else {
+        writeln!(
+            file,
+            "            async move {{ crate::client_api::decode(self.send(request).await?) }}"
+        )?;
+    }
+    writeln!(file, "        }}")?;
+    Ok(())
+}
+
+/// Write the entire module dedicated to the `ClientApi` trait.
+pub(crate) fn write_client_api_mod<W: Write>(
+    file: &mut W,
+    definitions: &[Definition],
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
+    // Begin outermost mod
+    writeln!(file, "#[allow(clippy::all)]")?;
+    writeln!(file, "pub mod client_api {{")?;
+    writeln!(file, "    use serde_json::json;")?;
+    writeln!(file, "    use std::future::Future;")?;
+
+    writeln!(
+        file,
+        "    /// The functions of TdLib, as methods sending their request through [`ClientApi::send`],"
+    )?;
+    writeln!(
+        file,
+        "    /// so that the code using them can be run against a mock instead of TdLib."
+    )?;
+    writeln!(file, "    pub trait ClientApi: Sync {{")?;
+    writeln!(
+        file,
+        "        /// Send the request to TdLib, returning the JSON of its response, or the error"
+    )?;
+    writeln!(file, "        /// TdLib answered with.")?;
+    writeln!(
+        file,
+        "        fn send(&self, request: serde_json::Value) -> impl Future<Output = Result<crate::RawJson, crate::types::Error>> + Send;"
+    )?;
+
+    for def in definitions
+        .iter()
+        .filter(|d| d.category == Category::Functions)
+    {
+        write_method(file, def, gen_bots_only_api, gen_typed_ids)?;
+    }
+
+    writeln!(file, "    }}")?;
+
+    // End outermost mod
+    writeln!(file, "}}")
+}
@@ -16,6 +16,36 @@ use crate::rustifier;
 use std::io::{self, Write};
 use tdlib_rs_parser::tl::{Category, Definition, Type};
 
+/// The name of the type whose unknown kinds are not deserialized into a
+/// fallback variant, so that they still reach the deserialization hook.
+const UPDATE_TYPE: &str = "Update";
+
+/// The name of the fallback variant added to the enumerations.
+const FALLBACK_VARIANT: &str = "Unsupported";
+
+/// The variant an unknown `@type` of the enumeration is deserialized into,
+/// so that a kind added by a newer TdLib doesn't fail the whole object, and
+/// whether it is added instead of being one of the variants of the type.
+///
+/// Only the types with several kinds have a fallback, so that the types
+/// with a single one, like `Message`, can still be destructured with `let`.
+/// A unit variant already named `Unsupported`, like the one of
+/// `storyContentUnsupported`, is used as the fallback; if the name is taken
+/// by a variant with data, the fallback is named `UnsupportedType`.
+fn fallback_variant(ty: &Type, defs: &[&Definition]) -> Option<(String, bool)> {
+    if ty.name == UPDATE_TYPE || defs.len() < 2 {
+        return None;
+    }
+    match defs
+        .iter()
+        .find(|d| rustifier::definitions::variant_name(d) == FALLBACK_VARIANT)
+    {
+        Some(d) if d.params.is_empty() => Some((FALLBACK_VARIANT.into(), false)),
+        Some(_) => Some((format!("{}Type", FALLBACK_VARIANT), true)),
+        None => Some((FALLBACK_VARIANT.into(), true)),
+    }
+}
+
 /// Writes an enumeration listing all types such as the following rust code:
 ///
 /// ```ignore
@@ -29,17 +59,17 @@ fn write_enum<W: Write>(
     metadata: &Metadata,
     gen_bots_only_api: bool,
 ) -> io::Result<()> {
-    writeln!(
-        file,
-        "    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]",
-    )?;
+    writeln!(file, "    #[derive(Clone, Debug, PartialEq, Deserialize)]",)?;
     writeln!(file, "    #[serde(tag = \"@type\")]")?;
     writeln!(file, "    pub enum {} {{", rustifier::types::type_name(ty))?;
-    for d in metadata.defs_with_type(ty) {
-        if rustifier::definitions::is_for_bots_only(d) && !gen_bots_only_api {
-            continue;
-        }
-
+    let defs = metadata
+        .defs_with_type(ty)
+        .iter()
+        .copied()
+        .filter(|d| !rustifier::definitions::is_for_bots_only(d) || gen_bots_only_api)
+        .collect::<Vec<_>>();
+    let fallback = fallback_variant(ty, &defs);
+    for d in defs {
         writeln!(
             file,
             "{}",
@@ -50,6 +80,11 @@ fn write_enum<W: Write>(
             "        #[serde(rename(serialize = \"{0}\", deserialize = \"{0}\"))]",
             d.name
         )?;
+        if fallback.as_ref().is_some_and(|(variant, added)| {
+            !added && *variant == rustifier::definitions::variant_name(d)
+        }) {
+            writeln!(file, "        #[serde(other)]")?;
+        }
         write!(file, "        {}", rustifier::definitions::variant_name(d))?;
 
         // Variant with no struct since it has no data and it only adds noise
@@ -70,6 +105,109 @@ fn write_enum<W: Write>(
 
         writeln!(file, "),")?;
     }
+    if let Some((variant, true)) = &fallback {
+        writeln!(
+            file,
+            "        /// A kind unknown to the schema of the library, sent by a newer TdLib"
+        )?;
+        writeln!(file, "        #[serde(other)]")?;
+        writeln!(file, "        {},", variant)?;
+    }
+    writeln!(file, "    }}")?;
+    write_serialize_impl(file, ty, metadata, gen_bots_only_api)?;
+    Ok(())
+}
+
+/// Writes the implementation of `Serialize` for an enumeration. Variants
+/// with data are serialized as their struct, which already writes its own
+/// `@type` tag, while the others are serialized as a lone `@type` tag:
+///
+/// ```ignore
+/// impl Serialize for Name {
+///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         match self {
+///             Name::Variant(x) => x.serialize(serializer),
+///         }
+///     }
+/// }
+/// ```
+fn write_serialize_impl<W: Write>(
+    file: &mut W,
+    ty: &Type,
+    metadata: &Metadata,
+    gen_bots_only_api: bool,
+) -> io::Result<()> {
+    let name = rustifier::types::type_name(ty);
+    let defs = metadata
+        .defs_with_type(ty)
+        .iter()
+        .filter(|d| !rustifier::definitions::is_for_bots_only(d) || gen_bots_only_api)
+        .collect::<Vec<_>>();
+
+    let fallback = fallback_variant(ty, &defs.iter().copied().copied().collect::<Vec<_>>())
+        .filter(|(_, added)| *added);
+
+    writeln!(file, "    impl Serialize for {} {{", name)?;
+
+    // The enumeration may have no variants if they are all for bots only
+    if defs.is_empty() && fallback.is_none() {
+        writeln!(
+            file,
+            "        fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {{"
+        )?;
+        writeln!(file, "            match *self {{}}")?;
+        writeln!(file, "        }}")?;
+        writeln!(file, "    }}")?;
+        return Ok(());
+    }
+
+    writeln!(
+        file,
+        "        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{"
+    )?;
+    writeln!(file, "            match self {{")?;
+    for d in defs {
+        let variant = rustifier::definitions::variant_name(d);
+        if d.params.is_empty() {
+            writeln!(file, "                {}::{} => {{", name, variant)?;
+            writeln!(
+                file,
+                "                    let mut state = serializer.serialize_struct(\"{}\", 1)?;",
+                name
+            )?;
+            writeln!(
+                file,
+                "                    state.serialize_field(\"@type\", \"{}\")?;",
+                d.name
+            )?;
+            writeln!(file, "                    state.end()")?;
+            writeln!(file, "                }}")?;
+        } else {
+            writeln!(
+                file,
+                "                {}::{}(x) => x.serialize(serializer),",
+                name, variant
+            )?;
+        }
+    }
+    // The unknown kinds are serialized as a lone `unsupported` tag, which is
+    // deserialized back into the fallback variant
+    if let Some((variant, _)) = fallback {
+        writeln!(file, "                {}::{} => {{", name, variant)?;
+        writeln!(
+            file,
+            "                    let mut state = serializer.serialize_struct(\"{}\", 1)?;",
+            name
+        )?;
+        writeln!(
+            file,
+            "                    state.serialize_field(\"@type\", \"unsupported\")?;"
+        )?;
+        writeln!(file, "                    state.end()")?;
+        writeln!(file, "                }}")?;
+    }
+    writeln!(file, "            }}")?;
+    writeln!(file, "        }}")?;
     writeln!(file, "    }}")?;
     Ok(())
 }
@@ -84,7 +222,10 @@ pub(crate) fn write_enums_mod<W: Write>(
     // Begin outermost mod
     writeln!(file, "#[allow(clippy::all)]")?;
     writeln!(file, "pub mod enums {{")?;
-    writeln!(file, "    use serde::{{Deserialize, Serialize}};")?;
+    writeln!(
+        file,
+        "    use serde::{{ser::SerializeStruct, Deserialize, Serialize, Serializer}};"
+    )?;
 
     let mut enums: Vec<&Type> = definitions
         .iter()
@@ -100,3 +241,49 @@ pub(crate) fn write_enums_mod<W: Write>(
     // End outermost mod
     writeln!(file, "}}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions(tl: &[&str]) -> Vec<Definition> {
+        tl.iter().map(|d| d.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn check_fallback_variant() {
+        let defs = definitions(&[
+            "chatTypePrivate user_id:int53 = ChatType",
+            "chatTypeSecret = ChatType",
+        ]);
+        let defs = defs.iter().collect::<Vec<_>>();
+        assert_eq!(
+            fallback_variant(&defs[0].ty, &defs),
+            Some(("Unsupported".into(), true))
+        );
+
+        let defs = definitions(&[
+            "storyContentPhoto = StoryContent",
+            "storyContentUnsupported = StoryContent",
+        ]);
+        let defs = defs.iter().collect::<Vec<_>>();
+        assert_eq!(
+            fallback_variant(&defs[0].ty, &defs),
+            Some(("Unsupported".into(), false))
+        );
+
+        let defs = definitions(&[
+            "messageExtendedMediaPhoto = MessageExtendedMedia",
+            "messageExtendedMediaUnsupported caption:string = MessageExtendedMedia",
+        ]);
+        let defs = defs.iter().collect::<Vec<_>>();
+        assert_eq!(
+            fallback_variant(&defs[0].ty, &defs),
+            Some(("UnsupportedType".into(), true))
+        );
+
+        let defs = definitions(&["message id:int53 = Message"]);
+        let defs = defs.iter().collect::<Vec<_>>();
+        assert_eq!(fallback_variant(&defs[0].ty, &defs), None);
+    }
+}
@@ -15,26 +15,17 @@ use crate::rustifier;
 use std::io::{self, Write};
 use tdlib_rs_parser::tl::{Category, Definition};
 
-/// Defines the `function` corresponding to the definition:
-///
-/// ```ignore
-/// pub async fn name(client_id: i32, field: Type) -> Result {
-///
-/// }
-/// ```
-fn write_function<W: Write>(
+/// Writes the documentation of the `function` corresponding to the definition,
+/// with the `client_id` argument unless it is a method.
+pub(crate) fn write_documentation<W: Write>(
     file: &mut W,
     def: &Definition,
-    _metadata: &Metadata,
+    indent: &str,
+    is_method: bool,
     gen_bots_only_api: bool,
 ) -> io::Result<()> {
-    if rustifier::definitions::is_for_bots_only(def) && !gen_bots_only_api {
-        return Ok(());
-    }
-
-    // Documentation
-    writeln!(file, "{}", rustifier::definitions::description(def, "    "))?;
-    writeln!(file, "    /// # Arguments")?;
+    writeln!(file, "{}", rustifier::definitions::description(def, indent))?;
+    writeln!(file, "{}/// # Arguments", indent)?;
     for param in def.params.iter() {
         if rustifier::parameters::is_for_bots_only(param) && !gen_bots_only_api {
             continue;
@@ -42,23 +33,32 @@ fn write_function<W: Write>(
 
         writeln!(
             file,
-            "    /// * `{}` - {}",
+            "{0}/// * `{1}` - {2}",
+            indent,
             rustifier::parameters::attr_name(param),
-            param.description.replace('\n', "\n    /// ")
+            param
+                .description
+                .replace('\n', &format!("\n{}/// ", indent))
         )?;
     }
-    writeln!(
-        file,
-        "    /// * `client_id` - The client id to send the request to"
-    )?;
+    if !is_method {
+        writeln!(
+            file,
+            "{}/// * `client_id` - The client id to send the request to",
+            indent
+        )?;
+    }
+    Ok(())
+}
 
-    // Function
-    writeln!(file, "    #[allow(clippy::too_many_arguments)]")?;
-    write!(
-        file,
-        "    pub async fn {}(",
-        rustifier::definitions::function_name(def)
-    )?;
+/// Writes the parameters of the `function` corresponding to the definition,
+/// each followed by a comma.
+pub(crate) fn write_params<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
     for param in def.params.iter() {
         if rustifier::parameters::is_for_bots_only(param) && !gen_bots_only_api {
             continue;
@@ -70,23 +70,29 @@ fn write_function<W: Write>(
         if is_optional {
             write!(file, "Option<")?;
         }
-        write!(file, "{}", rustifier::parameters::qual_name(param))?;
+        match rustifier::parameters::id_qual_name(def, param) {
+            Some(id_qual_name) if gen_typed_ids => write!(file, "{}", id_qual_name)?,
+            _ => write!(file, "{}", rustifier::parameters::qual_name(param))?,
+        }
         if is_optional {
             write!(file, ">")?;
         }
 
         write!(file, ", ")?;
     }
+    Ok(())
+}
 
-    writeln!(
-        file,
-        "client_id: i32) -> Result<{}, crate::types::Error> {{",
-        rustifier::types::qual_name(&def.ty, false)
-    )?;
-
-    // Compose request
-    writeln!(file, "        let request = json!({{")?;
-    writeln!(file, "            \"@type\": \"{}\",", def.name)?;
+/// Writes the `request` JSON of the `function` corresponding to the
+/// definition, built from its parameters.
+pub(crate) fn write_request<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    indent: &str,
+    gen_bots_only_api: bool,
+) -> io::Result<()> {
+    writeln!(file, "{}let request = json!({{", indent)?;
+    writeln!(file, "{}    \"@type\": \"{}\",", indent, def.name)?;
     for param in def.params.iter() {
         if rustifier::parameters::is_for_bots_only(param) && !gen_bots_only_api {
             continue;
@@ -94,34 +100,120 @@ fn write_function<W: Write>(
 
         writeln!(
             file,
-            "            \"{0}\": {1},",
+            "{0}    \"{1}\": {2},",
+            indent,
             param.name,
             rustifier::parameters::attr_name(param),
         )?;
     }
-    writeln!(file, "        }});")?;
+    writeln!(file, "{}}});", indent)?;
+    Ok(())
+}
+
+/// Writes the signature of the `function` corresponding to the definition,
+/// up to the opening brace of its body.
+fn write_signature<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    is_async: bool,
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
+    writeln!(file, "    #[allow(clippy::too_many_arguments)]")?;
+    write!(
+        file,
+        "    pub {}fn {}(",
+        if is_async { "async " } else { "" },
+        rustifier::definitions::function_name(def)
+    )?;
+    write_params(file, def, gen_bots_only_api, gen_typed_ids)?;
 
-    // Send request
     writeln!(
         file,
-        "        let response = send_request(client_id, request).await;"
+        "client_id: i32) -> Result<{}, crate::types::Error> {{",
+        rustifier::types::qual_name(&def.ty, false)
     )?;
-    writeln!(file, "        if response[\"@type\"] == \"error\" {{")?;
+    Ok(())
+}
+
+/// Defines the `function` corresponding to the definition:
+///
+/// ```ignore
+/// pub async fn name(client_id: i32, field: Type) -> Result {
+///
+/// }
+/// ```
+fn write_function<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    _metadata: &Metadata,
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
+    if rustifier::definitions::is_for_bots_only(def) && !gen_bots_only_api {
+        return Ok(());
+    }
+
+    write_documentation(file, def, "    ", false, gen_bots_only_api)?;
+    write_signature(file, def, true, gen_bots_only_api, gen_typed_ids)?;
+
+    // Compose request
+    write_request(file, def, "        ", gen_bots_only_api)?;
+
+    // Send request
     writeln!(
         file,
-        "            return Err(serde_json::from_value(response).unwrap())"
+        "        let response = send_request(client_id, request).await;"
     )?;
+    writeln!(file, "        if response.is_error() {{")?;
+    writeln!(file, "            return Err(response.error())")?;
     writeln!(file, "        }}")?;
 
     if rustifier::types::is_ok(&def.ty) {
         writeln!(file, "        Ok(())")?;
     } else {
-        writeln!(
-            file,
-            "        Ok(serde_json::from_value(response).unwrap())"
-        )?;
+        writeln!(file, "        response.result()")?;
+    }
+
+    writeln!(file, "    }}")?;
+    Ok(())
+}
+
+/// Defines the blocking `function` corresponding to the definition, which
+/// waits for the `function` of the `functions` module:
+///
+/// ```ignore
+/// pub fn name(client_id: i32, field: Type) -> Result {
+///     crate::runtime::block_on(crate::functions::name(client_id, field))
+/// }
+/// ```
+fn write_blocking_function<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
+    if rustifier::definitions::is_for_bots_only(def) && !gen_bots_only_api {
+        return Ok(());
     }
 
+    write_documentation(file, def, "    ", false, gen_bots_only_api)?;
+    write_signature(file, def, false, gen_bots_only_api, gen_typed_ids)?;
+
+    write!(
+        file,
+        "        crate::runtime::block_on(crate::functions::{}(",
+        rustifier::definitions::function_name(def)
+    )?;
+    for param in def.params.iter() {
+        if rustifier::parameters::is_for_bots_only(param) && !gen_bots_only_api {
+            continue;
+        }
+
+        write!(file, "{}, ", rustifier::parameters::attr_name(param))?;
+    }
+    writeln!(file, "client_id))")?;
+
     writeln!(file, "    }}")?;
     Ok(())
 }
@@ -132,8 +224,9 @@ fn write_definition<W: Write>(
     def: &Definition,
     metadata: &Metadata,
     gen_bots_only_api: bool,
+    gen_typed_ids: bool,
 ) -> io::Result<()> {
-    write_function(file, def, metadata, gen_bots_only_api)?;
+    write_function(file, def, metadata, gen_bots_only_api, gen_typed_ids)?;
     Ok(())
 }
 
@@ -143,6 +236,7 @@ pub(crate) fn write_functions_mod<W: Write>(
     definitions: &[Definition],
     metadata: &Metadata,
     gen_bots_only_api: bool,
+    gen_typed_ids: bool,
 ) -> io::Result<()> {
     // Begin outermost mod
     writeln!(file, "#[allow(clippy::all)]")?;
@@ -155,7 +249,36 @@ pub(crate) fn write_functions_mod<W: Write>(
         .filter(|d| d.category == Category::Functions);
 
     for definition in functions {
-        write_definition(&mut file, definition, metadata, gen_bots_only_api)?;
+        write_definition(
+            &mut file,
+            definition,
+            metadata,
+            gen_bots_only_api,
+            gen_typed_ids,
+        )?;
+    }
+
+    // End outermost mod
+    writeln!(file, "}}")
+}
+
+/// Write the entire module dedicated to blocking functions.
+pub(crate) fn write_blocking_mod<W: Write>(
+    mut file: &mut W,
+    definitions: &[Definition],
+    gen_bots_only_api: bool,
+    gen_typed_ids: bool,
+) -> io::Result<()> {
+    // Begin outermost mod
+    writeln!(file, "#[allow(clippy::all)]")?;
+    writeln!(file, "pub mod blocking {{")?;
+
+    let functions = definitions
+        .iter()
+        .filter(|d| d.category == Category::Functions);
+
+    for definition in functions {
+        write_blocking_function(&mut file, definition, gen_bots_only_api, gen_typed_ids)?;
     }
 
     // End outermost mod
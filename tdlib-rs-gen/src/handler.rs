@@ -0,0 +1,149 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to generate the `UpdateHandler` trait from TL definitions.
+
+use crate::metadata::Metadata;
+use crate::rustifier;
+use std::io::{self, Write};
+use tdlib_rs_parser::tl::{Category, Definition};
+
+/// The name of the type of the updates.
+const UPDATE_TYPE: &str = "Update";
+
+/// Get the name of the method handling the update of a definition.
+///
+/// For example, transforms `"updateNewMessage"` into `"on_new_message"`.
+fn method_name(def: &Definition) -> String {
+    let mut result = String::from("on");
+    rustifier::definitions::variant_name(def)
+        .chars()
+        .for_each(|c| {
+            if c.is_ascii_uppercase() {
+                result.push('_');
+                result.push(c.to_ascii_lowercase());
+            } else {
+                result.push(c);
+            }
+        });
+    result
+}
+
+/// Writes the trait with a method for each update, such as the following
+/// rust code:
+///
+/// ```ignore
+/// pub trait UpdateHandler {
+///     fn on_name(&mut self, update: crate::types::UpdateName, client_id: i32) {}
+///
+///     fn handle_update(&mut self, update: crate::enums::Update, client_id: i32) {
+///         match update {
+///             crate::enums::Update::Name(update) => self.on_name(update, client_id),
+///         }
+///     }
+/// }
+/// ```
+fn write_trait<W: Write>(
+    file: &mut W,
+    updates: &[&Definition],
+    metadata: &Metadata,
+) -> io::Result<()> {
+    writeln!(
+        file,
+        "    /// A handler of the updates received from TdLib, with a method for each kind of update."
+    )?;
+    writeln!(
+        file,
+        "    /// All the methods do nothing by default, so only the interesting updates need to be handled."
+    )?;
+    writeln!(file, "    pub trait UpdateHandler {{")?;
+    for d in updates {
+        writeln!(
+            file,
+            "{}",
+            rustifier::definitions::description(d, "        ")
+        )?;
+        write!(file, "        fn {}(&mut self, ", method_name(d))?;
+        if !d.params.is_empty() {
+            write!(file, "update: ")?;
+            if metadata.is_recursive_def(d) {
+                write!(file, "Box<{}>", rustifier::definitions::qual_name(d))?;
+            } else {
+                write!(file, "{}", rustifier::definitions::qual_name(d))?;
+            }
+            write!(file, ", ")?;
+        }
+        writeln!(file, "client_id: i32) {{}}")?;
+    }
+
+    writeln!(
+        file,
+        "        /// Handle an update, calling the method dedicated to its kind"
+    )?;
+    writeln!(
+        file,
+        "        fn handle_update(&mut self, update: crate::enums::Update, client_id: i32) {{"
+    )?;
+    writeln!(file, "            match update {{")?;
+    for d in updates {
+        let variant = rustifier::definitions::variant_name(d);
+        if d.params.is_empty() {
+            writeln!(
+                file,
+                "                crate::enums::Update::{} => self.{}(client_id),",
+                variant,
+                method_name(d)
+            )?;
+        } else {
+            writeln!(
+                file,
+                "                crate::enums::Update::{}(update) => self.{}(update, client_id),",
+                variant,
+                method_name(d)
+            )?;
+        }
+    }
+    writeln!(file, "            }}")?;
+    writeln!(file, "        }}")?;
+    writeln!(file, "    }}")?;
+    Ok(())
+}
+
+/// Write the entire module dedicated to the update handler.
+pub(crate) fn write_handler_mod<W: Write>(
+    file: &mut W,
+    definitions: &[Definition],
+    metadata: &Metadata,
+    gen_bots_only_api: bool,
+) -> io::Result<()> {
+    let updates = definitions
+        .iter()
+        .filter(|d| d.category == Category::Types && d.ty.name == UPDATE_TYPE)
+        .filter(|d| !rustifier::definitions::is_for_bots_only(d) || gen_bots_only_api)
+        .collect::<Vec<_>>();
+
+    // Begin outermost mod
+    writeln!(file, "#[allow(clippy::all, unused_variables)]")?;
+    writeln!(file, "pub mod handler {{")?;
+
+    write_trait(file, &updates, metadata)?;
+
+    // End outermost mod
+    writeln!(file, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_method_name() {
+        let def = "updateNewMessage message:message = Update".parse().unwrap();
+        assert_eq!(method_name(&def), "on_new_message");
+    }
+}
@@ -10,10 +10,13 @@
 
 //! This module gathers all the code generation submodules and coordinates
 //! them, feeding them the right data.
+mod client_api;
 mod enums;
 mod functions;
+mod handler;
 mod metadata;
 mod rustifier;
+mod schema;
 mod types;
 
 use std::io::{self, Write};
@@ -27,10 +30,21 @@ fn ignore_type(ty: &Type) -> bool {
     SPECIAL_CASED_TYPES.iter().any(|&x| x == ty.name)
 }
 
+/// Generate the Rust code for the given definitions.
+///
+/// * `gen_bots_only_api` - Generate the types and functions only available to the Telegram bots
+/// * `gen_extra_fields` - Add an `extra` map to every struct collecting the fields unknown to
+///   the schema, instead of ignoring them
+/// * `gen_typed_ids` - Use the `ChatId`, `UserId`, `MessageId` and `FileId` newtypes for the
+///   identifiers of chats, users, messages and files, instead of bare integers
+/// * `gen_blocking` - Generate the `blocking` module, with a synchronous version of every function
 pub fn generate_rust_code(
     file: &mut impl Write,
     definitions: &[Definition],
     gen_bots_only_api: bool,
+    gen_extra_fields: bool,
+    gen_typed_ids: bool,
+    gen_blocking: bool,
 ) -> io::Result<()> {
     write!(
         file,
@@ -48,9 +62,28 @@ pub fn generate_rust_code(
     )?;
 
     let metadata = metadata::Metadata::new(definitions);
-    types::write_types_mod(file, definitions, &metadata, gen_bots_only_api)?;
+    types::write_types_mod(
+        file,
+        definitions,
+        &metadata,
+        gen_bots_only_api,
+        gen_extra_fields,
+        gen_typed_ids,
+    )?;
     enums::write_enums_mod(file, definitions, &metadata, gen_bots_only_api)?;
-    functions::write_functions_mod(file, definitions, &metadata, gen_bots_only_api)?;
+    functions::write_functions_mod(
+        file,
+        definitions,
+        &metadata,
+        gen_bots_only_api,
+        gen_typed_ids,
+    )?;
+    if gen_blocking {
+        functions::write_blocking_mod(file, definitions, gen_bots_only_api, gen_typed_ids)?;
+    }
+    client_api::write_client_api_mod(file, definitions, gen_bots_only_api, gen_typed_ids)?;
+    handler::write_handler_mod(file, definitions, &metadata, gen_bots_only_api)?;
+    schema::write_schema_mod(file, definitions, gen_bots_only_api)?;
 
     Ok(())
 }
@@ -286,6 +286,45 @@ pub mod parameters {
     pub fn serde_as(param: &Parameter) -> Option<String> {
         types::serde_as(&param.ty)
     }
+
+    /// The entities with an identifier newtype, the builtin type of their
+    /// identifier and the qualified name of the newtype.
+    const ID_TYPES: [(&str, &str, &str); 4] = [
+        ("chat", "int53", "crate::ChatId"),
+        ("user", "int53", "crate::UserId"),
+        ("message", "int53", "crate::MessageId"),
+        ("file", "int32", "crate::FileId"),
+    ];
+
+    /// Get the qualified name of the identifier newtype of a parameter, if
+    /// it identifies a chat, a user, a message or a file.
+    ///
+    /// The parameters are matched by name, so `chat_id`, `from_chat_id` and
+    /// `chat_ids` are all chat identifiers, while `id` is matched only in the
+    /// definition of the entity it identifies.
+    pub fn id_qual_name(def: &Definition, param: &Parameter) -> Option<String> {
+        let (name, ty, is_vector) = match &param.ty.generic_arg {
+            Some(generic_ty) if param.ty.name == "vector" => {
+                (param.name.strip_suffix('s')?, &**generic_ty, true)
+            }
+            Some(_) => return None,
+            None => (&param.name[..], &param.ty, false),
+        };
+
+        let (_, _, id_qual_name) = ID_TYPES.iter().find(|(entity, builtin, _)| {
+            let id = format!("{}_id", entity);
+            ty.name == *builtin
+                && (name == id
+                    || name.ends_with(&format!("_{}", id))
+                    || (name == "id" && def.name == *entity && !is_vector))
+        })?;
+
+        if is_vector {
+            Some(format!("Vec<{}>", id_qual_name))
+        } else {
+            Some(id_qual_name.to_string())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -410,6 +449,44 @@ mod tests {
         assert_eq!(name, "crate::types::Int");
     }
 
+    #[test]
+    fn check_param_id_qual_name() {
+        let def = "user id:int53 first_name:string = User".parse().unwrap();
+        let param = "id:int53".parse().unwrap();
+        let name = parameters::id_qual_name(&def, &param);
+        assert_eq!(name.as_deref(), Some("crate::UserId"));
+    }
+
+    #[test]
+    fn check_param_prefixed_id_qual_name() {
+        let def = "forwardMessages chat_id:int53 from_chat_id:int53 = Messages"
+            .parse()
+            .unwrap();
+        let param = "from_chat_id:int53".parse().unwrap();
+        let name = parameters::id_qual_name(&def, &param);
+        assert_eq!(name.as_deref(), Some("crate::ChatId"));
+    }
+
+    #[test]
+    fn check_param_vec_id_qual_name() {
+        let def = "getMessages chat_id:int53 message_ids:vector<int53> = Messages"
+            .parse()
+            .unwrap();
+        let param = "message_ids:vector<int53>".parse().unwrap();
+        let name = parameters::id_qual_name(&def, &param);
+        assert_eq!(name.as_deref(), Some("Vec<crate::MessageId>"));
+    }
+
+    #[test]
+    fn check_param_no_id_qual_name() {
+        let def = "chat id:int53 title:string = Chat".parse().unwrap();
+        let param = "secret_chat_id:int32".parse().unwrap();
+        assert_eq!(parameters::id_qual_name(&def, &param), None);
+        let param = "id:int53".parse().unwrap();
+        let def = "supergroup id:int53 = Supergroup".parse().unwrap();
+        assert_eq!(parameters::id_qual_name(&def, &param), None);
+    }
+
     #[test]
     fn check_param_attr_name() {
         let param = "access_hash:long".parse().unwrap();
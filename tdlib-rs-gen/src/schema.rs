@@ -0,0 +1,133 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to generate the schema of the TL definitions as data, read at
+//! runtime through the `schema` module.
+
+use crate::ignore_type;
+use crate::rustifier;
+use std::io::{self, Write};
+use tdlib_rs_parser::tl::{Category, Definition};
+
+/// Writes the definition as a `crate::schema::Definition`, such as the
+/// following rust code:
+///
+/// ```ignore
+/// crate::schema::Definition {
+///     name: "name",
+///     ty: "Type",
+///     description: "Description",
+///     fields: &[crate::schema::Field {
+///         name: "field",
+///         ty: "vector<int32>",
+///         description: "Description",
+///         optional: false,
+///     }],
+/// },
+/// ```
+fn write_definition<W: Write>(
+    file: &mut W,
+    def: &Definition,
+    gen_bots_only_api: bool,
+) -> io::Result<()> {
+    writeln!(file, "        crate::schema::Definition {{")?;
+    writeln!(file, "            name: {:?},", def.name)?;
+    writeln!(file, "            ty: {:?},", def.ty.to_string())?;
+    writeln!(file, "            description: {:?},", def.description)?;
+    writeln!(file, "            fields: &[")?;
+    for param in def.params.iter() {
+        if rustifier::parameters::is_for_bots_only(param) && !gen_bots_only_api {
+            continue;
+        }
+        writeln!(
+            file,
+            "                crate::schema::Field {{ name: {:?}, ty: {:?}, description: {:?}, optional: {} }},",
+            param.name,
+            param.ty.to_string(),
+            param.description,
+            rustifier::parameters::is_optional(param)
+        )?;
+    }
+    writeln!(file, "            ],")?;
+    writeln!(file, "        }},")?;
+    Ok(())
+}
+
+/// Writes the static array of the definitions of the category.
+fn write_definitions<W: Write>(
+    file: &mut W,
+    name: &str,
+    definitions: &[Definition],
+    category: Category,
+    gen_bots_only_api: bool,
+) -> io::Result<()> {
+    writeln!(
+        file,
+        "    pub(crate) static {}: &[crate::schema::Definition] = &[",
+        name
+    )?;
+    for def in definitions
+        .iter()
+        .filter(|d| d.category == category && !ignore_type(&d.ty))
+        .filter(|d| !rustifier::definitions::is_for_bots_only(d) || gen_bots_only_api)
+    {
+        write_definition(file, def, gen_bots_only_api)?;
+    }
+    writeln!(file, "    ];")?;
+    Ok(())
+}
+
+/// Write the entire module dedicated to the schema.
+pub(crate) fn write_schema_mod<W: Write>(
+    file: &mut W,
+    definitions: &[Definition],
+    gen_bots_only_api: bool,
+) -> io::Result<()> {
+    // Begin outermost mod
+    writeln!(file, "#[allow(clippy::all)]")?;
+    writeln!(file, "pub mod schema {{")?;
+
+    write_definitions(
+        file,
+        "TYPES",
+        definitions,
+        Category::Types,
+        gen_bots_only_api,
+    )?;
+    write_definitions(
+        file,
+        "FUNCTIONS",
+        definitions,
+        Category::Functions,
+        gen_bots_only_api,
+    )?;
+
+    // End outermost mod
+    writeln!(file, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_write_definition() {
+        let def = "//@description Sends a message @chat_id Target chat @text Text of the message\nsendMessage chat_id:int53 text:vector<string> = Message"
+            .parse()
+            .unwrap();
+        let mut output = Vec::new();
+        write_definition(&mut output, &def, false).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("name: \"sendMessage\","));
+        assert!(output.contains("ty: \"Message\","));
+        assert!(output.contains("description: \"Sends a message\","));
+        assert!(output.contains(
+            "crate::schema::Field { name: \"text\", ty: \"vector<string>\", description: \"Text of the message\", optional: false },"
+        ));
+    }
+}
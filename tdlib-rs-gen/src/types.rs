@@ -28,6 +28,8 @@ fn write_struct<W: Write>(
     def: &Definition,
     metadata: &Metadata,
     gen_bots_only_api: bool,
+    gen_extra_fields: bool,
+    gen_typed_ids: bool,
 ) -> io::Result<()> {
     if rustifier::definitions::is_for_bots_only(def) && !gen_bots_only_api {
         return Ok(());
@@ -49,6 +51,13 @@ fn write_struct<W: Write>(
         write!(file, "Default, ",)?;
     }
     writeln!(file, "PartialEq, Deserialize, Serialize)]",)?;
+    // The tag is written when serializing, so that the type can be sent back
+    // to TdLib even outside of an enum, and it is ignored when deserializing
+    writeln!(
+        file,
+        "    #[serde(tag = \"@type\", rename = \"{}\")]",
+        def.name
+    )?;
 
     writeln!(
         file,
@@ -80,7 +89,10 @@ fn write_struct<W: Write>(
         if is_optional {
             write!(file, "Option<")?;
         }
-        write!(file, "{}", rustifier::parameters::qual_name(param))?;
+        match rustifier::parameters::id_qual_name(def, param) {
+            Some(id_qual_name) if gen_typed_ids => write!(file, "{}", id_qual_name)?,
+            _ => write!(file, "{}", rustifier::parameters::qual_name(param))?,
+        }
         if is_optional {
             write!(file, ">")?;
         }
@@ -88,6 +100,21 @@ fn write_struct<W: Write>(
         writeln!(file, ",")?;
     }
 
+    if gen_extra_fields {
+        writeln!(
+            file,
+            "        /// Fields sent by TDLib which are not part of the schema used to generate this type"
+        )?;
+        writeln!(
+            file,
+            "        #[serde(flatten, deserialize_with = \"crate::extra_fields::deserialize\")]"
+        )?;
+        writeln!(
+            file,
+            "        pub extra: serde_json::Map<String, serde_json::Value>,"
+        )?;
+    }
+
     writeln!(file, "    }}")?;
     Ok(())
 }
@@ -98,8 +125,17 @@ fn write_definition<W: Write>(
     def: &Definition,
     metadata: &Metadata,
     gen_bots_only_api: bool,
+    gen_extra_fields: bool,
+    gen_typed_ids: bool,
 ) -> io::Result<()> {
-    write_struct(file, def, metadata, gen_bots_only_api)?;
+    write_struct(
+        file,
+        def,
+        metadata,
+        gen_bots_only_api,
+        gen_extra_fields,
+        gen_typed_ids,
+    )?;
     Ok(())
 }
 
@@ -109,6 +145,8 @@ pub(crate) fn write_types_mod<W: Write>(
     definitions: &[Definition],
     metadata: &Metadata,
     gen_bots_only_api: bool,
+    gen_extra_fields: bool,
+    gen_typed_ids: bool,
 ) -> io::Result<()> {
     // Begin outermost mod
     writeln!(file, "#[allow(clippy::all)]")?;
@@ -121,7 +159,14 @@ pub(crate) fn write_types_mod<W: Write>(
         .filter(|d| d.category == Category::Types && !ignore_type(&d.ty) && !d.params.is_empty());
 
     for definition in types {
-        write_definition(&mut file, definition, metadata, gen_bots_only_api)?;
+        write_definition(
+            &mut file,
+            definition,
+            metadata,
+            gen_bots_only_api,
+            gen_extra_fields,
+            gen_typed_ids,
+        )?;
     }
 
     // End outermost mod
@@ -0,0 +1,82 @@
+// cargo bench -p tdlib-rs --bench observer
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use tdlib_rs::registry::Registry;
+
+/// The requests registered and answered by each sender.
+const REQUESTS: u32 = 10_000;
+
+/// The registry used before it was sharded.
+#[derive(Default)]
+struct SingleLock(RwLock<HashMap<u32, u64>>);
+
+trait Requests: Send + Sync + 'static {
+    fn subscribe(&self, extra: u32);
+    fn notify(&self, extra: u32) -> Option<u64>;
+}
+
+impl Requests for SingleLock {
+    fn subscribe(&self, extra: u32) {
+        self.0.write().unwrap().insert(extra, extra as u64);
+    }
+
+    fn notify(&self, extra: u32) -> Option<u64> {
+        self.0.write().unwrap().remove(&extra)
+    }
+}
+
+impl Requests for Registry<u64> {
+    fn subscribe(&self, extra: u32) {
+        self.insert(extra, extra as u64);
+    }
+
+    fn notify(&self, extra: u32) -> Option<u64> {
+        self.remove(extra)
+    }
+}
+
+/// Every sender registers its requests and removes them when answered, as
+/// the functions and `receive` do, all at the same time.
+fn run<R: Requests>(requests: &Arc<R>, senders: u32) {
+    let handles: Vec<_> = (0..senders)
+        .map(|sender| {
+            let requests = Arc::clone(requests);
+            thread::spawn(move || {
+                for i in 0..REQUESTS {
+                    let extra = i * senders + sender;
+                    requests.subscribe(extra);
+                    assert!(requests.notify(extra).is_some());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+fn observer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("observer");
+    group.sample_size(10);
+    for senders in [1, 4, 16] {
+        group.bench_with_input(
+            BenchmarkId::new("single_lock", senders),
+            &senders,
+            |b, &n| {
+                let requests = Arc::new(SingleLock::default());
+                b.iter(|| run(&requests, n))
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("sharded", senders), &senders, |b, &n| {
+            let requests = Arc::new(Registry::new());
+            b.iter(|| run(&requests, n))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, observer);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+// cargo bench -p tdlib-rs --bench parsing
+// cargo bench -p tdlib-rs --bench parsing --features simd-json
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::Value;
+use tdlib_rs::enums::Update;
+
+const UPDATE_NEW_MESSAGE: &str = r#"{"@type":"updateNewMessage","message":{"@type":"message","id":1048576,"sender_id":{"@type":"messageSenderUser","user_id":123456789},"chat_id":-1001234567890,"is_outgoing":false,"is_pinned":false,"is_from_offline":false,"can_be_edited":false,"can_be_forwarded":true,"can_be_replied_in_another_chat":true,"can_be_saved":true,"can_be_deleted_only_for_self":true,"can_be_deleted_for_all_users":false,"can_get_added_reactions":false,"can_get_statistics":false,"can_get_message_thread":true,"can_get_read_date":false,"can_get_viewers":false,"can_get_media_timestamp_links":false,"can_report_reactions":false,"has_timestamped_media":true,"is_channel_post":false,"is_topic_message":false,"contains_unread_mention":false,"date":1718000000,"edit_date":0,"unread_reactions":[],"message_thread_id":0,"saved_messages_topic_id":0,"self_destruct_in":0.0,"auto_delete_in":0.0,"via_bot_user_id":0,"sender_business_bot_user_id":0,"sender_boost_count":0,"author_signature":"","media_album_id":"0","restriction_reason":"","content":{"@type":"messageText","text":{"@type":"formattedText","text":"Hello, world! This is a benchmark message with some bold text.","entities":[{"@type":"textEntity","offset":52,"length":4,"type":{"@type":"textEntityTypeBold"}}]}}},"@client_id":1}"#;
+
+const UPDATE_CHAT_READ_INBOX: &str = r#"{"@type":"updateChatReadInbox","chat_id":-1001234567890,"last_read_inbox_message_id":1048576,"unread_count":0,"@client_id":1}"#;
+
+fn parse_via_value(json: &str) -> Update {
+    let value: Value = serde_json::from_str(json).unwrap();
+    serde_json::from_value(value).unwrap()
+}
+
+fn parse_from_str(json: &str) -> Update {
+    serde_json::from_str(json).unwrap()
+}
+
+#[cfg(feature = "simd-json")]
+fn parse_simd_json(json: &str) -> Update {
+    let mut bytes = json.as_bytes().to_vec();
+    simd_json::serde::from_slice(&mut bytes).unwrap()
+}
+
+fn bench_update(c: &mut Criterion, name: &str, json: &str) {
+    let mut group = c.benchmark_group(name);
+    group.bench_function("value_then_from_value", |b| {
+        b.iter(|| parse_via_value(black_box(json)))
+    });
+    group.bench_function("from_str", |b| b.iter(|| parse_from_str(black_box(json))));
+    #[cfg(feature = "simd-json")]
+    group.bench_function("simd_json", |b| b.iter(|| parse_simd_json(black_box(json))));
+    group.finish();
+}
+
+fn parsing(c: &mut Criterion) {
+    bench_update(c, "update_new_message", UPDATE_NEW_MESSAGE);
+    bench_update(c, "update_chat_read_inbox", UPDATE_CHAT_READ_INBOX);
+}
+
+criterion_group!(benches, parsing);
+criterion_main!(benches);
@@ -15,10 +15,10 @@ use tdlib_rs_gen::generate_rust_code;
 use tdlib_rs_parser::parse_tl_file;
 use tdlib_rs_parser::tl::Definition;
 
-#[allow(dead_code)]
-#[cfg(not(any(feature = "docs", feature = "pkg-config")))]
 /// The version of the TDLib library.
 const TDLIB_VERSION: &str = "1.8.29";
+/// The commit of the TDLib library.
+const TDLIB_COMMIT: &str = "af69dd4397b6dc1bf23ba0fd0bf429fcba6454f6";
 
 /// Load the type language definitions from a certain file.
 /// Parse errors will be printed to `stderr`, and only the
@@ -218,6 +218,9 @@ fn main() -> std::io::Result<()> {
     );
 
     println!("cargo:rerun-if-changed=build.rs");
+    // The TDLib the code is generated from, checked by `check_compatibility`
+    println!("cargo:rustc-env=TDLIB_VERSION={}", TDLIB_VERSION);
+    println!("cargo:rustc-env=TDLIB_COMMIT={}", TDLIB_COMMIT);
 
     #[cfg(feature = "local-tdlib")]
     println!("cargo:rerun-if-env-changed=LOCAL_TDLIB_PATH");
@@ -249,7 +252,14 @@ fn main() -> std::io::Result<()> {
 
     let mut file = BufWriter::new(File::create(Path::new(&out_dir).join("generated.rs"))?);
 
-    generate_rust_code(&mut file, &definitions, cfg!(feature = "bots-only-api"))?;
+    generate_rust_code(
+        &mut file,
+        &definitions,
+        cfg!(feature = "bots-only-api"),
+        cfg!(feature = "extra-fields"),
+        cfg!(feature = "typed-ids"),
+        cfg!(feature = "blocking"),
+    )?;
 
     file.flush()?;
 
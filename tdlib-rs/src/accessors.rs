@@ -0,0 +1,171 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Convenience methods on the generated enums, avoiding a `match` on their
+//! variants only to get a field shared by some of them.
+use crate::enums::{ChatType, MessageSender, Update};
+use crate::ids::{ChatId, UserId};
+
+impl MessageSender {
+    /// Returns the identifier of the user, if the sender is a user.
+    pub fn user_id(&self) -> Option<UserId> {
+        match self {
+            MessageSender::User(sender) => Some(sender.user_id),
+            MessageSender::Chat(_) | MessageSender::Unsupported => None,
+        }
+    }
+
+    /// Returns the identifier of the chat, if the sender is a chat.
+    pub fn chat_id(&self) -> Option<ChatId> {
+        match self {
+            MessageSender::User(_) | MessageSender::Unsupported => None,
+            MessageSender::Chat(sender) => Some(sender.chat_id),
+        }
+    }
+
+    /// Returns `true` if the sender is a user.
+    pub fn is_user(&self) -> bool {
+        matches!(self, MessageSender::User(_))
+    }
+
+    /// Returns `true` if the sender is a chat.
+    pub fn is_chat(&self) -> bool {
+        matches!(self, MessageSender::Chat(_))
+    }
+}
+
+#[cfg(feature = "typed-ids")]
+impl From<UserId> for MessageSender {
+    fn from(user_id: UserId) -> Self {
+        MessageSender::User(td_struct!(crate::types::MessageSenderUser { user_id }))
+    }
+}
+
+#[cfg(feature = "typed-ids")]
+impl From<ChatId> for MessageSender {
+    fn from(chat_id: ChatId) -> Self {
+        MessageSender::Chat(td_struct!(crate::types::MessageSenderChat { chat_id }))
+    }
+}
+
+impl ChatType {
+    /// Returns the identifier of the other user, if the chat is a private or
+    /// a secret chat.
+    pub fn user_id(&self) -> Option<UserId> {
+        match self {
+            ChatType::Private(chat) => Some(chat.user_id),
+            ChatType::Secret(chat) => Some(chat.user_id),
+            ChatType::BasicGroup(_) | ChatType::Supergroup(_) | ChatType::Unsupported => None,
+        }
+    }
+
+    /// Returns the identifier of the basic group, if the chat is a basic group.
+    pub fn basic_group_id(&self) -> Option<i64> {
+        match self {
+            ChatType::BasicGroup(chat) => Some(chat.basic_group_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier of the supergroup or channel, if the chat is a
+    /// supergroup or a channel.
+    pub fn supergroup_id(&self) -> Option<i64> {
+        match self {
+            ChatType::Supergroup(chat) => Some(chat.supergroup_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier of the secret chat, if the chat is a secret chat.
+    pub fn secret_chat_id(&self) -> Option<i32> {
+        match self {
+            ChatType::Secret(chat) => Some(chat.secret_chat_id),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the chat is a private or a secret chat.
+    pub fn is_private(&self) -> bool {
+        matches!(self, ChatType::Private(_) | ChatType::Secret(_))
+    }
+
+    /// Returns `true` if the chat is a basic group or a supergroup which is
+    /// not a channel.
+    pub fn is_group(&self) -> bool {
+        match self {
+            ChatType::BasicGroup(_) => true,
+            ChatType::Supergroup(chat) => !chat.is_channel,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the chat is a channel.
+    pub fn is_channel(&self) -> bool {
+        matches!(self, ChatType::Supergroup(chat) if chat.is_channel)
+    }
+}
+
+impl Update {
+    /// Returns the identifier of the chat the update is about, if any.
+    pub fn chat_id(&self) -> Option<ChatId> {
+        match self {
+            Update::NewMessage(update) => Some(update.message.chat_id),
+            Update::MessageSendAcknowledged(update) => Some(update.chat_id),
+            Update::MessageSendSucceeded(update) => Some(update.message.chat_id),
+            Update::MessageSendFailed(update) => Some(update.message.chat_id),
+            Update::MessageContent(update) => Some(update.chat_id),
+            Update::MessageEdited(update) => Some(update.chat_id),
+            Update::MessageIsPinned(update) => Some(update.chat_id),
+            Update::MessageInteractionInfo(update) => Some(update.chat_id),
+            Update::MessageContentOpened(update) => Some(update.chat_id),
+            Update::MessageMentionRead(update) => Some(update.chat_id),
+            Update::MessageUnreadReactions(update) => Some(update.chat_id),
+            Update::MessageLiveLocationViewed(update) => Some(update.chat_id),
+            Update::NewChat(update) => Some(update.chat.id),
+            Update::ChatTitle(update) => Some(update.chat_id),
+            Update::ChatPhoto(update) => Some(update.chat_id),
+            Update::ChatAccentColors(update) => Some(update.chat_id),
+            Update::ChatPermissions(update) => Some(update.chat_id),
+            Update::ChatLastMessage(update) => Some(update.chat_id),
+            Update::ChatPosition(update) => Some(update.chat_id),
+            Update::ChatAddedToList(update) => Some(update.chat_id),
+            Update::ChatRemovedFromList(update) => Some(update.chat_id),
+            Update::ChatReadInbox(update) => Some(update.chat_id),
+            Update::ChatReadOutbox(update) => Some(update.chat_id),
+            Update::ChatActionBar(update) => Some(update.chat_id),
+            Update::ChatBusinessBotManageBar(update) => Some(update.chat_id),
+            Update::ChatAvailableReactions(update) => Some(update.chat_id),
+            Update::ChatDraftMessage(update) => Some(update.chat_id),
+            Update::ChatEmojiStatus(update) => Some(update.chat_id),
+            Update::ChatMessageSender(update) => Some(update.chat_id),
+            Update::ChatMessageAutoDeleteTime(update) => Some(update.chat_id),
+            Update::ChatNotificationSettings(update) => Some(update.chat_id),
+            Update::ChatPendingJoinRequests(update) => Some(update.chat_id),
+            Update::ChatReplyMarkup(update) => Some(update.chat_id),
+            Update::ChatBackground(update) => Some(update.chat_id),
+            Update::ChatTheme(update) => Some(update.chat_id),
+            Update::ChatUnreadMentionCount(update) => Some(update.chat_id),
+            Update::ChatUnreadReactionCount(update) => Some(update.chat_id),
+            Update::ChatVideoChat(update) => Some(update.chat_id),
+            Update::ChatDefaultDisableNotification(update) => Some(update.chat_id),
+            Update::ChatHasProtectedContent(update) => Some(update.chat_id),
+            Update::ChatIsTranslatable(update) => Some(update.chat_id),
+            Update::ChatIsMarkedAsUnread(update) => Some(update.chat_id),
+            Update::ChatViewAsTopics(update) => Some(update.chat_id),
+            Update::ChatBlockList(update) => Some(update.chat_id),
+            Update::ChatHasScheduledMessages(update) => Some(update.chat_id),
+            Update::ChatOnlineMemberCount(update) => Some(update.chat_id),
+            Update::ForumTopicInfo(update) => Some(update.chat_id),
+            Update::NotificationGroup(update) => Some(update.chat_id),
+            Update::DeleteMessages(update) => Some(update.chat_id),
+            Update::ChatAction(update) => Some(update.chat_id),
+            Update::AnimatedEmojiMessageClicked(update) => Some(update.chat_id),
+            _ => None,
+        }
+    }
+}
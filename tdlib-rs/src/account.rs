@@ -0,0 +1,173 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The settings of the account of the user, once logged in: its phone
+//! number, its login email address, and when it is deleted.
+//!
+//! Changing the phone number or the login email address is a flow of two
+//! steps, sending a code then checking the code received by the user, which
+//! can be sent again meanwhile.
+//!
+//! Deleting the account needs a [`DeletionConfirmation`], which is only
+//! given for the phone number of the account.
+use crate::enums::{self, EmailAddressAuthentication, PhoneNumberCodeType};
+use crate::ids::UserId;
+use crate::{functions, identity, types, TdError};
+use std::ops::RangeInclusive;
+
+/// The number of days of inactivity after which an account can be deleted.
+pub const ACCOUNT_TTL_DAYS: RangeInclusive<i32> = 30..=366;
+
+/// Send a code to the new phone number of the account, to check with
+/// [`check_phone_number_code`].
+pub async fn change_phone_number(
+    phone_number: String,
+    settings: Option<types::PhoneNumberAuthenticationSettings>,
+    client_id: i32,
+) -> Result<types::AuthenticationCodeInfo, TdError> {
+    let enums::AuthenticationCodeInfo::AuthenticationCodeInfo(info) =
+        functions::send_phone_number_code(
+            phone_number,
+            settings,
+            PhoneNumberCodeType::Change,
+            client_id,
+        )
+        .await?;
+    Ok(info)
+}
+
+/// Send the code to the new phone number again, with the next type of code.
+pub async fn resend_phone_number_code(
+    client_id: i32,
+) -> Result<types::AuthenticationCodeInfo, TdError> {
+    let enums::AuthenticationCodeInfo::AuthenticationCodeInfo(info) =
+        functions::resend_phone_number_code(client_id).await?;
+    Ok(info)
+}
+
+/// Check the code sent to the new phone number, which then replaces the
+/// current one.
+pub async fn check_phone_number_code(code: String, client_id: i32) -> Result<(), TdError> {
+    functions::check_phone_number_code(code, client_id).await?;
+    Ok(())
+}
+
+/// Returns the pattern of the login email address of the account, such as
+/// `a***@example.com`, if there is one.
+pub async fn login_email_address_pattern(client_id: i32) -> Result<Option<String>, TdError> {
+    let enums::PasswordState::PasswordState(state) =
+        functions::get_password_state(client_id).await?;
+    Ok(Some(state.login_email_address_pattern).filter(|pattern| !pattern.is_empty()))
+}
+
+/// Send a code to the new login email address of the account, to check with
+/// [`check_login_email_address_code`].
+pub async fn set_login_email_address(
+    new_login_email_address: String,
+    client_id: i32,
+) -> Result<types::EmailAddressAuthenticationCodeInfo, TdError> {
+    let enums::EmailAddressAuthenticationCodeInfo::EmailAddressAuthenticationCodeInfo(info) =
+        functions::set_login_email_address(new_login_email_address, client_id).await?;
+    Ok(info)
+}
+
+/// Send the code to the new login email address again.
+pub async fn resend_login_email_address_code(
+    client_id: i32,
+) -> Result<types::EmailAddressAuthenticationCodeInfo, TdError> {
+    let enums::EmailAddressAuthenticationCodeInfo::EmailAddressAuthenticationCodeInfo(info) =
+        functions::resend_login_email_address_code(client_id).await?;
+    Ok(info)
+}
+
+/// Returns the authentication with the code received by email, as
+/// [`EmailAddressAuthentication::Code`].
+pub fn email_code(code: String) -> EmailAddressAuthentication {
+    EmailAddressAuthentication::Code(td_struct!(types::EmailAddressAuthenticationCode { code }))
+}
+
+/// Check the code sent to the new login email address, or the token of an
+/// Apple or Google account with the same address, which then replaces the
+/// current one.
+pub async fn check_login_email_address_code(
+    code: EmailAddressAuthentication,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::check_login_email_address_code(code, client_id).await?;
+    Ok(())
+}
+
+/// Returns the number of days of inactivity after which the account is
+/// deleted.
+pub async fn account_ttl(client_id: i32) -> Result<i32, TdError> {
+    let enums::AccountTtl::AccountTtl(ttl) = functions::get_account_ttl(client_id).await?;
+    Ok(ttl.days)
+}
+
+/// Change the number of days of inactivity after which the account is
+/// deleted, failing without a request unless in [`ACCOUNT_TTL_DAYS`].
+pub async fn set_account_ttl(days: i32, client_id: i32) -> Result<(), TdError> {
+    if !ACCOUNT_TTL_DAYS.contains(&days) {
+        return Err(TdError::invalid(
+            400,
+            "The account TTL must be from 30 to 366 days",
+        ));
+    }
+    functions::set_account_ttl(td_struct!(types::AccountTtl { days }), client_id).await?;
+    Ok(())
+}
+
+/// The confirmation that the account of a user must be deleted, given by
+/// [`confirm_deletion`].
+#[derive(Debug)]
+pub struct DeletionConfirmation {
+    user_id: UserId,
+}
+
+/// Returns the confirmation to delete the account, if the phone number,
+/// typically typed by the user, is the one of the account. Only the digits
+/// of the phone number are compared.
+pub async fn confirm_deletion(
+    phone_number: &str,
+    client_id: i32,
+) -> Result<DeletionConfirmation, TdError> {
+    let me = identity::me(client_id).await?;
+    let digits = |phone_number: &str| {
+        phone_number
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+    };
+    if me.phone_number.is_empty() || digits(phone_number) != digits(&me.phone_number) {
+        return Err(TdError::invalid(
+            400,
+            "The phone number is not the one of the account",
+        ));
+    }
+    Ok(DeletionConfirmation { user_id: me.id })
+}
+
+/// Delete the account confirmed for deletion, with all its data, for the
+/// reason if not empty. The 2-step verification password is needed if set.
+/// Fails without a request if the confirmation was given for the account of
+/// another client.
+pub async fn delete_account(
+    confirmation: DeletionConfirmation,
+    reason: String,
+    password: String,
+    client_id: i32,
+) -> Result<(), TdError> {
+    if identity::my_id(client_id).await? != confirmation.user_id {
+        return Err(TdError::invalid(
+            400,
+            "The deletion was confirmed for another account",
+        ));
+    }
+    functions::delete_account(reason, password, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The actions of the bar shown above some chats, such as the "Report spam"
+//! and "Add contact" banner of the chats with unknown users.
+//!
+//! Every action removes the bar of the chat once done, as the clients are
+//! expected to.
+use crate::enums::{self, BlockList, ChatActionBar, MessageSender, ReportReason};
+use crate::ids::{ChatId, UserId};
+use crate::{functions, types, TdError};
+
+/// Returns the action bar of the chat, if any.
+pub async fn action_bar(chat_id: ChatId, client_id: i32) -> Result<Option<ChatActionBar>, TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    Ok(chat.action_bar)
+}
+
+/// Returns the other user of a private or secret chat.
+async fn user_id(chat_id: ChatId, client_id: i32) -> Result<UserId, TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    chat.r#type
+        .user_id()
+        .ok_or_else(|| TdError::invalid(400, "Chat is not a private or secret chat"))
+}
+
+/// Remove the action bar of the chat without any other action.
+pub async fn dismiss(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    functions::remove_chat_action_bar(chat_id, client_id).await?;
+    Ok(())
+}
+
+async fn report(chat_id: ChatId, reason: ReportReason, client_id: i32) -> Result<(), TdError> {
+    functions::report_chat(chat_id, Vec::new(), reason, String::new(), client_id).await?;
+    dismiss(chat_id, client_id).await
+}
+
+/// Report the chat as spam, for [`ChatActionBar::ReportSpam`] and
+/// [`ChatActionBar::ReportAddBlock`].
+pub async fn report_spam(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    report(chat_id, ReportReason::Spam, client_id).await
+}
+
+/// Report the location of the supergroup as unrelated, for
+/// [`ChatActionBar::ReportUnrelatedLocation`].
+pub async fn report_unrelated_location(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    report(chat_id, ReportReason::UnrelatedLocation, client_id).await
+}
+
+/// Block the other user of the private or secret chat, for
+/// [`ChatActionBar::ReportAddBlock`].
+pub async fn block(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    let user_id = user_id(chat_id, client_id).await?;
+    let sender = MessageSender::User(td_struct!(types::MessageSenderUser { user_id }));
+    functions::set_message_sender_block_list(sender, Some(BlockList::Main), client_id).await?;
+    dismiss(chat_id, client_id).await
+}
+
+/// Add the other user of the private or secret chat to the contacts, with
+/// the name of their profile, for [`ChatActionBar::AddContact`] and
+/// [`ChatActionBar::ReportAddBlock`].
+pub async fn add_contact(
+    chat_id: ChatId,
+    share_phone_number: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let user_id = user_id(chat_id, client_id).await?;
+    let enums::User::User(user) = functions::get_user(user_id, client_id).await?;
+    let contact = td_struct!(types::Contact {
+        phone_number: user.phone_number,
+        first_name: user.first_name,
+        last_name: user.last_name,
+        vcard: String::new(),
+        user_id
+    });
+    functions::add_contact(contact, share_phone_number, client_id).await?;
+    dismiss(chat_id, client_id).await
+}
+
+/// Share the phone number of the user with the other user of the private
+/// chat, for [`ChatActionBar::SharePhoneNumber`].
+pub async fn share_phone_number(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    let user_id = user_id(chat_id, client_id).await?;
+    functions::share_phone_number(user_id, client_id).await?;
+    dismiss(chat_id, client_id).await
+}
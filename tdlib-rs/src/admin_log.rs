@@ -0,0 +1,149 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The events of the last 48 hours taken by the members and administrators
+//! of the supergroups and channels, paged from the latest like
+//! `admin_log(chat_id).filter(EventKind::MemberJoins).next(client_id)`.
+use crate::enums;
+use crate::ids::{ChatId, UserId};
+use crate::{functions, types, TdError};
+
+/// The maximum number of events returned by `getChatEventLog`.
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+/// A kind of events of the admin log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// The edits of messages.
+    MessageEdits,
+    /// The deletions of messages.
+    MessageDeletions,
+    /// The pins and unpins of messages.
+    MessagePins,
+    /// The members joining the chat.
+    MemberJoins,
+    /// The members leaving the chat.
+    MemberLeaves,
+    /// The members invited to the chat.
+    MemberInvites,
+    /// The changes of the rights of the administrators.
+    MemberPromotions,
+    /// The changes of the permissions of the members.
+    MemberRestrictions,
+    /// The changes of the title, photo, description and the like.
+    InfoChanges,
+    /// The changes of the settings of the chat.
+    SettingChanges,
+    /// The changes of the invite links.
+    InviteLinkChanges,
+    /// The changes of the video chats.
+    VideoChatChanges,
+    /// The changes of the forum topics.
+    ForumChanges,
+}
+
+/// The pages of the events of an admin log, started by [`admin_log`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdminLog {
+    chat_id: ChatId,
+    query: String,
+    filters: Option<types::ChatEventLogFilters>,
+    user_ids: Vec<UserId>,
+    from_event_id: Option<i64>,
+    page_size: i32,
+}
+
+/// Page through the events of the admin log of the chat, from the latest,
+/// by pages of at most [`MAX_PAGE_SIZE`]; the administrator rights are
+/// needed.
+pub fn admin_log(chat_id: ChatId) -> AdminLog {
+    AdminLog {
+        chat_id,
+        query: String::new(),
+        filters: None,
+        user_ids: Vec::new(),
+        from_event_id: Some(0),
+        page_size: MAX_PAGE_SIZE,
+    }
+}
+
+impl AdminLog {
+    /// Only the events of the kind, along with the other kinds filtered;
+    /// every kind of events is returned without filter.
+    pub fn filter(mut self, kind: EventKind) -> Self {
+        let filters = self.filters.get_or_insert_with(Default::default);
+        let filter = match kind {
+            EventKind::MessageEdits => &mut filters.message_edits,
+            EventKind::MessageDeletions => &mut filters.message_deletions,
+            EventKind::MessagePins => &mut filters.message_pins,
+            EventKind::MemberJoins => &mut filters.member_joins,
+            EventKind::MemberLeaves => &mut filters.member_leaves,
+            EventKind::MemberInvites => &mut filters.member_invites,
+            EventKind::MemberPromotions => &mut filters.member_promotions,
+            EventKind::MemberRestrictions => &mut filters.member_restrictions,
+            EventKind::InfoChanges => &mut filters.info_changes,
+            EventKind::SettingChanges => &mut filters.setting_changes,
+            EventKind::InviteLinkChanges => &mut filters.invite_link_changes,
+            EventKind::VideoChatChanges => &mut filters.video_chat_changes,
+            EventKind::ForumChanges => &mut filters.forum_changes,
+        };
+        *filter = true;
+        self
+    }
+
+    /// Only the events matching the search query.
+    pub fn query(mut self, query: impl Into<String>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Only the events related to the user, along with the other users
+    /// filtered.
+    pub fn user(mut self, user_id: UserId) -> Self {
+        self.user_ids.push(user_id);
+        self
+    }
+
+    /// Request pages of at most `page_size` events, up to [`MAX_PAGE_SIZE`].
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// Returns the next page of events, older than the previous ones, or
+    /// `None` after the last one.
+    pub async fn next(&mut self, client_id: i32) -> Result<Option<Vec<types::ChatEvent>>, TdError> {
+        let Some(from_event_id) = self.from_event_id.take() else {
+            return Ok(None);
+        };
+        let enums::ChatEvents::ChatEvents(events) = functions::get_chat_event_log(
+            self.chat_id,
+            self.query.clone(),
+            from_event_id,
+            self.page_size,
+            self.filters.clone(),
+            self.user_ids.clone(),
+            client_id,
+        )
+        .await?;
+        let Some(last) = events.events.last() else {
+            return Ok(None);
+        };
+        self.from_event_id = Some(last.id);
+        Ok(Some(events.events))
+    }
+
+    /// Returns the remaining events of the admin log.
+    pub async fn all(mut self, client_id: i32) -> Result<Vec<types::ChatEvent>, TdError> {
+        let mut all = Vec::new();
+        while let Some(events) = self.next(client_id).await? {
+            all.extend(events);
+        }
+        Ok(all)
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The archive chat list of each client, as tracked by the [`chat_lists`]
+//! module, and helpers to archive and unarchive chats and to change what is
+//! archived automatically.
+//!
+//! The archived chats are sent by TdLib once loaded with [`load_chats`], the
+//! main list being loaded separately.
+use crate::chat_lists;
+use crate::enums::{self, ChatList};
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+
+/// Returns the archived chats sent by TdLib, in the order in which they are
+/// shown.
+pub fn archived_chats(client_id: i32) -> Vec<ChatId> {
+    chat_lists::chats(&ChatList::Archive, client_id)
+}
+
+/// Returns `true` if TdLib sent the chat as archived.
+pub fn is_archived(chat_id: ChatId, client_id: i32) -> bool {
+    archived_chats(client_id).contains(&chat_id)
+}
+
+/// Ask TdLib to send at most `limit` more archived chats, tracked by
+/// [`archived_chats`]. Returns `false` once every archived chat was sent.
+pub async fn load_chats(limit: i32, client_id: i32) -> Result<bool, TdError> {
+    match functions::load_chats(Some(ChatList::Archive), limit, client_id).await {
+        Ok(()) => Ok(true),
+        Err(e) if e.code == 404 => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Move the chat to the archive.
+pub async fn archive(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    functions::add_chat_to_list(chat_id, ChatList::Archive, client_id).await?;
+    Ok(())
+}
+
+/// Move the chat back to the main chat list.
+pub async fn unarchive(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    functions::add_chat_to_list(chat_id, ChatList::Main, client_id).await?;
+    Ok(())
+}
+
+/// Returns what is archived automatically.
+pub async fn settings(client_id: i32) -> Result<types::ArchiveChatListSettings, TdError> {
+    let enums::ArchiveChatListSettings::ArchiveChatListSettings(settings) =
+        functions::get_archive_chat_list_settings(client_id).await?;
+    Ok(settings)
+}
+
+/// Change what is archived automatically.
+pub async fn set_settings(
+    settings: types::ArchiveChatListSettings,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::set_archive_chat_list_settings(settings, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The time after which the messages are deleted automatically, in the chats
+//! and by default in the new chats, as durations.
+//!
+//! Apart from the secret chats, TdLib only accepts whole days, up to
+//! [`MAX_AUTO_DELETE_TIME`]: the other durations fail without a request, and
+//! can be rounded first with [`round`].
+use crate::enums;
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+use std::time::Duration;
+
+const DAY: u64 = 86400;
+
+/// The longest time after which the messages can be deleted automatically.
+pub const MAX_AUTO_DELETE_TIME: Duration = Duration::from_secs(365 * DAY);
+
+fn from_seconds(seconds: i32) -> Option<Duration> {
+    u64::try_from(seconds)
+        .ok()
+        .filter(|&seconds| seconds > 0)
+        .map(Duration::from_secs)
+}
+
+/// Returns the seconds of the time for TdLib, 0 if `None`, failing unless
+/// whole days up to a year or any time in a secret chat.
+fn to_seconds(time: Option<Duration>, is_secret: bool) -> Result<i32, TdError> {
+    let Some(time) = time else {
+        return Ok(0);
+    };
+    let seconds = time.as_secs();
+    let allowed = if is_secret {
+        seconds > 0 && time.subsec_nanos() == 0
+    } else {
+        seconds > 0 && seconds % DAY == 0 && time <= MAX_AUTO_DELETE_TIME
+    };
+    let expected = if is_secret {
+        "a whole number of seconds"
+    } else {
+        "a whole number of days up to 365 days"
+    };
+    match i32::try_from(seconds) {
+        Ok(seconds) if allowed => Ok(seconds),
+        _ => Err(TdError::invalid(
+            400,
+            format!("Unsupported auto-delete time of {time:?}, it must be {expected}"),
+        )),
+    }
+}
+
+/// Returns the time rounded to the nearest whole number of days accepted
+/// for the chats other than the secret chats, at least a day.
+pub fn round(time: Duration) -> Duration {
+    let days = time.as_secs().saturating_add(DAY / 2) / DAY;
+    Duration::from_secs(days.clamp(1, 365) * DAY)
+}
+
+/// Returns the time after which the messages of the chat are deleted, or
+/// `None` if they are kept.
+pub async fn chat_auto_delete(
+    chat_id: ChatId,
+    client_id: i32,
+) -> Result<Option<Duration>, TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    Ok(from_seconds(chat.message_auto_delete_time))
+}
+
+/// Change the time after which the messages of the chat are deleted, or
+/// keep them if `None`.
+pub async fn set_chat_auto_delete(
+    chat_id: ChatId,
+    time: Option<Duration>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    let seconds = to_seconds(time, chat.r#type.secret_chat_id().is_some())?;
+    functions::set_chat_message_auto_delete_time(chat_id, seconds, client_id).await?;
+    Ok(())
+}
+
+/// Returns the time after which the messages of the new chats are deleted,
+/// or `None` if they are kept.
+pub async fn default_auto_delete(client_id: i32) -> Result<Option<Duration>, TdError> {
+    let enums::MessageAutoDeleteTime::MessageAutoDeleteTime(time) =
+        functions::get_default_message_auto_delete_time(client_id).await?;
+    Ok(from_seconds(time.time))
+}
+
+/// Change the time after which the messages of the new chats are deleted,
+/// or keep them if `None`.
+pub async fn set_default_auto_delete(
+    time: Option<Duration>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let time = to_seconds(time, false)?;
+    functions::set_default_message_auto_delete_time(
+        td_struct!(types::MessageAutoDeleteTime { time }),
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
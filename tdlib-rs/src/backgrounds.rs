@@ -0,0 +1,193 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The backgrounds and the themes of the chats.
+//!
+//! The backgrounds are built from their fill, type and input, then set like
+//! `chat_background(chat_id).background(local(path)).only_for_self().set(client_id)`.
+//! The chat themes are tracked from `updateChatThemes`, as TdLib sends them
+//! without request.
+use crate::enums::{BackgroundFill, BackgroundType, InputBackground, InputFile, Update};
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+static CHAT_THEMES: Lazy<RwLock<HashMap<i32, Vec<types::ChatTheme>>>> = Lazy::new(RwLock::default);
+
+/// Keep the chat themes of the client up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::ChatThemes(update) = update {
+        CHAT_THEMES
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(client_id, update.chat_themes.clone());
+    }
+}
+
+/// Returns the chat themes which can be set, empty until TdLib sends them.
+pub fn chat_themes(client_id: i32) -> Vec<types::ChatTheme> {
+    CHAT_THEMES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Returns a fill of a single color, in the RGB24 format.
+pub fn solid(color: i32) -> BackgroundFill {
+    BackgroundFill::Solid(td_struct!(types::BackgroundFillSolid { color }))
+}
+
+/// Returns a gradient fill from the top color to the bottom one, rotated
+/// clockwise by the angle in degrees, rounded down to a multiple of 45.
+pub fn gradient(top_color: i32, bottom_color: i32, rotation_angle: i32) -> BackgroundFill {
+    BackgroundFill::Gradient(td_struct!(types::BackgroundFillGradient {
+        top_color,
+        bottom_color,
+        rotation_angle: rotation_angle.rem_euclid(360) / 45 * 45
+    }))
+}
+
+/// Returns a freeform gradient fill of 3 or 4 colors.
+pub fn freeform_gradient(colors: Vec<i32>) -> BackgroundFill {
+    BackgroundFill::FreeformGradient(td_struct!(types::BackgroundFillFreeformGradient { colors }))
+}
+
+/// Returns the type of a background filled without image.
+pub fn fill(fill: BackgroundFill) -> BackgroundType {
+    BackgroundType::Fill(td_struct!(types::BackgroundTypeFill { fill }))
+}
+
+/// Returns the type of a JPEG wallpaper, blurred or moving with the device
+/// if asked.
+pub fn wallpaper(is_blurred: bool, is_moving: bool) -> BackgroundType {
+    BackgroundType::Wallpaper(td_struct!(types::BackgroundTypeWallpaper {
+        is_blurred,
+        is_moving
+    }))
+}
+
+/// Returns the type of a PNG pattern shown above the fill, with an intensity
+/// from 0 to 100.
+pub fn pattern(
+    fill: BackgroundFill,
+    intensity: i32,
+    is_inverted: bool,
+    is_moving: bool,
+) -> BackgroundType {
+    BackgroundType::Pattern(td_struct!(types::BackgroundTypePattern {
+        fill,
+        intensity: intensity.clamp(0, 100),
+        is_inverted,
+        is_moving
+    }))
+}
+
+/// Returns the background of the local file: JPEG for the wallpapers, PNG
+/// for the patterns.
+pub fn local(path: impl Into<String>) -> InputBackground {
+    InputBackground::Local(td_struct!(types::InputBackgroundLocal {
+        background: InputFile::Local(td_struct!(types::InputFileLocal { path: path.into() }))
+    }))
+}
+
+/// Returns a background of the server.
+pub fn remote(background_id: i64) -> InputBackground {
+    InputBackground::Remote(td_struct!(types::InputBackgroundRemote { background_id }))
+}
+
+/// Returns the background previously set in the chat by the message.
+pub fn previous(message_id: MessageId) -> InputBackground {
+    InputBackground::Previous(td_struct!(types::InputBackgroundPrevious { message_id }))
+}
+
+/// The background to set in a chat, started by [`chat_background`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatBackground {
+    chat_id: ChatId,
+    background: Option<InputBackground>,
+    r#type: Option<BackgroundType>,
+    dark_theme_dimming: i32,
+    only_for_self: bool,
+}
+
+/// Set the background of the chat, a new one of the default type unless
+/// given.
+pub fn chat_background(chat_id: ChatId) -> ChatBackground {
+    ChatBackground {
+        chat_id,
+        background: None,
+        r#type: None,
+        dark_theme_dimming: 0,
+        only_for_self: false,
+    }
+}
+
+impl ChatBackground {
+    /// Use the background, instead of a new filled one.
+    pub fn background(mut self, background: InputBackground) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Use the type of background, instead of the default one.
+    pub fn background_type(mut self, r#type: BackgroundType) -> Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    /// Dim the wallpapers and the fills in the dark themes, as a percentage.
+    pub fn dark_theme_dimming(mut self, dark_theme_dimming: i32) -> Self {
+        self.dark_theme_dimming = dark_theme_dimming.clamp(0, 100);
+        self
+    }
+
+    /// Set the background only for the user instead of every member.
+    pub fn only_for_self(mut self) -> Self {
+        self.only_for_self = true;
+        self
+    }
+
+    /// Set the background.
+    pub async fn set(self, client_id: i32) -> Result<(), TdError> {
+        functions::set_chat_background(
+            self.chat_id,
+            self.background,
+            self.r#type,
+            self.dark_theme_dimming,
+            self.only_for_self,
+            client_id,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Delete the background of the chat, restoring the previous one if asked.
+pub async fn delete_chat_background(
+    chat_id: ChatId,
+    restore_previous: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::delete_chat_background(chat_id, restore_previous, client_id).await?;
+    Ok(())
+}
+
+/// Set the theme of the private or secret chat, one of [`chat_themes`], or
+/// restore the default theme if `None`.
+pub async fn set_chat_theme(
+    chat_id: ChatId,
+    theme_name: Option<String>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::set_chat_theme(chat_id, theme_name.unwrap_or_default(), client_id).await?;
+    Ok(())
+}
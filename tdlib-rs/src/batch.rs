@@ -0,0 +1,164 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Send several requests at once and wait for all their responses.
+//!
+//! Every function sends its request with its own `@extra` as soon as it is
+//! first polled, so the requests of a batch are all sent before any response
+//! is awaited, and the batch takes as long as its slowest request.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::{batch, functions};
+//!
+//! # async fn startup(client_id: i32) {
+//! let (me, chats, version) = batch::join3(
+//!     functions::get_me(client_id),
+//!     functions::get_chats(None, 20, client_id),
+//!     functions::get_option("version".into(), client_id),
+//! )
+//! .await;
+//! # }
+//! ```
+use std::future::{poll_fn, Future};
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future which keeps its output once it has completed.
+enum MaybeDone<F: Future> {
+    Future(Pin<Box<F>>),
+    Done(F::Output),
+    Gone,
+}
+
+impl<F: Future> MaybeDone<F> {
+    fn new(future: F) -> Self {
+        MaybeDone::Future(Box::pin(future))
+    }
+
+    /// Polls the future, returning `true` once it has completed.
+    fn poll(&mut self, cx: &mut Context<'_>) -> bool {
+        match self {
+            MaybeDone::Future(future) => match future.as_mut().poll(cx) {
+                Poll::Ready(output) => {
+                    *self = MaybeDone::Done(output);
+                    true
+                }
+                Poll::Pending => false,
+            },
+            MaybeDone::Done(_) => true,
+            MaybeDone::Gone => panic!("the output of the future was already taken"),
+        }
+    }
+
+    fn take(&mut self) -> F::Output {
+        match mem::replace(self, MaybeDone::Gone) {
+            MaybeDone::Done(output) => output,
+            _ => panic!("the future has not completed"),
+        }
+    }
+}
+
+/// A batch of requests with the same type of result, which are sent
+/// together by [`Batch::run`].
+pub struct Batch<T> {
+    futures: Vec<Pin<Box<dyn Future<Output = T> + Send>>>,
+}
+
+impl<T> Batch<T> {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self {
+            futures: Vec::new(),
+        }
+    }
+
+    /// Add a request to the batch, usually the future returned by a function
+    /// of the `functions` module; it is not sent until the batch is run.
+    pub fn push<F>(&mut self, future: F)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.futures.push(Box::pin(future));
+    }
+
+    /// Returns the number of requests of the batch.
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+
+    /// Returns `true` if the batch has no requests.
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+
+    /// Send all the requests of the batch, returning their results in the
+    /// order in which the requests were added.
+    pub async fn run(self) -> Vec<T> {
+        let mut futures: Vec<_> = self.futures.into_iter().map(MaybeDone::new).collect();
+        poll_fn(|cx| {
+            let mut done = true;
+            for future in futures.iter_mut() {
+                done &= future.poll(cx);
+            }
+            if done {
+                Poll::Ready(futures.iter_mut().map(MaybeDone::take).collect())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<T> Default for Batch<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Defines a function sending the requests of all the futures together and
+/// returning the tuple of their results.
+macro_rules! join {
+    ($(#[$doc:meta])* $name:ident($($future:ident: $ty:ident),+)) => {
+        $(#[$doc])*
+        pub async fn $name<$($ty: Future),+>($($future: $ty),+) -> ($($ty::Output),+) {
+            $(let mut $future = MaybeDone::new($future);)+
+            poll_fn(|cx| {
+                let mut done = true;
+                $(done &= $future.poll(cx);)+
+                if done {
+                    Poll::Ready(($($future.take()),+))
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await
+        }
+    };
+}
+
+join!(
+    /// Send the requests of both futures together, returning their results.
+    join2(a: A, b: B)
+);
+
+join!(
+    /// Send the requests of the three futures together, returning their results.
+    join3(a: A, b: B, c: C)
+);
+
+join!(
+    /// Send the requests of the four futures together, returning their results.
+    join4(a: A, b: B, c: C, d: D)
+);
+
+join!(
+    /// Send the requests of the five futures together, returning their results.
+    join5(a: A, b: B, c: C, d: D, e: E)
+);
@@ -0,0 +1,105 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Block and unblock the users and the chats, and page through the block
+//! lists.
+//!
+//! A sender is in at most one block list: the main one stops them from
+//! writing to the user, while the one of the stories only hides the stories
+//! of the user from them.
+use crate::enums::{self, BlockList, MessageSender};
+use crate::{functions, TdError};
+
+/// The maximum number of senders returned by `getBlockedMessageSenders`.
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+/// Add the sender to the main block list.
+pub async fn block(sender: MessageSender, client_id: i32) -> Result<(), TdError> {
+    functions::set_message_sender_block_list(sender, Some(BlockList::Main), client_id).await?;
+    Ok(())
+}
+
+/// Add the sender to the block list of the stories.
+pub async fn block_from_stories(sender: MessageSender, client_id: i32) -> Result<(), TdError> {
+    functions::set_message_sender_block_list(sender, Some(BlockList::Stories), client_id).await?;
+    Ok(())
+}
+
+/// Remove the sender from its block list.
+pub async fn unblock(sender: MessageSender, client_id: i32) -> Result<(), TdError> {
+    functions::set_message_sender_block_list(sender, None, client_id).await?;
+    Ok(())
+}
+
+/// The pages of a block list, started by [`blocked`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blocked {
+    block_list: BlockList,
+    offset: i32,
+    page_size: i32,
+    total_count: Option<i32>,
+}
+
+/// Page through the senders of the block list, by pages of
+/// [`MAX_PAGE_SIZE`].
+pub fn blocked(block_list: BlockList) -> Blocked {
+    Blocked {
+        block_list,
+        offset: 0,
+        page_size: MAX_PAGE_SIZE,
+        total_count: None,
+    }
+}
+
+impl Blocked {
+    /// Request pages of at most `page_size` senders, up to
+    /// [`MAX_PAGE_SIZE`].
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// Returns the number of senders in the block list, once a page was
+    /// requested.
+    pub fn total_count(&self) -> Option<i32> {
+        self.total_count
+    }
+
+    /// Returns the next page of senders, or `None` after the last one.
+    pub async fn next(&mut self, client_id: i32) -> Result<Option<Vec<MessageSender>>, TdError> {
+        if self
+            .total_count
+            .is_some_and(|total_count| self.offset >= total_count)
+        {
+            return Ok(None);
+        }
+        let enums::MessageSenders::MessageSenders(senders) =
+            functions::get_blocked_message_senders(
+                self.block_list.clone(),
+                self.offset,
+                self.page_size,
+                client_id,
+            )
+            .await?;
+        self.total_count = Some(senders.total_count);
+        if senders.senders.is_empty() {
+            return Ok(None);
+        }
+        self.offset += senders.senders.len() as i32;
+        Ok(Some(senders.senders))
+    }
+
+    /// Returns the remaining senders of the block list.
+    pub async fn all(mut self, client_id: i32) -> Result<Vec<MessageSender>, TdError> {
+        let mut all = Vec::new();
+        while let Some(senders) = self.next(client_id).await? {
+            all.extend(senders);
+        }
+        Ok(all)
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The boosts of the supergroups and channels: their status and links, the
+//! boosts applied to them, and the boost slots of the user to apply.
+use crate::enums;
+use crate::ids::ChatId;
+#[cfg(feature = "bots-only-api")]
+use crate::ids::UserId;
+use crate::{functions, types, TdError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The maximum number of boosts returned by `getChatBoosts`.
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+/// Returns the boost status of the chat.
+pub async fn status(chat_id: ChatId, client_id: i32) -> Result<types::ChatBoostStatus, TdError> {
+    let enums::ChatBoostStatus::ChatBoostStatus(status) =
+        functions::get_chat_boost_status(chat_id, client_id).await?;
+    Ok(status)
+}
+
+/// Returns the link to boost the chat.
+pub async fn link(chat_id: ChatId, client_id: i32) -> Result<types::ChatBoostLink, TdError> {
+    let enums::ChatBoostLink::ChatBoostLink(link) =
+        functions::get_chat_boost_link(chat_id, client_id).await?;
+    Ok(link)
+}
+
+/// Returns the chat boosted by the link, and whether the link is public.
+pub async fn link_info(url: String, client_id: i32) -> Result<types::ChatBoostLinkInfo, TdError> {
+    let enums::ChatBoostLinkInfo::ChatBoostLinkInfo(info) =
+        functions::get_chat_boost_link_info(url, client_id).await?;
+    Ok(info)
+}
+
+/// Returns the boost slots of the user.
+pub async fn slots(client_id: i32) -> Result<Vec<types::ChatBoostSlot>, TdError> {
+    let enums::ChatBoostSlots::ChatBoostSlots(slots) =
+        functions::get_available_chat_boost_slots(client_id).await?;
+    Ok(slots.slots)
+}
+
+/// Boost the chat from the slots, returning the boost slots of the user
+/// after the boost.
+pub async fn boost(
+    chat_id: ChatId,
+    slot_ids: Vec<i32>,
+    client_id: i32,
+) -> Result<Vec<types::ChatBoostSlot>, TdError> {
+    let enums::ChatBoostSlots::ChatBoostSlots(slots) =
+        functions::boost_chat(chat_id, slot_ids, client_id).await?;
+    Ok(slots.slots)
+}
+
+/// Boost the chat from the slots of the user which boost no chat, returning
+/// the boost slots of the user after the boost. The slots boosting another
+/// chat are left as they are, see [`boost_moving_slots`] to move them too.
+/// Nothing is requested if there is no unused slot.
+pub async fn boost_with_available_slots(
+    chat_id: ChatId,
+    client_id: i32,
+) -> Result<Vec<types::ChatBoostSlot>, TdError> {
+    boost_from_slots(chat_id, false, client_id).await
+}
+
+/// Boost the chat from the slots of the user which boost no chat, and from
+/// those which boost another chat and can be moved, taking their boosts
+/// away from these chats. Returns the boost slots of the user after the
+/// boost; nothing is requested if there is no such slot.
+pub async fn boost_moving_slots(
+    chat_id: ChatId,
+    client_id: i32,
+) -> Result<Vec<types::ChatBoostSlot>, TdError> {
+    boost_from_slots(chat_id, true, client_id).await
+}
+
+async fn boost_from_slots(
+    chat_id: ChatId,
+    move_boosts: bool,
+    client_id: i32,
+) -> Result<Vec<types::ChatBoostSlot>, TdError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let slots = slots(client_id).await?;
+    let slot_ids = slots
+        .iter()
+        .filter(|slot| {
+            slot.currently_boosted_chat_id == ChatId::default()
+                || (move_boosts
+                    && slot.currently_boosted_chat_id != chat_id
+                    && i64::from(slot.cooldown_until_date) <= now)
+        })
+        .map(|slot| slot.slot_id)
+        .collect::<Vec<_>>();
+    if slot_ids.is_empty() {
+        return Ok(slots);
+    }
+    boost(chat_id, slot_ids, client_id).await
+}
+
+/// Returns the boosts applied to the chat by the user; the administrator
+/// rights are needed.
+#[cfg(feature = "bots-only-api")]
+pub async fn user_boosts(
+    chat_id: ChatId,
+    user_id: UserId,
+    client_id: i32,
+) -> Result<Vec<types::ChatBoost>, TdError> {
+    let enums::FoundChatBoosts::FoundChatBoosts(found) =
+        functions::get_user_chat_boosts(chat_id, user_id, client_id).await?;
+    Ok(found.boosts)
+}
+
+/// The pages of the boosts applied to a chat, started by [`boosts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Boosts {
+    chat_id: ChatId,
+    only_gift_codes: bool,
+    offset: Option<String>,
+    page_size: i32,
+    total_count: Option<i32>,
+}
+
+/// Page through the boosts applied to the chat, by pages of at most
+/// [`MAX_PAGE_SIZE`]; the administrator rights are needed.
+pub fn boosts(chat_id: ChatId) -> Boosts {
+    Boosts {
+        chat_id,
+        only_gift_codes: false,
+        offset: Some(String::new()),
+        page_size: MAX_PAGE_SIZE,
+        total_count: None,
+    }
+}
+
+impl Boosts {
+    /// Only the boosts of the gift codes and the giveaways of the chat.
+    pub fn only_gift_codes(mut self) -> Self {
+        self.only_gift_codes = true;
+        self
+    }
+
+    /// Request pages of at most `page_size` boosts, up to [`MAX_PAGE_SIZE`].
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// Returns the number of boosts applied to the chat, once a page was
+    /// requested.
+    pub fn total_count(&self) -> Option<i32> {
+        self.total_count
+    }
+
+    /// Returns the next page of boosts, or `None` after the last one.
+    pub async fn next(&mut self, client_id: i32) -> Result<Option<Vec<types::ChatBoost>>, TdError> {
+        let Some(offset) = self.offset.take() else {
+            return Ok(None);
+        };
+        let enums::FoundChatBoosts::FoundChatBoosts(found) = functions::get_chat_boosts(
+            self.chat_id,
+            self.only_gift_codes,
+            offset,
+            self.page_size,
+            client_id,
+        )
+        .await?;
+        self.total_count = Some(found.total_count);
+        if !found.next_offset.is_empty() {
+            self.offset = Some(found.next_offset);
+        }
+        if found.boosts.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(found.boosts))
+    }
+
+    /// Returns the remaining boosts applied to the chat.
+    pub async fn all(mut self, client_id: i32) -> Result<Vec<types::ChatBoost>, TdError> {
+        let mut all = Vec::new();
+        while let Some(boosts) = self.next(client_id).await? {
+            all.extend(boosts);
+        }
+        Ok(all)
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A subset of the methods of the HTTP Bot API, with the same arguments and
+//! semantics, implemented with the functions of TdLib. It eases porting the
+//! code written for the Bot API to TdLib, either as a bot or as a user.
+//!
+//! `getUpdates` is mapped onto a subscription to the receive loop of the
+//! `updates` module.
+use crate::enums::{
+    self, InputMessageContent, InputMessageReplyTo, ReplyMarkup, TextParseMode, Update,
+};
+use crate::ids::{ChatId, MessageId};
+use crate::runtime;
+use crate::updates::UpdateReceiver;
+use crate::{functions, rate_limit, types, TdError};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+
+/// The formatting of the text of a message, as the `parse_mode` of the Bot API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMode {
+    /// The legacy `Markdown` mode.
+    Markdown,
+    /// The `MarkdownV2` mode.
+    MarkdownV2,
+    /// The `HTML` mode.
+    Html,
+}
+
+impl From<ParseMode> for TextParseMode {
+    fn from(mode: ParseMode) -> Self {
+        match mode {
+            ParseMode::Markdown => {
+                TextParseMode::Markdown(td_struct!(types::TextParseModeMarkdown { version: 1 }))
+            }
+            ParseMode::MarkdownV2 => {
+                TextParseMode::Markdown(td_struct!(types::TextParseModeMarkdown { version: 2 }))
+            }
+            ParseMode::Html => TextParseMode::Html,
+        }
+    }
+}
+
+/// Returns the text with its entities, parsed according to the mode if any.
+async fn formatted_text(
+    text: String,
+    parse_mode: Option<ParseMode>,
+    client_id: i32,
+) -> Result<types::FormattedText, TdError> {
+    match parse_mode {
+        Some(mode) => {
+            let enums::FormattedText::FormattedText(text) =
+                functions::parse_text_entities(text, mode.into(), client_id).await?;
+            Ok(text)
+        }
+        None => Ok(td_struct!(types::FormattedText {
+            text,
+            entities: Vec::new()
+        })),
+    }
+}
+
+fn text_content(text: types::FormattedText) -> InputMessageContent {
+    InputMessageContent::InputMessageText(td_struct!(types::InputMessageText {
+        text,
+        link_preview_options: None,
+        clear_draft: false
+    }))
+}
+
+/// Same as the `sendMessage` method.
+pub async fn send_message(
+    chat_id: ChatId,
+    text: String,
+    parse_mode: Option<ParseMode>,
+    reply_to_message_id: Option<MessageId>,
+    reply_markup: Option<ReplyMarkup>,
+    client_id: i32,
+) -> Result<enums::Message, TdError> {
+    let text = formatted_text(text, parse_mode, client_id).await?;
+    let reply_to = reply_to_message_id.map(|message_id| {
+        InputMessageReplyTo::Message(td_struct!(types::InputMessageReplyToMessage {
+            chat_id: ChatId::default(),
+            message_id,
+            quote: None
+        }))
+    });
+    Ok(rate_limit::send_message(
+        chat_id,
+        0,
+        reply_to,
+        None,
+        reply_markup,
+        text_content(text),
+        client_id,
+    )
+    .await?)
+}
+
+/// Same as the `editMessageText` method, for the messages sent by the client.
+pub async fn edit_message_text(
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: String,
+    parse_mode: Option<ParseMode>,
+    reply_markup: Option<ReplyMarkup>,
+    client_id: i32,
+) -> Result<enums::Message, TdError> {
+    let text = formatted_text(text, parse_mode, client_id).await?;
+    Ok(functions::edit_message_text(
+        chat_id,
+        message_id,
+        reply_markup,
+        text_content(text),
+        client_id,
+    )
+    .await?)
+}
+
+/// Same as the `answerCallbackQuery` method.
+pub async fn answer_callback_query(
+    callback_query_id: i64,
+    text: Option<String>,
+    show_alert: bool,
+    url: Option<String>,
+    cache_time: i32,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::answer_callback_query(
+        callback_query_id,
+        text.unwrap_or_default(),
+        show_alert,
+        url.unwrap_or_default(),
+        cache_time,
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Same as the `getUpdates` method: waits up to `timeout` for an update,
+/// then returns those already received, at most `limit` (at least 1). Unlike
+/// the Bot API there is no offset, since the returned updates are removed from
+/// the queue.
+pub async fn get_updates(
+    receiver: &UpdateReceiver,
+    limit: usize,
+    timeout: Duration,
+) -> Vec<(Update, i32)> {
+    let limit = limit.max(1);
+    let mut updates = Vec::new();
+    if receiver.is_empty() && !timeout.is_zero() {
+        let mut recv = pin!(receiver.recv());
+        let mut sleep = pin!(runtime::sleep(timeout));
+        let first = poll_fn(|cx| {
+            if let Poll::Ready(update) = recv.as_mut().poll(cx) {
+                return Poll::Ready(Some(update));
+            }
+            sleep.as_mut().poll(cx).map(|_| None)
+        })
+        .await;
+        updates.extend(first);
+    }
+    while updates.len() < limit {
+        match receiver.try_recv() {
+            Some(update) => updates.push(update),
+            None => break,
+        }
+    }
+    updates
+}
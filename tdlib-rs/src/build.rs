@@ -1,12 +1,12 @@
-/// The build module is used to build the project using the enabled features.
-/// The features are correctly set when exactly one of the following features is enabled:
-/// - `local-tdlib`
-/// - `pkg-config`
-/// - `download-tdlib`
+//! The build module is used to build the project using the enabled features.
+//! The features are correctly set when exactly one of the following features is enabled:
+//! - `local-tdlib`
+//! - `pkg-config`
+//! - `download-tdlib`
 
 #[allow(dead_code)]
 #[cfg(not(any(feature = "docs", feature = "pkg-config")))]
-const TDLIB_VERSION: &str = "1.8.29";
+const TDLIB_VERSION: &str = crate::TDLIB_SCHEMA_VERSION;
 #[cfg(feature = "download-tdlib")]
 const TDLIB_CARGO_PKG_VERSION: &str = "1.0.5";
 
@@ -451,7 +451,7 @@ pub fn build_local_tdlib() {
 /// [dependencies]
 /// tdlib = { version = "...", features = ["download-tdlib"] }
 ///
-
+///
 /// [build-dependencies]
 /// tdlib = { version = "...", features = [ "download-tdlib" ] }
 /// ```
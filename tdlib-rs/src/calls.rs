@@ -0,0 +1,207 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The video chats (group calls) of the chats, tracked from the updates sent
+//! for them, and helpers to create and manage them.
+//!
+//! Only the signaling is handled: joining a video chat needs the payload of
+//! a WebRTC stack, which is not part of the library.
+use crate::enums::{self, MessageSender, Update};
+use crate::ids::{ChatId, UserId};
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct GroupCall {
+    group_call: Option<types::GroupCall>,
+    participants: Vec<types::GroupCallParticipant>,
+}
+
+static GROUP_CALLS: Lazy<RwLock<HashMap<(i32, i32), GroupCall>>> = Lazy::new(RwLock::default);
+
+/// Returns the group call, if TdLib sent it.
+pub fn group_call(group_call_id: i32, client_id: i32) -> Option<types::GroupCall> {
+    GROUP_CALLS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(client_id, group_call_id))?
+        .group_call
+        .clone()
+}
+
+/// Returns the participants of the group call sent by TdLib, in the order
+/// in which they are shown. More participants are sent after
+/// [`load_participants`].
+pub fn participants(group_call_id: i32, client_id: i32) -> Vec<types::GroupCallParticipant> {
+    let mut participants = GROUP_CALLS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(client_id, group_call_id))
+        .map(|group_call| group_call.participants.clone())
+        .unwrap_or_default();
+    participants.sort_by_key(|participant| Reverse(participant.order.clone()));
+    participants
+}
+
+/// Keep the group calls of the client and their participants up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::GroupCall(update) => {
+            GROUP_CALLS
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry((client_id, update.group_call.id))
+                .or_default()
+                .group_call = Some(update.group_call.clone());
+        }
+        Update::GroupCallParticipant(update) => {
+            let mut group_calls = GROUP_CALLS.write().unwrap_or_else(|e| e.into_inner());
+            let participants = &mut group_calls
+                .entry((client_id, update.group_call_id))
+                .or_default()
+                .participants;
+            let participant = &update.participant;
+            participants.retain(|p| p.participant_id != participant.participant_id);
+            // A participant with an empty order is no longer in the list
+            if !participant.order.is_empty() {
+                participants.push(participant.clone());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the group call, requesting it from TdLib.
+pub async fn get_group_call(
+    group_call_id: i32,
+    client_id: i32,
+) -> Result<types::GroupCall, TdError> {
+    let enums::GroupCall::GroupCall(group_call) =
+        functions::get_group_call(group_call_id, client_id).await?;
+    Ok(group_call)
+}
+
+/// Create a video chat in the chat, starting at `start_date` if scheduled or
+/// now if 0, and returns the identifier of its group call.
+pub async fn create_video_chat(
+    chat_id: ChatId,
+    title: String,
+    start_date: i32,
+    is_rtmp_stream: bool,
+    client_id: i32,
+) -> Result<i32, TdError> {
+    let enums::GroupCallId::GroupCallId(id) =
+        functions::create_video_chat(chat_id, title, start_date, is_rtmp_stream, client_id).await?;
+    Ok(id.id)
+}
+
+/// Start a scheduled group call now.
+pub async fn start_scheduled(group_call_id: i32, client_id: i32) -> Result<(), TdError> {
+    functions::start_scheduled_group_call(group_call_id, client_id).await?;
+    Ok(())
+}
+
+/// Join the group call with the payload of the WebRTC stack, returning the
+/// answer to pass back to it.
+#[allow(clippy::too_many_arguments)]
+pub async fn join(
+    group_call_id: i32,
+    participant_id: Option<MessageSender>,
+    audio_source_id: i32,
+    payload: String,
+    is_muted: bool,
+    is_my_video_enabled: bool,
+    invite_hash: String,
+    client_id: i32,
+) -> Result<String, TdError> {
+    let enums::Text::Text(text) = functions::join_group_call(
+        group_call_id,
+        participant_id,
+        audio_source_id,
+        payload,
+        is_muted,
+        is_my_video_enabled,
+        invite_hash,
+        client_id,
+    )
+    .await?;
+    Ok(text.text)
+}
+
+/// Invite the users to the group call.
+pub async fn invite(
+    group_call_id: i32,
+    user_ids: Vec<UserId>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::invite_group_call_participants(group_call_id, user_ids, client_id).await?;
+    Ok(())
+}
+
+/// Returns an invite link to the group call, for listeners only unless
+/// `can_self_unmute`.
+pub async fn invite_link(
+    group_call_id: i32,
+    can_self_unmute: bool,
+    client_id: i32,
+) -> Result<String, TdError> {
+    let enums::HttpUrl::HttpUrl(url) =
+        functions::get_group_call_invite_link(group_call_id, can_self_unmute, client_id).await?;
+    Ok(url.url)
+}
+
+/// Change the title of the group call.
+pub async fn set_title(group_call_id: i32, title: String, client_id: i32) -> Result<(), TdError> {
+    functions::set_group_call_title(group_call_id, title, client_id).await?;
+    Ok(())
+}
+
+/// Mute or unmute the participants joining the group call.
+pub async fn set_mute_new_participants(
+    group_call_id: i32,
+    mute_new_participants: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::toggle_group_call_mute_new_participants(
+        group_call_id,
+        mute_new_participants,
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Ask TdLib to send more participants of the group call, tracked by
+/// [`participants`].
+pub async fn load_participants(
+    group_call_id: i32,
+    limit: i32,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::load_group_call_participants(group_call_id, limit, client_id).await?;
+    Ok(())
+}
+
+/// Leave the group call.
+pub async fn leave(group_call_id: i32, client_id: i32) -> Result<(), TdError> {
+    functions::leave_group_call(group_call_id, client_id).await?;
+    Ok(())
+}
+
+/// End the group call for every participant.
+pub async fn end(group_call_id: i32, client_id: i32) -> Result<(), TdError> {
+    functions::end_group_call(group_call_id, client_id).await?;
+    GROUP_CALLS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&(client_id, group_call_id));
+    Ok(())
+}
@@ -0,0 +1,129 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The chat lists of each client, tracked from the positions of the chats
+//! sent by TdLib, and [`load_all_chats`] to load a whole list at startup.
+//!
+//! TdLib sends the positions of the chats loaded by `loadChats` in updates
+//! before answering it, so a list is complete once `loadChats` fails with
+//! the `404` error, as long as the updates are received.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::chat_lists;
+//! use tdlib_rs::enums::ChatList;
+//!
+//! # async fn run(client_id: i32) -> Result<(), tdlib_rs::TdError> {
+//! let chats = chat_lists::load_all_chats(ChatList::Main, client_id).await?;
+//! println!("{} chats in the main list", chats.len());
+//! # Ok(())
+//! # }
+//! ```
+use crate::enums::{AuthorizationState, ChatList, Update};
+use crate::ids::ChatId;
+use crate::{functions, TdError};
+use once_cell::sync::Lazy;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// The number of chats asked to TdLib by each `loadChats` of
+/// [`load_all_chats`].
+pub const LOAD_LIMIT: i32 = 100;
+
+/// The order of the chats of each list of a client, by chat.
+type Lists = Vec<(ChatList, HashMap<ChatId, i64>)>;
+
+static LISTS: Lazy<RwLock<HashMap<i32, Lists>>> = Lazy::new(RwLock::default);
+
+/// Set the order of the chat in the list, removing it if 0.
+fn set_order(client_id: i32, list: &ChatList, chat_id: ChatId, order: i64) {
+    let mut lists = LISTS.write().unwrap_or_else(|e| e.into_inner());
+    let lists = lists.entry(client_id).or_default();
+    let index = match lists.iter().position(|(other, _)| other == list) {
+        Some(index) => index,
+        None => {
+            lists.push((list.clone(), HashMap::new()));
+            lists.len() - 1
+        }
+    };
+    let chats = &mut lists[index].1;
+    if order == 0 {
+        chats.remove(&chat_id);
+    } else {
+        chats.insert(chat_id, order);
+    }
+}
+
+/// Keep the chat lists of the client up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::NewChat(update) => {
+            for position in &update.chat.positions {
+                set_order(client_id, &position.list, update.chat.id, position.order);
+            }
+        }
+        Update::ChatPosition(update) => {
+            set_order(
+                client_id,
+                &update.position.list,
+                update.chat_id,
+                update.position.order,
+            );
+        }
+        Update::ChatLastMessage(update) => {
+            for position in &update.positions {
+                set_order(client_id, &position.list, update.chat_id, position.order);
+            }
+        }
+        Update::ChatDraftMessage(update) => {
+            for position in &update.positions {
+                set_order(client_id, &position.list, update.chat_id, position.order);
+            }
+        }
+        Update::ChatRemovedFromList(update) => {
+            set_order(client_id, &update.chat_list, update.chat_id, 0);
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                LISTS
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&client_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the chats of the list sent by TdLib, in the order in which they
+/// are shown.
+pub fn chats(list: &ChatList, client_id: i32) -> Vec<ChatId> {
+    let lists = LISTS.read().unwrap_or_else(|e| e.into_inner());
+    let Some((_, chats)) = lists
+        .get(&client_id)
+        .and_then(|lists| lists.iter().find(|(other, _)| other == list))
+    else {
+        return Vec::new();
+    };
+    let mut chats = chats.iter().collect::<Vec<_>>();
+    chats.sort_by_key(|&(&chat_id, &order)| Reverse((order, chat_id)));
+    chats.into_iter().map(|(&chat_id, _)| chat_id).collect()
+}
+
+/// Load every chat of the list, calling `loadChats` until TdLib has no more
+/// chats to send. Returns the chats of the list, in the order in which they
+/// are shown.
+pub async fn load_all_chats(list: ChatList, client_id: i32) -> Result<Vec<ChatId>, TdError> {
+    loop {
+        match functions::load_chats(Some(list.clone()), LOAD_LIMIT, client_id).await {
+            Ok(()) => {}
+            Err(e) if e.code == 404 => return Ok(chats(&list, client_id)),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The functions of TdLib as the methods of the [`ClientApi`] trait, so
+//! that the code calling them can be tested against a [`MockClientApi`]
+//! instead of TdLib.
+//!
+//! ```rust
+//! use serde_json::json;
+//! use tdlib_rs::client_api::{ClientApi, MockClientApi};
+//! use tdlib_rs::enums::OptionValue;
+//!
+//! async fn version(api: &impl ClientApi) -> Option<String> {
+//!     match api.get_option("version".into()).await {
+//!         Ok(OptionValue::String(version)) => Some(version.value),
+//!         _ => None,
+//!     }
+//! }
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let mock = MockClientApi::new();
+//! mock.respond(
+//!     "getOption",
+//!     json!({ "@type": "optionValueString", "value": "1.8.29" }),
+//! );
+//! assert_eq!(version(&mock).await.as_deref(), Some("1.8.29"));
+//! assert_eq!(mock.calls_to("getOption")[0]["name"], "version");
+//! mock.verify();
+//! # }
+//! ```
+pub use crate::generated::client_api::ClientApi;
+use crate::json::RawJson;
+use crate::observer::Response;
+use crate::types;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::future::{self, Future};
+use std::sync::{Arc, Mutex};
+
+/// Deserialize the response of a method of [`ClientApi`].
+pub(crate) fn decode<T: DeserializeOwned>(response: RawJson) -> Result<T, types::Error> {
+    Response::new(String::new(), response.into_string()).result()
+}
+
+/// A client of TdLib, sending the requests of the [`ClientApi`] methods to
+/// it like the functions of the `functions` module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Client {
+    client_id: i32,
+}
+
+impl Client {
+    /// Returns the client with the identifier.
+    pub fn new(client_id: i32) -> Self {
+        Self { client_id }
+    }
+
+    /// Create a TdLib client.
+    pub fn create() -> Self {
+        Self::new(crate::create_client())
+    }
+
+    /// Returns the identifier of the client.
+    pub fn id(&self) -> i32 {
+        self.client_id
+    }
+}
+
+impl ClientApi for Client {
+    fn send(&self, request: Value) -> impl Future<Output = Result<RawJson, types::Error>> + Send {
+        let client_id = self.client_id;
+        async move {
+            let response = crate::send_request(client_id, request).await;
+            if response.is_error() {
+                return Err(response.error());
+            }
+            Ok(RawJson::new(response.into_json()))
+        }
+    }
+}
+
+type Responder = Arc<dyn Fn(&Value) -> Result<Value, types::Error> + Send + Sync>;
+
+#[derive(Default)]
+struct Mocked {
+    queued: VecDeque<Result<Value, types::Error>>,
+    always: Option<Responder>,
+}
+
+/// A fake [`ClientApi`] answering the requests with the responses set for
+/// their function, and recording them.
+///
+/// The responses are given as the JSON of TdLib, or as the types of the
+/// library serialized into it. The requests of a function without any
+/// response fail with a `404` error.
+#[derive(Default)]
+pub struct MockClientApi {
+    functions: Mutex<HashMap<String, Mocked>>,
+    calls: Mutex<Vec<Value>>,
+}
+
+impl MockClientApi {
+    /// Start a mock without any response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn mocked<T>(&self, function: &str, f: impl FnOnce(&mut Mocked) -> T) -> T {
+        let mut functions = self.functions.lock().unwrap_or_else(|e| e.into_inner());
+        f(functions.entry(function.into()).or_default())
+    }
+
+    /// Answer the next request of the function, like `getMe`, with the
+    /// response, once; the responses are used in the order they are set.
+    ///
+    /// # Panics
+    ///
+    /// If the response can't be serialized into JSON.
+    pub fn respond(&self, function: &str, response: impl Serialize) -> &Self {
+        let response = serde_json::to_value(response).expect("serialize the mocked response");
+        self.mocked(function, |mocked| mocked.queued.push_back(Ok(response)));
+        self
+    }
+
+    /// Fail the next request of the function with the error, once.
+    pub fn fail(&self, function: &str, code: i32, message: &str) -> &Self {
+        let error = td_struct!(types::Error {
+            code,
+            message: message.into()
+        });
+        self.mocked(function, |mocked| mocked.queued.push_back(Err(error)));
+        self
+    }
+
+    /// Answer every request of the function with the response, once the
+    /// ones set with [`respond`](Self::respond) and [`fail`](Self::fail)
+    /// are used.
+    ///
+    /// # Panics
+    ///
+    /// If the response can't be serialized into JSON.
+    pub fn respond_always(&self, function: &str, response: impl Serialize) -> &Self {
+        let response = serde_json::to_value(response).expect("serialize the mocked response");
+        self.respond_with(function, move |_| Ok(response.clone()))
+    }
+
+    /// Answer every request of the function with the result of the
+    /// callback, given the request, once the ones set with
+    /// [`respond`](Self::respond) and [`fail`](Self::fail) are used.
+    pub fn respond_with<F>(&self, function: &str, responder: F) -> &Self
+    where
+        F: Fn(&Value) -> Result<Value, types::Error> + Send + Sync + 'static,
+    {
+        self.mocked(function, |mocked| mocked.always = Some(Arc::new(responder)));
+        self
+    }
+
+    /// Returns the requests sent, in order.
+    pub fn calls(&self) -> Vec<Value> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns the requests of the function sent, in order.
+    pub fn calls_to(&self, function: &str) -> Vec<Value> {
+        self.calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|request| request["@type"] == function)
+            .cloned()
+            .collect()
+    }
+
+    /// Assert that the function was called the number of times.
+    ///
+    /// # Panics
+    ///
+    /// If the function was called another number of times.
+    pub fn assert_called(&self, function: &str, times: usize) {
+        let calls = self.calls_to(function).len();
+        assert_eq!(
+            calls, times,
+            "{} was called {} times instead of {}",
+            function, calls, times
+        );
+    }
+
+    /// Assert that every response set with [`respond`](Self::respond) and
+    /// [`fail`](Self::fail) was used.
+    ///
+    /// # Panics
+    ///
+    /// If a response wasn't used, as its request wasn't sent.
+    pub fn verify(&self) {
+        let functions = self.functions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut unused: Vec<_> = functions
+            .iter()
+            .filter(|(_, mocked)| !mocked.queued.is_empty())
+            .map(|(function, mocked)| format!("{} ({})", function, mocked.queued.len()))
+            .collect();
+        unused.sort();
+        assert!(
+            unused.is_empty(),
+            "Some mocked responses were not used: {}",
+            unused.join(", ")
+        );
+    }
+
+    fn answer(&self, request: &Value) -> Result<Value, types::Error> {
+        let function = request["@type"].as_str().unwrap_or_default();
+        // The responder is called without the lock, as it may use the mock
+        let responder = self.mocked(function, |mocked| match mocked.queued.pop_front() {
+            Some(response) => Err(response),
+            None => Ok(mocked.always.clone()),
+        });
+        match responder {
+            Err(response) => response,
+            Ok(Some(responder)) => responder(request),
+            Ok(None) => Err(not_mocked(function)),
+        }
+    }
+}
+
+fn not_mocked(function: &str) -> types::Error {
+    td_struct!(types::Error {
+        code: 404,
+        message: format!("No response mocked for {}", function)
+    })
+}
+
+impl ClientApi for MockClientApi {
+    fn send(&self, request: Value) -> impl Future<Output = Result<RawJson, types::Error>> + Send {
+        let response = self.answer(&request);
+        self.calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(request);
+        future::ready(response.map(|response| RawJson::new(response.to_string())))
+    }
+}
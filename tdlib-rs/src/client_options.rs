@@ -0,0 +1,119 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The options of each client, set when it is created with
+//! [`create_client_with`] or later with [`set_client_options`].
+//!
+//! The options left to `None` fall back to the settings shared by every
+//! client, like the policy of [`retry::set_policy`](crate::retry::set_policy). The timeout of
+//! [`receive`](crate::receive) and the capacity of the queues of the
+//! `updates` module are shared by every client, as the updates of all of
+//! them are received together, and are set with
+//! [`set_receive_timeout`](crate::set_receive_timeout) and
+//! [`updates::subscribe`](crate::updates::subscribe).
+use crate::enums::{AuthorizationState, Update};
+use crate::retry::RetryPolicy;
+use crate::{functions, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// The options of a client.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClientOptions {
+    /// How long the functions wait for their response before failing with a
+    /// `Request timeout` error, never retried since TdLib may still run the
+    /// request; they wait as long as needed if `None`.
+    pub request_timeout: Option<Duration>,
+    /// How the requests which TdLib guarantees were not executed are retried,
+    /// instead of the policy of [`retry::set_policy`](crate::retry::set_policy).
+    pub retry_policy: Option<RetryPolicy>,
+    /// The verbosity level of the internal log of TdLib to set when the
+    /// client is created with [`create_client_with`].
+    pub log_verbosity_level: Option<i32>,
+    /// How long the requests may wait for their response before being
+    /// reported as slow, instead of the threshold of
+    /// [`slow_requests::set_threshold`](crate::slow_requests::set_threshold).
+    pub slow_request_threshold: Option<Duration>,
+}
+
+impl ClientOptions {
+    /// Start the options of a client, all falling back to the shared
+    /// settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail the requests not answered within the timeout.
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    /// Retry the requests of the client with the policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Set the verbosity level of the internal log of TdLib.
+    pub fn log_verbosity_level(mut self, log_verbosity_level: i32) -> Self {
+        self.log_verbosity_level = Some(log_verbosity_level);
+        self
+    }
+
+    /// Report the requests of the client answered after the threshold.
+    pub fn slow_request_threshold(mut self, slow_request_threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(slow_request_threshold);
+        self
+    }
+}
+
+static OPTIONS: Lazy<RwLock<HashMap<i32, ClientOptions>>> = Lazy::new(RwLock::default);
+
+/// Forget the options of the clients once closed.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::AuthorizationState(update) = update {
+        if matches!(update.authorization_state, AuthorizationState::Closed) {
+            OPTIONS
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&client_id);
+        }
+    }
+}
+
+/// Set the options of the client, replacing the previous ones.
+pub fn set_client_options(client_id: i32, options: ClientOptions) {
+    OPTIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(client_id, options);
+}
+
+/// Returns the options of the client, the default ones if never set.
+pub fn client_options(client_id: i32) -> ClientOptions {
+    OPTIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Create a TdLib client with the options, returning its id once the log
+/// verbosity level is set if asked.
+pub async fn create_client_with(options: ClientOptions) -> Result<i32, TdError> {
+    let client_id = crate::create_client();
+    set_client_options(client_id, options);
+    if let Some(level) = options.log_verbosity_level {
+        functions::set_log_verbosity_level(level, client_id).await?;
+    }
+    Ok(client_id)
+}
@@ -0,0 +1,62 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Check that the TdLib linked at runtime is the one the code was generated
+//! from, since a different version may send updates and responses which
+//! can't be deserialized.
+use crate::enums::OptionValue;
+use crate::{functions, TdError};
+
+/// The version of TdLib the code was generated from.
+pub const TDLIB_SCHEMA_VERSION: &str = env!("TDLIB_VERSION");
+
+/// The commit of TdLib the code was generated from.
+pub const TDLIB_SCHEMA_COMMIT: &str = env!("TDLIB_COMMIT");
+
+/// What [`check_compatibility`] does when the version differs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnMismatch {
+    /// Log a warning and return `Ok`.
+    Warn,
+    /// Return [`TdError::IncompatibleTdLib`].
+    Error,
+}
+
+async fn string_option(name: &str, client_id: i32) -> Result<String, TdError> {
+    match functions::get_option(name.into(), client_id).await? {
+        OptionValue::String(option) => Ok(option.value),
+        _ => Ok(String::new()),
+    }
+}
+
+/// Compare the `version` and `commit_hash` options of the TdLib linked at
+/// runtime with [`TDLIB_SCHEMA_VERSION`] and [`TDLIB_SCHEMA_COMMIT`].
+pub async fn check_compatibility(client_id: i32, on_mismatch: OnMismatch) -> Result<(), TdError> {
+    let version = string_option("version", client_id).await?;
+    let commit_hash = string_option("commit_hash", client_id).await?;
+    if version == TDLIB_SCHEMA_VERSION && commit_hash == TDLIB_SCHEMA_COMMIT {
+        return Ok(());
+    }
+
+    match on_mismatch {
+        OnMismatch::Warn => {
+            log::warn!(
+                "TdLib {} ({}) is linked, but the code was generated from TdLib {} ({})",
+                version,
+                commit_hash,
+                TDLIB_SCHEMA_VERSION,
+                TDLIB_SCHEMA_COMMIT
+            );
+            Ok(())
+        }
+        OnMismatch::Error => Err(TdError::IncompatibleTdLib {
+            version,
+            commit_hash,
+        }),
+    }
+}
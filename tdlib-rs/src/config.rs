@@ -0,0 +1,451 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Load the parameters of a client from a TOML file, so that they can be
+//! changed without recompiling the application.
+//!
+//! ```toml
+//! api_id = 12345
+//! api_hash = "0123456789abcdef0123456789abcdef"
+//! database_directory = "tdlib"
+//! log_verbosity_level = 1
+//! request_timeout_ms = 30000
+//!
+//! [retry]
+//! max_retries = 3
+//!
+//! [proxy]
+//! server = "127.0.0.1"
+//! port = 1080
+//! type = "socks5"
+//! ```
+//!
+//! Every parameter of TdLib, the log verbosity level and the timeouts can be
+//! overridden by the environment variable named as the parameter in
+//! uppercase, prefixed by `TDLIB_`, such as `TDLIB_API_HASH`.
+//!
+//! The secrets of the configuration, like the API hash, the encryption key
+//! of the database and the credentials of the proxy, are redacted when it is
+//! formatted with `Debug`.
+use crate::client_options::{self, ClientOptions};
+use crate::redact::REDACTED;
+use crate::retry::RetryPolicy;
+use crate::{enums, functions, types, TdError};
+use serde::Deserialize;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use std::{env, fmt, fs, io};
+
+/// The prefix of the environment variables overriding the configuration.
+pub const ENV_PREFIX: &str = "TDLIB_";
+
+/// The error returned when the configuration can't be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not a valid configuration.
+    Toml(toml::de::Error),
+    /// The value of an environment variable is not valid for its parameter.
+    Env { name: String, value: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Cannot read the configuration: {}", e),
+            ConfigError::Toml(e) => write!(f, "Invalid configuration: {}", e),
+            ConfigError::Env { name, value } => {
+                write!(f, "Invalid value of the variable {}: {}", name, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Toml(e) => Some(e),
+            ConfigError::Env { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(error: toml::de::Error) -> Self {
+        ConfigError::Toml(error)
+    }
+}
+
+/// The retry policy of the configuration, see [`RetryPolicy`].
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub multiplier: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        let policy = RetryPolicy::default();
+        Self {
+            max_retries: policy.max_retries,
+            initial_backoff_ms: policy.initial_backoff.as_millis() as u64,
+            max_backoff_ms: policy.max_backoff.as_millis() as u64,
+            multiplier: policy.multiplier,
+        }
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        RetryPolicy {
+            max_retries: config.max_retries,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            multiplier: config.multiplier,
+        }
+    }
+}
+
+/// The kind of a proxy of the configuration, with its credentials.
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ProxyKind {
+    Socks5 {
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    Http {
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+        #[serde(default)]
+        http_only: bool,
+    },
+    Mtproto {
+        secret: String,
+    },
+}
+
+impl fmt::Debug for ProxyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyKind::Socks5 { username, .. } => f
+                .debug_struct("Socks5")
+                .field("username", username)
+                .field("password", &REDACTED)
+                .finish(),
+            ProxyKind::Http {
+                username,
+                http_only,
+                ..
+            } => f
+                .debug_struct("Http")
+                .field("username", username)
+                .field("password", &REDACTED)
+                .field("http_only", http_only)
+                .finish(),
+            ProxyKind::Mtproto { .. } => f
+                .debug_struct("Mtproto")
+                .field("secret", &REDACTED)
+                .finish(),
+        }
+    }
+}
+
+impl From<&ProxyKind> for enums::ProxyType {
+    fn from(kind: &ProxyKind) -> Self {
+        match kind.clone() {
+            ProxyKind::Socks5 { username, password } => {
+                enums::ProxyType::Socks5(td_struct!(types::ProxyTypeSocks5 { username, password }))
+            }
+            ProxyKind::Http {
+                username,
+                password,
+                http_only,
+            } => enums::ProxyType::Http(td_struct!(types::ProxyTypeHttp {
+                username,
+                password,
+                http_only
+            })),
+            ProxyKind::Mtproto { secret } => {
+                enums::ProxyType::Mtproto(td_struct!(types::ProxyTypeMtproto { secret }))
+            }
+        }
+    }
+}
+
+/// The proxy enabled by the configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ProxyConfig {
+    pub server: String,
+    pub port: i32,
+    #[serde(flatten)]
+    pub kind: ProxyKind,
+}
+
+/// The parameters of a client: those of `functions::set_tdlib_parameters`,
+/// documented there, and the options of the library.
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ClientConfig {
+    pub use_test_dc: bool,
+    pub database_directory: String,
+    pub files_directory: String,
+    pub database_encryption_key: String,
+    pub use_file_database: bool,
+    pub use_chat_info_database: bool,
+    pub use_message_database: bool,
+    pub use_secret_chats: bool,
+    pub api_id: i32,
+    pub api_hash: String,
+    pub system_language_code: String,
+    pub device_model: String,
+    pub system_version: String,
+    pub application_version: String,
+    /// The verbosity level of the internal log of TdLib, if it must be set.
+    pub log_verbosity_level: Option<i32>,
+    /// How long the requests of the client wait for their response, in
+    /// milliseconds, see [`ClientOptions::request_timeout`].
+    pub request_timeout_ms: Option<u64>,
+    /// How long TdLib is waited for at most by each receive, in
+    /// milliseconds, see [`crate::set_receive_timeout`].
+    pub receive_timeout_ms: Option<u64>,
+    /// The retry policy of the client to set, if any.
+    pub retry: Option<RetryConfig>,
+    /// The proxy to enable, if any.
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            use_test_dc: false,
+            database_directory: "tdlib".into(),
+            files_directory: String::new(),
+            database_encryption_key: String::new(),
+            use_file_database: true,
+            use_chat_info_database: true,
+            use_message_database: true,
+            use_secret_chats: false,
+            api_id: 0,
+            api_hash: String::new(),
+            system_language_code: "en".into(),
+            device_model: "Desktop".into(),
+            system_version: String::new(),
+            application_version: "1.0".into(),
+            log_verbosity_level: None,
+            request_timeout_ms: None,
+            receive_timeout_ms: None,
+            retry: None,
+            proxy: None,
+        }
+    }
+}
+
+impl fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientConfig")
+            .field("use_test_dc", &self.use_test_dc)
+            .field("database_directory", &self.database_directory)
+            .field("files_directory", &self.files_directory)
+            .field("database_encryption_key", &REDACTED)
+            .field("use_file_database", &self.use_file_database)
+            .field("use_chat_info_database", &self.use_chat_info_database)
+            .field("use_message_database", &self.use_message_database)
+            .field("use_secret_chats", &self.use_secret_chats)
+            .field("api_id", &self.api_id)
+            .field("api_hash", &REDACTED)
+            .field("system_language_code", &self.system_language_code)
+            .field("device_model", &self.device_model)
+            .field("system_version", &self.system_version)
+            .field("application_version", &self.application_version)
+            .field("log_verbosity_level", &self.log_verbosity_level)
+            .field("request_timeout_ms", &self.request_timeout_ms)
+            .field("receive_timeout_ms", &self.receive_timeout_ms)
+            .field("retry", &self.retry)
+            .field("proxy", &self.proxy)
+            .finish()
+    }
+}
+
+fn env_value<T: FromStr>(name: &str) -> Result<Option<T>, ConfigError> {
+    let name = format!("{}{}", ENV_PREFIX, name);
+    match env::var(&name) {
+        Ok(value) => match value.parse() {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(_) => Err(ConfigError::Env { name, value }),
+        },
+        Err(_) => Ok(None),
+    }
+}
+
+fn env_override<T: FromStr>(name: &str, field: &mut T) -> Result<(), ConfigError> {
+    if let Some(value) = env_value(name)? {
+        *field = value;
+    }
+    Ok(())
+}
+
+impl ClientConfig {
+    /// Load the configuration from the TOML file, then apply the overrides of
+    /// the environment variables.
+    pub fn from_toml(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_toml_str(&fs::read_to_string(path)?)
+    }
+
+    /// Load the configuration from the TOML string, then apply the overrides
+    /// of the environment variables.
+    pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+        let mut config: Self = toml::from_str(toml)?;
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Override the parameters with the values of the environment variables.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        env_override("USE_TEST_DC", &mut self.use_test_dc)?;
+        env_override("DATABASE_DIRECTORY", &mut self.database_directory)?;
+        env_override("FILES_DIRECTORY", &mut self.files_directory)?;
+        env_override("DATABASE_ENCRYPTION_KEY", &mut self.database_encryption_key)?;
+        env_override("USE_FILE_DATABASE", &mut self.use_file_database)?;
+        env_override("USE_CHAT_INFO_DATABASE", &mut self.use_chat_info_database)?;
+        env_override("USE_MESSAGE_DATABASE", &mut self.use_message_database)?;
+        env_override("USE_SECRET_CHATS", &mut self.use_secret_chats)?;
+        env_override("API_ID", &mut self.api_id)?;
+        env_override("API_HASH", &mut self.api_hash)?;
+        env_override("SYSTEM_LANGUAGE_CODE", &mut self.system_language_code)?;
+        env_override("DEVICE_MODEL", &mut self.device_model)?;
+        env_override("SYSTEM_VERSION", &mut self.system_version)?;
+        env_override("APPLICATION_VERSION", &mut self.application_version)?;
+
+        if let Some(level) = env_value("LOG_VERBOSITY_LEVEL")? {
+            self.log_verbosity_level = Some(level);
+        }
+        if let Some(timeout) = env_value("REQUEST_TIMEOUT_MS")? {
+            self.request_timeout_ms = Some(timeout);
+        }
+        if let Some(timeout) = env_value("RECEIVE_TIMEOUT_MS")? {
+            self.receive_timeout_ms = Some(timeout);
+        }
+        Ok(())
+    }
+
+    /// Send the parameters of TdLib with the client.
+    pub async fn set_tdlib_parameters(&self, client_id: i32) -> Result<(), TdError> {
+        functions::set_tdlib_parameters(
+            self.use_test_dc,
+            self.database_directory.clone(),
+            self.files_directory.clone(),
+            self.database_encryption_key.clone(),
+            self.use_file_database,
+            self.use_chat_info_database,
+            self.use_message_database,
+            self.use_secret_chats,
+            self.api_id,
+            self.api_hash.clone(),
+            self.system_language_code.clone(),
+            self.device_model.clone(),
+            self.system_version.clone(),
+            self.application_version.clone(),
+            client_id,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Set the request timeout and the retry policy in the options of the
+    /// client, and the receive timeout.
+    fn apply_options(&self, client_id: i32) {
+        let options = client_options::client_options(client_id);
+        client_options::set_client_options(
+            client_id,
+            ClientOptions {
+                request_timeout: self
+                    .request_timeout_ms
+                    .map(Duration::from_millis)
+                    .or(options.request_timeout),
+                retry_policy: self.retry.as_ref().map(Into::into).or(options.retry_policy),
+                ..options
+            },
+        );
+        if let Some(timeout) = self.receive_timeout_ms {
+            crate::set_receive_timeout(Duration::from_millis(timeout));
+        }
+    }
+
+    /// Apply the whole configuration: set the log verbosity level, the
+    /// timeouts and the retry policy of the client, send the parameters of
+    /// TdLib and enable the proxy.
+    pub async fn apply(&self, client_id: i32) -> Result<(), TdError> {
+        if let Some(level) = self.log_verbosity_level {
+            functions::set_log_verbosity_level(level, client_id).await?;
+        }
+        self.apply_options(client_id);
+        self.set_tdlib_parameters(client_id).await?;
+        if let Some(proxy) = &self.proxy {
+            functions::add_proxy(
+                proxy.server.clone(),
+                proxy.port,
+                true,
+                (&proxy.kind).into(),
+                client_id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_client_options() {
+        let config: ClientConfig = toml::from_str(
+            r#"
+            api_id = 1
+            api_hash = "x"
+            request_timeout_ms = 1000
+
+            [retry]
+            max_retries = 2
+            "#,
+        )
+        .unwrap();
+        let (client_id, other_client_id) = (-1001, -1002);
+        config.apply_options(client_id);
+
+        let options = client_options::client_options(client_id);
+        assert_eq!(options.request_timeout, Some(Duration::from_secs(1)));
+        assert_eq!(
+            options.retry_policy.map(|policy| policy.max_retries),
+            Some(2)
+        );
+        assert_eq!(
+            client_options::client_options(other_client_id),
+            ClientOptions::default()
+        );
+        assert_eq!(crate::retry::policy(), RetryPolicy::NEVER);
+    }
+}
@@ -0,0 +1,369 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The state of the connection of the clients to Telegram, tracked from
+//! `updateConnectionState`, a queue holding the messages sent while the
+//! connection isn't ready, and a [`Watchdog`] nudging TdLib when the
+//! connection takes too long to be restored.
+//!
+//! TdLib accepts the messages sent while offline, but keeps them pending
+//! without limit; an [`OfflineQueue`] instead sends them once the connection
+//! is ready, in order, and fails the ones waiting for too long or beyond its
+//! size.
+use crate::enums::{self, ConnectionState, InputMessageContent, NetworkType, Update};
+use crate::ids::ChatId;
+use crate::runtime;
+use crate::{functions, rate_limit, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// The state of the connection of each client, and since when.
+static STATES: Lazy<RwLock<HashMap<i32, (ConnectionState, Instant)>>> = Lazy::new(RwLock::default);
+
+static CHANGED: Notify = Notify::const_new();
+
+/// Keep the state of the connection of the client up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::ConnectionState(update) = update {
+        STATES
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(client_id, (update.state.clone(), Instant::now()));
+        CHANGED.notify_waiters();
+    }
+}
+
+/// Returns the state of the connection of the client, `None` until TdLib
+/// sends it.
+pub fn state(client_id: i32) -> Option<ConnectionState> {
+    state_since(client_id).map(|(state, _)| state)
+}
+
+/// Returns the state of the connection of the client and since when it is
+/// in that state, `None` until TdLib sends it.
+pub fn state_since(client_id: i32) -> Option<(ConnectionState, Instant)> {
+    STATES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .cloned()
+}
+
+/// Whether the client is connected to Telegram; it is assumed until TdLib
+/// sends the state of the connection.
+pub fn is_ready(client_id: i32) -> bool {
+    matches!(state(client_id), None | Some(ConnectionState::Ready))
+}
+
+/// Wait until the client is connected to Telegram, at most for the timeout;
+/// returns whether it is.
+pub async fn wait_ready(client_id: i32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut sleep = pin!(runtime::sleep(timeout));
+    loop {
+        let mut changed = pin!(CHANGED.notified());
+        changed.as_mut().enable();
+        if is_ready(client_id) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        let timed_out = poll_fn(|cx| {
+            if changed.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(false);
+            }
+            sleep.as_mut().poll(cx).map(|_| true)
+        })
+        .await;
+        if timed_out {
+            return is_ready(client_id);
+        }
+    }
+}
+
+/// A queue of the messages of a client sent while its connection isn't
+/// ready.
+#[derive(Debug)]
+pub struct OfflineQueue {
+    client_id: i32,
+    max_len: usize,
+    max_age: Duration,
+    len: AtomicUsize,
+    order: tokio::sync::Mutex<()>,
+}
+
+impl OfflineQueue {
+    /// Start a queue of the client, of at most 100 messages waiting at most
+    /// 5 minutes.
+    pub fn new(client_id: i32) -> Self {
+        Self {
+            client_id,
+            max_len: 100,
+            max_age: Duration::from_secs(300),
+            len: AtomicUsize::new(0),
+            order: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Queue at most `max_len` messages, failing the next ones.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Fail the messages queued for longer than `max_age`.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Returns the number of messages queued.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::SeqCst)
+    }
+
+    /// Whether no message is queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send the message now if the connection is ready and nothing is
+    /// queued, or else once the connection is ready, after the messages
+    /// queued before it; returns the message being sent.
+    pub async fn send(
+        &self,
+        chat_id: ChatId,
+        content: InputMessageContent,
+    ) -> Result<types::Message, TdError> {
+        if self.is_empty() && is_ready(self.client_id) {
+            return send_message(chat_id, content, self.client_id).await;
+        }
+
+        let queued = self.len.fetch_add(1, Ordering::SeqCst);
+        let result = self.send_queued(queued, chat_id, content).await;
+        self.len.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    async fn send_queued(
+        &self,
+        queued: usize,
+        chat_id: ChatId,
+        content: InputMessageContent,
+    ) -> Result<types::Message, TdError> {
+        if queued >= self.max_len {
+            return Err(TdError::invalid(
+                400,
+                format!("The offline queue of {} messages is full", self.max_len),
+            ));
+        }
+        let queued_at = Instant::now();
+        let _order = self.order.lock().await;
+        let remaining = self.max_age.saturating_sub(queued_at.elapsed());
+        if !wait_ready(self.client_id, remaining).await {
+            return Err(TdError::invalid(
+                400,
+                format!("The connection wasn't ready within {:?}", self.max_age),
+            ));
+        }
+        send_message(chat_id, content, self.client_id).await
+    }
+}
+
+async fn send_message(
+    chat_id: ChatId,
+    content: InputMessageContent,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    let enums::Message::Message(message) =
+        rate_limit::send_message(chat_id, 0, None, None, None, content, client_id).await?;
+    Ok(message)
+}
+
+/// What a [`Watchdog`] noticed or did.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatchdogEvent {
+    /// The connection is in the state for longer than allowed.
+    Stalled {
+        /// The state of the connection.
+        state: ConnectionState,
+        /// How long the connection is in that state.
+        duration: Duration,
+    },
+    /// The network type was set again, so that TdLib reconnects.
+    NetworkTypeSet(NetworkType),
+    /// The proxy was enabled, the next one of the rotation.
+    ProxyEnabled(i32),
+    /// A request of the watchdog failed.
+    Failed(types::Error),
+    /// The connection is ready again after it stalled.
+    Recovered {
+        /// How long the connection was not ready.
+        duration: Duration,
+    },
+}
+
+type WatchdogHook = Box<dyn Fn(&WatchdogEvent) + Send + Sync>;
+
+/// A watchdog of the connection of a client, setting the network type
+/// again, and optionally enabling the next proxy, whenever the connection
+/// has been waiting for the network or connecting for too long, while
+/// [`Watchdog::run`] is awaited.
+pub struct Watchdog {
+    client_id: i32,
+    stall_after: Duration,
+    network_type: NetworkType,
+    proxy_ids: Vec<i32>,
+    next_proxy: AtomicUsize,
+    on_event: Option<WatchdogHook>,
+}
+
+impl Watchdog {
+    /// Start a watchdog of the client, nudging TdLib after a minute without
+    /// connection, with the `Other` network type.
+    pub fn new(client_id: i32) -> Self {
+        Self {
+            client_id,
+            stall_after: Duration::from_secs(60),
+            network_type: NetworkType::Other,
+            proxy_ids: Vec::new(),
+            next_proxy: AtomicUsize::new(0),
+            on_event: None,
+        }
+    }
+
+    /// Nudge TdLib once the connection isn't ready for `stall_after`, and
+    /// again after each `stall_after` it stays so.
+    pub fn stall_after(mut self, stall_after: Duration) -> Self {
+        self.stall_after = stall_after;
+        self
+    }
+
+    /// Set the network type instead of `Other`.
+    pub fn network_type(mut self, network_type: NetworkType) -> Self {
+        self.network_type = network_type;
+        self
+    }
+
+    /// Enable the next of the proxies, added with `addProxy`, at each nudge.
+    pub fn rotate_proxies(mut self, proxy_ids: Vec<i32>) -> Self {
+        self.proxy_ids = proxy_ids;
+        self
+    }
+
+    /// Call the callback with what the watchdog notices and does.
+    pub fn on_event<F>(mut self, on_event: F) -> Self
+    where
+        F: Fn(&WatchdogEvent) + Send + Sync + 'static,
+    {
+        self.on_event = Some(Box::new(on_event));
+        self
+    }
+
+    fn emit(&self, event: WatchdogEvent) {
+        match &self.on_event {
+            Some(on_event) => on_event(&event),
+            None => log::info!(
+                "Connection watchdog of client {}: {:?}",
+                self.client_id,
+                event
+            ),
+        }
+    }
+
+    /// Returns since when the connection is stalled, if it is.
+    fn stalled_since(&self) -> Option<(ConnectionState, Instant)> {
+        state_since(self.client_id).filter(|(state, _)| {
+            matches!(
+                state,
+                ConnectionState::WaitingForNetwork
+                    | ConnectionState::ConnectingToProxy
+                    | ConnectionState::Connecting
+            )
+        })
+    }
+
+    async fn nudge(&self) {
+        let network_type = self.network_type.clone();
+        match functions::set_network_type(Some(network_type.clone()), self.client_id).await {
+            Ok(()) => self.emit(WatchdogEvent::NetworkTypeSet(network_type)),
+            Err(e) => self.emit(WatchdogEvent::Failed(e)),
+        }
+        if self.proxy_ids.is_empty() {
+            return;
+        }
+        let next = self.next_proxy.fetch_add(1, Ordering::Relaxed);
+        let proxy_id = self.proxy_ids[next % self.proxy_ids.len()];
+        match functions::enable_proxy(proxy_id, self.client_id).await {
+            Ok(()) => self.emit(WatchdogEvent::ProxyEnabled(proxy_id)),
+            Err(e) => self.emit(WatchdogEvent::Failed(e)),
+        }
+    }
+
+    /// Watch the connection until the future is dropped.
+    pub async fn run(&self) {
+        // When the connection started to stall, once it was nudged
+        let mut stalled: Option<Instant> = None;
+        let mut nudged_at: Option<Instant> = None;
+        loop {
+            let mut changed = pin!(CHANGED.notified());
+            changed.as_mut().enable();
+
+            let wait = match self.stalled_since() {
+                Some((state, since)) => {
+                    let last = nudged_at.map_or(since, |nudged_at| nudged_at.max(since));
+                    let elapsed = last.elapsed();
+                    if elapsed >= self.stall_after {
+                        let since = *stalled.get_or_insert(since);
+                        self.emit(WatchdogEvent::Stalled {
+                            state,
+                            duration: since.elapsed(),
+                        });
+                        self.nudge().await;
+                        nudged_at = Some(Instant::now());
+                        continue;
+                    }
+                    Some(self.stall_after - elapsed)
+                }
+                None => {
+                    if is_ready(self.client_id) {
+                        if let Some(since) = stalled.take() {
+                            self.emit(WatchdogEvent::Recovered {
+                                duration: since.elapsed(),
+                            });
+                        }
+                        nudged_at = None;
+                    }
+                    None
+                }
+            };
+
+            match wait {
+                Some(wait) => {
+                    let mut sleep = pin!(runtime::sleep(wait));
+                    poll_fn(|cx| {
+                        if changed.as_mut().poll(cx).is_ready() {
+                            return Poll::Ready(());
+                        }
+                        sleep.as_mut().poll(cx)
+                    })
+                    .await
+                }
+                None => changed.await,
+            }
+        }
+    }
+}
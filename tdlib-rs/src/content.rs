@@ -0,0 +1,303 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Build the content of a message from the path of a local file, as a
+//! photo, a video, an audio or a document depending on its extension.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::{content, functions};
+//!
+//! # async fn send(client_id: i32) {
+//! # let chat_id = Default::default();
+//! let content = content::from_path("holidays/beach.jpg");
+//! # #[cfg(not(feature = "bots-only-api"))]
+//! functions::send_message(chat_id, 0, None, None, content, client_id).await;
+//! # }
+//! ```
+//!
+//! With the `image` feature, the dimensions of the photos and the
+//! animations are read from the files, along with a JPEG thumbnail, and
+//! those of the MP4 videos, with their duration, from their headers. The
+//! ones left unknown are detected by TdLib once the file is uploaded.
+//!
+//! The thumbnails of the other files, like the videos and the documents,
+//! are generated by the [`ThumbnailGenerator`] set with
+//! [`set_thumbnail_generator`], for example by running `ffmpeg`; none is
+//! generated by default.
+use crate::enums::{InputFile, InputMessageContent};
+use crate::types;
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// The kind of message a file is sent as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    /// A compressed photo.
+    Photo,
+    /// A video.
+    Video,
+    /// An animation without sound, like a GIF.
+    Animation,
+    /// A music file.
+    Audio,
+    /// A voice note, encoded with Opus in an Ogg container.
+    VoiceNote,
+    /// A file sent as is.
+    Document,
+}
+
+impl MediaKind {
+    /// Returns the kind of message the file is sent as, from its extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match extension(path.as_ref()).as_deref() {
+            Some("jpg" | "jpeg" | "png" | "webp" | "bmp") => MediaKind::Photo,
+            Some("mp4" | "m4v" | "mov" | "mkv" | "webm" | "avi") => MediaKind::Video,
+            Some("gif") => MediaKind::Animation,
+            Some("mp3" | "m4a" | "aac" | "flac" | "wav" | "ogg") => MediaKind::Audio,
+            Some("oga" | "opus") => MediaKind::VoiceNote,
+            _ => MediaKind::Document,
+        }
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Returns the MIME type of the file from its extension, if known.
+pub fn mime_type(path: impl AsRef<Path>) -> Option<&'static str> {
+    let mime_type = match extension(path.as_ref())?.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "gif" => "image/gif",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "avi" => "video/x-msvideo",
+        "mp3" => "audio/mpeg",
+        "m4a" | "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "txt" => "text/plain",
+        _ => return None,
+    };
+    Some(mime_type)
+}
+
+/// A generator of the thumbnails of the files sent, called when building
+/// their content unless a thumbnail is already set or probed.
+pub trait ThumbnailGenerator: Send + Sync {
+    /// Returns the thumbnail of the file sent as the kind, a JPEG of at most
+    /// 320 pixels on its largest side, or `None` to send it without.
+    fn thumbnail(&self, path: &Path, kind: MediaKind) -> Option<types::InputThumbnail>;
+}
+
+impl<F> ThumbnailGenerator for F
+where
+    F: Fn(&Path, MediaKind) -> Option<types::InputThumbnail> + Send + Sync,
+{
+    fn thumbnail(&self, path: &Path, kind: MediaKind) -> Option<types::InputThumbnail> {
+        self(path, kind)
+    }
+}
+
+/// The default generator, sending the files without thumbnail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoThumbnail;
+
+impl ThumbnailGenerator for NoThumbnail {
+    fn thumbnail(&self, _path: &Path, _kind: MediaKind) -> Option<types::InputThumbnail> {
+        None
+    }
+}
+
+static THUMBNAIL_GENERATOR: Lazy<RwLock<Arc<dyn ThumbnailGenerator>>> =
+    Lazy::new(|| RwLock::new(Arc::new(NoThumbnail)));
+
+/// Set the generator of the thumbnails of the files sent, replacing the
+/// previous one.
+pub fn set_thumbnail_generator(generator: impl ThumbnailGenerator + 'static) {
+    *THUMBNAIL_GENERATOR
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = Arc::new(generator);
+}
+
+fn thumbnail_generator() -> Arc<dyn ThumbnailGenerator> {
+    THUMBNAIL_GENERATOR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Returns the content of a message sending the file as the kind detected
+/// from its extension, without caption.
+pub fn from_path(path: impl AsRef<Path>) -> InputMessageContent {
+    file(path).build()
+}
+
+/// Start the content of a message sending the file.
+pub fn file(path: impl AsRef<Path>) -> MediaFile {
+    MediaFile {
+        path: path.as_ref().to_path_buf(),
+        kind: None,
+        caption: None,
+        thumbnail: None,
+        has_spoiler: false,
+    }
+}
+
+/// The content of a message sending a local file, built by [`file()`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaFile {
+    path: PathBuf,
+    kind: Option<MediaKind>,
+    caption: Option<types::FormattedText>,
+    thumbnail: Option<types::InputThumbnail>,
+    has_spoiler: bool,
+}
+
+impl MediaFile {
+    /// Send the file as the kind, instead of the one of its extension.
+    pub fn kind(mut self, kind: MediaKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Set the caption of the message.
+    pub fn caption(mut self, caption: types::FormattedText) -> Self {
+        self.caption = Some(caption);
+        self
+    }
+
+    /// Send the thumbnail, instead of the one probed or generated.
+    pub fn thumbnail(mut self, thumbnail: types::InputThumbnail) -> Self {
+        self.thumbnail = Some(thumbnail);
+        self
+    }
+
+    /// Cover the photo, the video or the animation with a spoiler animation.
+    pub fn spoiler(mut self) -> Self {
+        self.has_spoiler = true;
+        self
+    }
+
+    /// Returns the kind of message the file is sent as.
+    pub fn media_kind(&self) -> MediaKind {
+        self.kind
+            .unwrap_or_else(|| MediaKind::from_path(&self.path))
+    }
+
+    /// Returns the content of the message.
+    pub fn build(self) -> InputMessageContent {
+        let kind = self.media_kind();
+        let file = InputFile::Local(td_struct!(types::InputFileLocal {
+            path: self.path.to_string_lossy().into_owned(),
+        }));
+        let caption = self.caption;
+        let has_spoiler = self.has_spoiler;
+        #[cfg(feature = "image")]
+        let (width, height, duration, thumbnail) = match kind {
+            MediaKind::Photo | MediaKind::Animation => {
+                let (width, height) =
+                    crate::probe::image_dimensions(&self.path).unwrap_or_default();
+                let thumbnail = match self.thumbnail {
+                    Some(_) => None,
+                    None => crate::probe::image_thumbnail(&self.path),
+                };
+                (width, height, 0, thumbnail)
+            }
+            MediaKind::Video => crate::probe::video_info(&self.path)
+                .map_or((0, 0, 0, None), |info| {
+                    (info.width, info.height, info.duration, None)
+                }),
+            _ => (0, 0, 0, None),
+        };
+        #[cfg(not(feature = "image"))]
+        let (width, height, duration, thumbnail) = (0, 0, 0, None);
+        let thumbnail = self
+            .thumbnail
+            .or(thumbnail)
+            .or_else(|| thumbnail_generator().thumbnail(&self.path, kind));
+        match kind {
+            MediaKind::Photo => {
+                InputMessageContent::InputMessagePhoto(td_struct!(types::InputMessagePhoto {
+                    photo: file,
+                    thumbnail,
+                    added_sticker_file_ids: Vec::new(),
+                    width,
+                    height,
+                    caption,
+                    self_destruct_type: None,
+                    has_spoiler,
+                }))
+            }
+            MediaKind::Video => {
+                InputMessageContent::InputMessageVideo(td_struct!(types::InputMessageVideo {
+                    video: file,
+                    thumbnail,
+                    added_sticker_file_ids: Vec::new(),
+                    duration,
+                    width,
+                    height,
+                    supports_streaming: true,
+                    caption,
+                    self_destruct_type: None,
+                    has_spoiler,
+                }))
+            }
+            MediaKind::Animation => InputMessageContent::InputMessageAnimation(td_struct!(
+                types::InputMessageAnimation {
+                    animation: file,
+                    thumbnail,
+                    added_sticker_file_ids: Vec::new(),
+                    duration,
+                    width,
+                    height,
+                    caption,
+                    has_spoiler,
+                }
+            )),
+            MediaKind::Audio => {
+                InputMessageContent::InputMessageAudio(td_struct!(types::InputMessageAudio {
+                    audio: file,
+                    album_cover_thumbnail: thumbnail,
+                    duration: 0,
+                    title: String::new(),
+                    performer: String::new(),
+                    caption,
+                }))
+            }
+            MediaKind::VoiceNote => InputMessageContent::InputMessageVoiceNote(td_struct!(
+                types::InputMessageVoiceNote {
+                    voice_note: file,
+                    duration: 0,
+                    waveform: String::new(),
+                    caption,
+                    self_destruct_type: None,
+                }
+            )),
+            MediaKind::Document => {
+                InputMessageContent::InputMessageDocument(td_struct!(types::InputMessageDocument {
+                    document: file,
+                    thumbnail,
+                    disable_content_type_detection: false,
+                    caption,
+                }))
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The stickers of the custom emoji, cached for each client once returned
+//! by TdLib, since the same custom emoji are shown again and again.
+use crate::enums::{self, StickerFullType};
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// The maximum number of custom emoji returned by `getCustomEmojiStickers`.
+pub const MAX_CUSTOM_EMOJI: usize = 200;
+
+static STICKERS: Lazy<RwLock<HashMap<(i32, i64), types::Sticker>>> = Lazy::new(RwLock::default);
+
+/// Returns the sticker of the custom emoji, if already cached.
+pub fn cached(custom_emoji_id: i64, client_id: i32) -> Option<types::Sticker> {
+    STICKERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(client_id, custom_emoji_id))
+        .cloned()
+}
+
+/// Returns the stickers of the custom emoji in the same order, requesting
+/// only those not cached yet. The custom emoji which aren't found are
+/// skipped.
+pub async fn stickers(
+    custom_emoji_ids: &[i64],
+    client_id: i32,
+) -> Result<Vec<types::Sticker>, TdError> {
+    let mut missing = custom_emoji_ids
+        .iter()
+        .copied()
+        .filter(|&id| cached(id, client_id).is_none())
+        .collect::<Vec<_>>();
+    missing.sort_unstable();
+    missing.dedup();
+
+    for ids in missing.chunks(MAX_CUSTOM_EMOJI) {
+        let enums::Stickers::Stickers(stickers) =
+            functions::get_custom_emoji_stickers(ids.to_vec(), client_id).await?;
+        let mut cache = STICKERS.write().unwrap_or_else(|e| e.into_inner());
+        for sticker in stickers.stickers {
+            if let StickerFullType::CustomEmoji(full_type) = &sticker.full_type {
+                cache.insert((client_id, full_type.custom_emoji_id), sticker);
+            }
+        }
+    }
+
+    Ok(custom_emoji_ids
+        .iter()
+        .filter_map(|&id| cached(id, client_id))
+        .collect())
+}
+
+/// Returns the sticker of the custom emoji, if found.
+pub async fn sticker(
+    custom_emoji_id: i64,
+    client_id: i32,
+) -> Result<Option<types::Sticker>, TdError> {
+    Ok(stickers(&[custom_emoji_id], client_id).await?.pop())
+}
@@ -0,0 +1,123 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Convert the dates of TdLib, Unix timestamps in seconds, into the
+//! `DateTime` of [chrono](https://docs.rs/chrono) and back, and the helpers
+//! taking a `DateTime` instead of a timestamp.
+//!
+//! TdLib sends 0 for the dates which aren't set, returned as `None`.
+//!
+//! ```rust
+//! use chrono::{TimeZone, Utc};
+//! use tdlib_rs::dates;
+//!
+//! let date_time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+//! assert_eq!(dates::to_timestamp(&date_time), 1717243200);
+//! assert_eq!(dates::from_timestamp(1717243200), Some(date_time));
+//! assert_eq!(dates::from_timestamp(0), None);
+//! ```
+use crate::enums::{self, MessageSchedulingState, MessageSender};
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+/// Returns the date and time of the timestamp, or `None` if 0.
+pub fn from_timestamp(timestamp: i32) -> Option<DateTime<Utc>> {
+    if timestamp == 0 {
+        return None;
+    }
+    DateTime::from_timestamp(timestamp.into(), 0)
+}
+
+/// Returns the timestamp of the date and time, clamped to the range of the
+/// timestamps of TdLib.
+pub fn to_timestamp<Tz: TimeZone>(date_time: &DateTime<Tz>) -> i32 {
+    date_time
+        .timestamp()
+        .clamp(i32::MIN.into(), i32::MAX.into()) as i32
+}
+
+/// Returns the seconds from now until the date and time, 0 if it is past.
+fn seconds_until<Tz: TimeZone>(date_time: &DateTime<Tz>) -> i32 {
+    let seconds = (date_time.to_utc() - Utc::now()).num_seconds();
+    seconds.clamp(0, i32::MAX.into()) as i32
+}
+
+impl types::Message {
+    /// Returns when the message was sent, or is scheduled to be sent.
+    pub fn date_time(&self) -> DateTime<Utc> {
+        from_timestamp(self.date).unwrap_or_default()
+    }
+
+    /// Returns when the message was last edited, if ever.
+    pub fn edit_date_time(&self) -> Option<DateTime<Utc>> {
+        from_timestamp(self.edit_date)
+    }
+}
+
+/// Returns the scheduling state of a message sent at the date and time.
+pub fn send_at<Tz: TimeZone>(date_time: &DateTime<Tz>) -> MessageSchedulingState {
+    MessageSchedulingState::SendAtDate(td_struct!(types::MessageSchedulingStateSendAtDate {
+        send_date: to_timestamp(date_time)
+    }))
+}
+
+/// Returns the options to send a message at the date and time, within 367
+/// days.
+pub fn scheduled<Tz: TimeZone>(date_time: &DateTime<Tz>) -> types::MessageSendOptions {
+    types::MessageSendOptions {
+        scheduling_state: Some(send_at(date_time)),
+        ..Default::default()
+    }
+}
+
+/// Mute the notifications of the chat until the date and time.
+pub async fn mute_until<Tz: TimeZone>(
+    chat_id: ChatId,
+    until: &DateTime<Tz>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    let mut settings = chat.notification_settings;
+    settings.use_default_mute_for = false;
+    settings.mute_for = seconds_until(until);
+    functions::set_chat_notification_settings(chat_id, settings, client_id).await?;
+    Ok(())
+}
+
+/// Set how long the members of the chat must wait between their messages,
+/// one of 0, 10, 30, 60, 300, 900 or 3600 seconds.
+pub async fn set_slow_mode_delay(
+    chat_id: ChatId,
+    delay: TimeDelta,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let delay = delay.num_seconds().clamp(0, i32::MAX.into()) as i32;
+    functions::set_chat_slow_mode_delay(chat_id, delay, client_id).await?;
+    Ok(())
+}
+
+/// Ban the member from the chat until the date and time, removing their
+/// messages if `revoke_messages`.
+pub async fn ban_until<Tz: TimeZone>(
+    chat_id: ChatId,
+    member_id: MessageSender,
+    until: &DateTime<Tz>,
+    revoke_messages: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::ban_chat_member(
+        chat_id,
+        member_id,
+        to_timestamp(until),
+        revoke_messages,
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Facilities to investigate the behaviour of the library, for example to
+//! attach a trace of the received updates to a bug report.
+use crate::redact;
+use once_cell::sync::Lazy;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The size after which the dump of [`dump_updates`] is rotated.
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// The number of rotated dumps kept by [`dump_updates`].
+pub const DEFAULT_MAX_FILES: usize = 4;
+
+struct Dump {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl Dump {
+    fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    /// Renames `path` to `path.1`, `path.1` to `path.2` and so on, removing
+    /// the oldest dump, then starts a new one.
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    fs::rename(from, self.rotated_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        *self = Dump::open(self.path.clone(), self.max_bytes, self.max_files)?;
+        Ok(())
+    }
+
+    fn write(&mut self, json: &str, client_id: i32) -> io::Result<()> {
+        if self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let line = format!(
+            "{{\"timestamp\":{:.3},\"client_id\":{},\"update\":{}}}\n",
+            timestamp,
+            client_id,
+            redact::redact(json)
+        );
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DUMP: Lazy<Mutex<Option<Dump>>> = Lazy::new(Mutex::default);
+
+/// Write every update received from now on to the file at `path`, as one
+/// JSON object per line with the `timestamp` in seconds, the `client_id` and
+/// the `update` as received from TdLib, including the updates which can't be
+/// deserialized, with the fields selected by the `redact` module redacted so
+/// that the dump can be shared. The file is appended to, and is rotated to `path.1`
+/// when it exceeds [`DEFAULT_MAX_BYTES`], keeping [`DEFAULT_MAX_FILES`] dumps.
+pub fn dump_updates(path: impl AsRef<Path>) -> io::Result<()> {
+    dump_updates_with_rotation(path, DEFAULT_MAX_BYTES, DEFAULT_MAX_FILES)
+}
+
+/// Same as [`dump_updates`], rotating the file when it exceeds `max_bytes`
+/// and keeping `max_files` rotated dumps.
+pub fn dump_updates_with_rotation(
+    path: impl AsRef<Path>,
+    max_bytes: u64,
+    max_files: usize,
+) -> io::Result<()> {
+    let dump = Dump::open(path.as_ref().to_path_buf(), max_bytes, max_files)?;
+    *DUMP.lock().unwrap_or_else(|e| e.into_inner()) = Some(dump);
+    ENABLED.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// Stop writing the received updates started with [`dump_updates`].
+pub fn stop_dump() {
+    ENABLED.store(false, Ordering::Release);
+    *DUMP.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Write the update to the dump, if enabled. The dump is stopped if it
+/// can't be written.
+pub(crate) fn tap(json: &str, client_id: i32) {
+    if !ENABLED.load(Ordering::Acquire) {
+        return;
+    }
+    let mut dump = DUMP.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(file) = dump.as_mut() {
+        if let Err(e) = file.write(json, client_id) {
+            log::warn!("Cannot write the dump of the updates: {}", e);
+            ENABLED.store(false, Ordering::Release);
+            *dump = None;
+        }
+    }
+}
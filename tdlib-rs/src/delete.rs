@@ -0,0 +1,127 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Delete messages and the history of the chats, built like
+//! `delete_messages(chat_id, message_ids).revoke().run(client_id)`.
+//!
+//! The messages are deleted only for the user of the client unless asked
+//! explicitly, and the deletions which TdLib would silently apply for every
+//! member of the chat fail instead.
+use crate::enums;
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, TdError};
+
+/// The maximum number of messages deleted by a single request.
+pub const MAX_DELETED_MESSAGES: usize = 100;
+
+/// Messages to delete, started by [`delete_messages`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeleteMessages {
+    chat_id: ChatId,
+    message_ids: Vec<MessageId>,
+    revoke: bool,
+}
+
+/// Delete the messages of the chat, only for the user of the client unless
+/// [`DeleteMessages::revoke`] is called.
+pub fn delete_messages(chat_id: ChatId, message_ids: Vec<MessageId>) -> DeleteMessages {
+    DeleteMessages {
+        chat_id,
+        message_ids,
+        revoke: false,
+    }
+}
+
+impl DeleteMessages {
+    /// Delete the messages for every member of the chat.
+    pub fn revoke(mut self) -> Self {
+        self.revoke = true;
+        self
+    }
+
+    /// Delete the messages, as many requests as needed. Without
+    /// [`DeleteMessages::revoke`], fails without deleting anything in the
+    /// supergroups, the channels and the secret chats, where the messages
+    /// are always deleted for every member.
+    pub async fn run(self, client_id: i32) -> Result<(), TdError> {
+        if !self.revoke {
+            let enums::Chat::Chat(chat) = functions::get_chat(self.chat_id, client_id).await?;
+            if chat.r#type.supergroup_id().is_some() || chat.r#type.secret_chat_id().is_some() {
+                return Err(TdError::invalid(
+                    400,
+                    "The messages of this chat can only be deleted for everyone",
+                ));
+            }
+        }
+
+        for message_ids in self.message_ids.chunks(MAX_DELETED_MESSAGES) {
+            functions::delete_messages(self.chat_id, message_ids.to_vec(), self.revoke, client_id)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// The history of a chat to delete, started by [`delete_chat_history`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeleteChatHistory {
+    chat_id: ChatId,
+    remove_from_chat_list: bool,
+    revoke: bool,
+}
+
+/// Delete every message of the chat, only for the user of the client unless
+/// [`DeleteChatHistory::for_everyone`] is called.
+pub fn delete_chat_history(chat_id: ChatId) -> DeleteChatHistory {
+    DeleteChatHistory {
+        chat_id,
+        remove_from_chat_list: false,
+        revoke: false,
+    }
+}
+
+impl DeleteChatHistory {
+    /// Delete the history for every member of the chat.
+    pub fn for_everyone(mut self) -> Self {
+        self.revoke = true;
+        self
+    }
+
+    /// Also remove the chat from the chat lists.
+    pub fn remove_from_chat_list(mut self) -> Self {
+        self.remove_from_chat_list = true;
+        self
+    }
+
+    /// Delete the history, failing without deleting anything if it can't be
+    /// deleted as asked.
+    pub async fn run(self, client_id: i32) -> Result<(), TdError> {
+        let enums::Chat::Chat(chat) = functions::get_chat(self.chat_id, client_id).await?;
+        if self.revoke && !chat.can_be_deleted_for_all_users {
+            return Err(TdError::invalid(
+                400,
+                "The history of this chat can't be deleted for everyone",
+            ));
+        }
+        if !self.revoke && !chat.can_be_deleted_only_for_self {
+            return Err(TdError::invalid(
+                400,
+                "The history of this chat can't be deleted only for self",
+            ));
+        }
+
+        functions::delete_chat_history(
+            self.chat_id,
+            self.remove_from_chat_list,
+            self.revoke,
+            client_id,
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The drafts of the chats, tracked from `updateChatDraftMessage`, helpers
+//! to set and clear them, and a [`DraftSync`] keeping the draft being
+//! edited in sync with the other devices of the user.
+//!
+//! When the draft changes on another device, the most recent one wins: the
+//! draft of the other device replaces the local one if it was saved after
+//! the last local edit. The local edits are protected while the user is
+//! typing, for 10 seconds after the last one by default, so that a draft
+//! received meanwhile doesn't replace the text being written.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::drafts::{DraftSync, SyncOutcome};
+//! use tdlib_rs::markdown;
+//!
+//! # async fn edit(client_id: i32) -> Result<(), tdlib_rs::TdError> {
+//! # let chat_id = Default::default();
+//! let mut draft = DraftSync::new(chat_id, client_id);
+//! draft.edit(markdown::parse("Hello").unwrap());
+//!
+//! // On every updateChatDraftMessage of the chat
+//! if draft.sync() == SyncOutcome::Applied {
+//!     println!("The draft is now {}", draft.text().text);
+//! }
+//!
+//! // When the user leaves the chat
+//! draft.save().await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::enums::{AuthorizationState, InputMessageContent, InputMessageReplyTo, Update};
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The draft of a chat sent by TdLib.
+struct Remote {
+    /// Incremented with every change, to find the ones not synced yet
+    version: u64,
+    /// When the draft was saved, or cleared
+    date: i32,
+    draft: Option<types::DraftMessage>,
+}
+
+static DRAFTS: Lazy<RwLock<HashMap<(i32, ChatId), Remote>>> = Lazy::new(RwLock::default);
+
+fn now() -> i32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i32
+}
+
+fn set_remote(client_id: i32, chat_id: ChatId, draft: Option<types::DraftMessage>) {
+    let mut drafts = DRAFTS.write().unwrap_or_else(|e| e.into_inner());
+    let remote = drafts.entry((client_id, chat_id)).or_insert(Remote {
+        version: 0,
+        date: 0,
+        draft: None,
+    });
+    remote.version += 1;
+    remote.date = draft.as_ref().map_or_else(now, |draft| draft.date);
+    remote.draft = draft;
+}
+
+/// Keep the drafts of the chats of the client up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::NewChat(update) => {
+            set_remote(client_id, update.chat.id, update.chat.draft_message.clone());
+        }
+        Update::ChatDraftMessage(update) => {
+            set_remote(client_id, update.chat_id, update.draft_message.clone());
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                DRAFTS
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .retain(|&(id, _), _| id != client_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the text of the draft, if it is a text.
+fn draft_text_of(draft: &types::DraftMessage) -> Option<&types::FormattedText> {
+    match &draft.input_message_text {
+        InputMessageContent::InputMessageText(content) => Some(&content.text),
+        _ => None,
+    }
+}
+
+/// Returns the draft of the chat, if any.
+pub fn draft(chat_id: ChatId, client_id: i32) -> Option<types::DraftMessage> {
+    DRAFTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(client_id, chat_id))?
+        .draft
+        .clone()
+}
+
+/// Returns the text of the draft of the chat, if any.
+pub fn draft_text(chat_id: ChatId, client_id: i32) -> Option<types::FormattedText> {
+    draft_text_of(&draft(chat_id, client_id)?).cloned()
+}
+
+/// Set the draft of the chat to the text, replying to a message if
+/// `reply_to`; an empty text without reply clears the draft.
+pub async fn set_draft(
+    chat_id: ChatId,
+    text: types::FormattedText,
+    reply_to: Option<InputMessageReplyTo>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    if text.text.is_empty() && reply_to.is_none() {
+        return clear_draft(chat_id, client_id).await;
+    }
+    let draft = td_struct!(types::DraftMessage {
+        reply_to,
+        date: 0,
+        input_message_text: InputMessageContent::InputMessageText(td_struct!(
+            types::InputMessageText {
+                text,
+                link_preview_options: None,
+                clear_draft: false
+            }
+        ))
+    });
+    functions::set_chat_draft_message(chat_id, 0, Some(draft), client_id).await?;
+    Ok(())
+}
+
+/// Clear the draft of the chat.
+pub async fn clear_draft(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    functions::set_chat_draft_message(chat_id, 0, None, client_id).await?;
+    Ok(())
+}
+
+/// What [`DraftSync::sync`] did with the draft of the chat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncOutcome {
+    /// The draft didn't change, or changed to the local text
+    Unchanged,
+    /// The draft of another device replaced the local text
+    Applied,
+    /// The draft of another device was ignored, the local text being more
+    /// recent or being edited
+    KeptLocal,
+}
+
+/// The draft of a chat being edited, kept in sync with the other devices.
+#[derive(Debug)]
+pub struct DraftSync {
+    chat_id: ChatId,
+    client_id: i32,
+    text: types::FormattedText,
+    reply_to: Option<InputMessageReplyTo>,
+    /// The version of the draft of TdLib last synced
+    version: u64,
+    /// When the text was last edited and not saved since, as a Unix
+    /// timestamp and an instant
+    edited: Option<(i32, Instant)>,
+    protection: Duration,
+}
+
+impl DraftSync {
+    /// Start editing the draft of the chat, from its current draft.
+    pub fn new(chat_id: ChatId, client_id: i32) -> Self {
+        let mut sync = Self {
+            chat_id,
+            client_id,
+            text: types::FormattedText::default(),
+            reply_to: None,
+            version: 0,
+            edited: None,
+            protection: Duration::from_secs(10),
+        };
+        sync.sync();
+        sync
+    }
+
+    /// Protect the local edits for `protection` after the last one.
+    pub fn protection(mut self, protection: Duration) -> Self {
+        self.protection = protection;
+        self
+    }
+
+    /// Returns the text of the draft.
+    pub fn text(&self) -> &types::FormattedText {
+        &self.text
+    }
+
+    /// Returns the message the draft replies to, if any.
+    pub fn reply_to(&self) -> Option<&InputMessageReplyTo> {
+        self.reply_to.as_ref()
+    }
+
+    /// Whether the text was edited since it was last saved or synced.
+    pub fn is_edited(&self) -> bool {
+        self.edited.is_some()
+    }
+
+    /// Replace the text of the draft, as edited by the user.
+    pub fn edit(&mut self, text: types::FormattedText) {
+        self.text = text;
+        self.edited = Some((now(), Instant::now()));
+    }
+
+    /// Set the message the draft replies to, as chosen by the user.
+    pub fn set_reply_to(&mut self, reply_to: Option<InputMessageReplyTo>) {
+        self.reply_to = reply_to;
+        self.edited = Some((now(), Instant::now()));
+    }
+
+    /// Apply the draft of the chat received from TdLib since the last sync,
+    /// unless the local one is more recent or being edited.
+    pub fn sync(&mut self) -> SyncOutcome {
+        let drafts = DRAFTS.read().unwrap_or_else(|e| e.into_inner());
+        let Some(remote) = drafts.get(&(self.client_id, self.chat_id)) else {
+            return SyncOutcome::Unchanged;
+        };
+        if remote.version == self.version {
+            return SyncOutcome::Unchanged;
+        }
+
+        let text = remote
+            .draft
+            .as_ref()
+            .and_then(draft_text_of)
+            .cloned()
+            .unwrap_or_default();
+        let reply_to = remote
+            .draft
+            .as_ref()
+            .and_then(|draft| draft.reply_to.clone());
+        if text == self.text && reply_to == self.reply_to {
+            self.version = remote.version;
+            self.edited = None;
+            return SyncOutcome::Unchanged;
+        }
+        if let Some((edit_date, edited_at)) = self.edited {
+            // Not synced, so that a more recent draft is applied once the
+            // protection expires
+            if edited_at.elapsed() < self.protection {
+                return SyncOutcome::KeptLocal;
+            }
+            if remote.date <= edit_date {
+                self.version = remote.version;
+                return SyncOutcome::KeptLocal;
+            }
+        }
+        self.version = remote.version;
+        self.text = text;
+        self.reply_to = reply_to;
+        self.edited = None;
+        SyncOutcome::Applied
+    }
+
+    /// Save the draft, sending it to the other devices; an empty text
+    /// without reply clears it. The draft is synced first, so that the one
+    /// of another device saved after the last local edit is kept instead.
+    pub async fn save(&mut self) -> Result<(), TdError> {
+        if self.sync() == SyncOutcome::Applied {
+            return Ok(());
+        }
+        set_draft(
+            self.chat_id,
+            self.text.clone(),
+            self.reply_to.clone(),
+            self.client_id,
+        )
+        .await?;
+        self.edited = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receive_draft(chat_id: ChatId, text: &str, date: i32, client_id: i32) {
+        let draft = td_struct!(types::DraftMessage {
+            reply_to: None,
+            date,
+            input_message_text: InputMessageContent::InputMessageText(td_struct!(
+                types::InputMessageText {
+                    text: td_struct!(types::FormattedText {
+                        text: text.into(),
+                        entities: Vec::new()
+                    }),
+                    link_preview_options: None,
+                    clear_draft: false
+                }
+            ))
+        });
+        let update = Update::ChatDraftMessage(td_struct!(types::UpdateChatDraftMessage {
+            chat_id,
+            draft_message: Some(draft),
+            positions: Vec::new()
+        }));
+        observe(&update, client_id);
+    }
+
+    fn formatted(text: &str) -> types::FormattedText {
+        td_struct!(types::FormattedText {
+            text: text.into(),
+            entities: Vec::new()
+        })
+    }
+
+    #[test]
+    fn check_sync() {
+        let (chat_id, client_id) = (ChatId::default(), -100);
+        receive_draft(chat_id, "remote", now() - 60, client_id);
+        let mut draft = DraftSync::new(chat_id, client_id).protection(Duration::from_millis(50));
+        assert_eq!(draft.text().text, "remote");
+
+        // A newer draft is kept aside while the local text is edited
+        draft.edit(formatted("local"));
+        receive_draft(chat_id, "newer", now() + 60, client_id);
+        assert_eq!(draft.sync(), SyncOutcome::KeptLocal);
+        assert_eq!(draft.text().text, "local");
+
+        // And applied once the protection expires
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(draft.sync(), SyncOutcome::Applied);
+        assert_eq!(draft.text().text, "newer");
+        assert_eq!(draft.sync(), SyncOutcome::Unchanged);
+
+        // An older draft never replaces the local text
+        draft.edit(formatted("latest"));
+        std::thread::sleep(Duration::from_millis(60));
+        receive_draft(chat_id, "older", now() - 60, client_id);
+        assert_eq!(draft.sync(), SyncOutcome::KeptLocal);
+        assert_eq!(draft.sync(), SyncOutcome::Unchanged);
+        assert_eq!(draft.text().text, "latest");
+    }
+}
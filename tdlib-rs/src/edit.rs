@@ -0,0 +1,203 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Edit the messages, choosing the function of TdLib from the content of
+//! the message, with the text parsed according to an optional parse mode.
+//!
+//! The inline messages sent via a bot can be edited only by the bot, with
+//! `bots-only-api`.
+#[cfg(feature = "bots-only-api")]
+use crate::enums::ReplyMarkup;
+use crate::enums::{self, InputMessageContent, MessageContent, TextParseMode};
+use crate::{functions, types, TdError};
+
+/// Returns the text with its entities, parsed according to the mode if any.
+async fn formatted_text(
+    text: String,
+    parse_mode: Option<TextParseMode>,
+    client_id: i32,
+) -> Result<types::FormattedText, TdError> {
+    match parse_mode {
+        Some(mode) => {
+            let enums::FormattedText::FormattedText(text) =
+                functions::parse_text_entities(text, mode, client_id).await?;
+            Ok(text)
+        }
+        None => Ok(td_struct!(types::FormattedText {
+            text,
+            entities: Vec::new()
+        })),
+    }
+}
+
+fn text_content(
+    text: types::FormattedText,
+    link_preview_options: Option<types::LinkPreviewOptions>,
+) -> InputMessageContent {
+    InputMessageContent::InputMessageText(td_struct!(types::InputMessageText {
+        text,
+        link_preview_options,
+        clear_draft: false
+    }))
+}
+
+fn check_can_be_edited(message: &types::Message) -> Result<(), TdError> {
+    if message.can_be_edited {
+        Ok(())
+    } else {
+        Err(TdError::invalid(400, "Message can't be edited"))
+    }
+}
+
+/// Replace the text of a text message, or the caption of any other message,
+/// keeping the options of the link preview of a text message. Fails without
+/// a request if the message can't be edited.
+pub async fn edit(
+    message: &types::Message,
+    text: String,
+    parse_mode: Option<TextParseMode>,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    match &message.content {
+        MessageContent::MessageText(_) => edit_text(message, text, parse_mode, client_id).await,
+        _ => edit_caption(message, text, parse_mode, client_id).await,
+    }
+}
+
+/// Replace the text of a text message, keeping the options of its link
+/// preview.
+pub async fn edit_text(
+    message: &types::Message,
+    text: String,
+    parse_mode: Option<TextParseMode>,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    let link_preview_options = match &message.content {
+        MessageContent::MessageText(content) => content.link_preview_options.clone(),
+        _ => None,
+    };
+    edit_text_with_link_preview(message, text, parse_mode, link_preview_options, client_id).await
+}
+
+/// Replace the text of a text message and the options of its link preview,
+/// built with [`LinkPreviewBuilder`](crate::link_preview::LinkPreviewBuilder),
+/// or the default ones if `None`.
+pub async fn edit_text_with_link_preview(
+    message: &types::Message,
+    text: String,
+    parse_mode: Option<TextParseMode>,
+    link_preview_options: Option<types::LinkPreviewOptions>,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    check_can_be_edited(message)?;
+    let content = text_content(
+        formatted_text(text, parse_mode, client_id).await?,
+        link_preview_options,
+    );
+    #[cfg(feature = "bots-only-api")]
+    let message = functions::edit_message_text(
+        message.chat_id,
+        message.id,
+        message.reply_markup.clone(),
+        content,
+        client_id,
+    )
+    .await?;
+    #[cfg(not(feature = "bots-only-api"))]
+    let message =
+        functions::edit_message_text(message.chat_id, message.id, content, client_id).await?;
+    let enums::Message::Message(message) = message;
+    Ok(message)
+}
+
+/// Replace the caption of a media message.
+pub async fn edit_caption(
+    message: &types::Message,
+    caption: String,
+    parse_mode: Option<TextParseMode>,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    check_can_be_edited(message)?;
+    let caption = formatted_text(caption, parse_mode, client_id).await?;
+    #[cfg(feature = "bots-only-api")]
+    let message = functions::edit_message_caption(
+        message.chat_id,
+        message.id,
+        message.reply_markup.clone(),
+        Some(caption),
+        client_id,
+    )
+    .await?;
+    #[cfg(not(feature = "bots-only-api"))]
+    let message =
+        functions::edit_message_caption(message.chat_id, message.id, Some(caption), client_id)
+            .await?;
+    let enums::Message::Message(message) = message;
+    Ok(message)
+}
+
+/// Replace the reply markup of a message sent by the bot, or remove it if
+/// `None`.
+#[cfg(feature = "bots-only-api")]
+pub async fn edit_reply_markup(
+    message: &types::Message,
+    reply_markup: Option<ReplyMarkup>,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    check_can_be_edited(message)?;
+    let enums::Message::Message(message) =
+        functions::edit_message_reply_markup(message.chat_id, message.id, reply_markup, client_id)
+            .await?;
+    Ok(message)
+}
+
+/// Replace the text of an inline text or game message, and its reply markup.
+#[cfg(feature = "bots-only-api")]
+pub async fn edit_inline_text(
+    inline_message_id: String,
+    text: String,
+    parse_mode: Option<TextParseMode>,
+    reply_markup: Option<ReplyMarkup>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let content = text_content(formatted_text(text, parse_mode, client_id).await?, None);
+    functions::edit_inline_message_text(inline_message_id, reply_markup, content, client_id)
+        .await?;
+    Ok(())
+}
+
+/// Replace the caption of an inline media message, and its reply markup.
+#[cfg(feature = "bots-only-api")]
+pub async fn edit_inline_caption(
+    inline_message_id: String,
+    caption: String,
+    parse_mode: Option<TextParseMode>,
+    reply_markup: Option<ReplyMarkup>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let caption = formatted_text(caption, parse_mode, client_id).await?;
+    functions::edit_inline_message_caption(
+        inline_message_id,
+        reply_markup,
+        Some(caption),
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Replace the reply markup of an inline message, or remove it if `None`.
+#[cfg(feature = "bots-only-api")]
+pub async fn edit_inline_reply_markup(
+    inline_message_id: String,
+    reply_markup: Option<ReplyMarkup>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::edit_inline_message_reply_markup(inline_message_id, reply_markup, client_id).await?;
+    Ok(())
+}
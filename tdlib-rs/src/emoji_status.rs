@@ -0,0 +1,93 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The emoji statuses shown next to the names of the Telegram Premium
+//! users, with their expiration, and the stickers to display them.
+use crate::enums;
+use crate::{custom_emoji, functions, types, TdError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Returns the emoji status of the user, unless expired. TdLib sends the
+/// user again once the status expires, but the cached users may still
+/// have it.
+pub fn emoji_status(user: &types::User) -> Option<&types::EmojiStatus> {
+    user.emoji_status
+        .as_ref()
+        .filter(|status| status.expiration_date == 0 || i64::from(status.expiration_date) > now())
+}
+
+/// Set the emoji status of the user to the custom emoji, until the duration
+/// has elapsed or forever if `None`.
+pub async fn set(
+    custom_emoji_id: i64,
+    duration: Option<Duration>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let expiration_date = match duration {
+        Some(duration) => {
+            i32::try_from(now().saturating_add_unsigned(duration.as_secs())).unwrap_or(i32::MAX)
+        }
+        None => 0,
+    };
+    let status = td_struct!(types::EmojiStatus {
+        custom_emoji_id,
+        expiration_date
+    });
+    functions::set_emoji_status(Some(status), client_id).await?;
+    Ok(())
+}
+
+/// Remove the emoji status of the user.
+pub async fn clear(client_id: i32) -> Result<(), TdError> {
+    functions::set_emoji_status(None, client_id).await?;
+    Ok(())
+}
+
+/// Returns the custom emoji suggested as emoji statuses, matching the theme
+/// of the application.
+pub async fn themed(client_id: i32) -> Result<Vec<i64>, TdError> {
+    let enums::EmojiStatuses::EmojiStatuses(statuses) =
+        functions::get_themed_emoji_statuses(client_id).await?;
+    Ok(statuses.custom_emoji_ids)
+}
+
+/// Returns the custom emoji recently used as emoji statuses.
+pub async fn recent(client_id: i32) -> Result<Vec<i64>, TdError> {
+    let enums::EmojiStatuses::EmojiStatuses(statuses) =
+        functions::get_recent_emoji_statuses(client_id).await?;
+    Ok(statuses.custom_emoji_ids)
+}
+
+/// Returns the custom emoji suggested by default as emoji statuses.
+pub async fn defaults(client_id: i32) -> Result<Vec<i64>, TdError> {
+    let enums::EmojiStatuses::EmojiStatuses(statuses) =
+        functions::get_default_emoji_statuses(client_id).await?;
+    Ok(statuses.custom_emoji_ids)
+}
+
+/// Clear the custom emoji recently used as emoji statuses.
+pub async fn clear_recent(client_id: i32) -> Result<(), TdError> {
+    functions::clear_recent_emoji_statuses(client_id).await?;
+    Ok(())
+}
+
+/// Returns the sticker displaying the emoji status, from the cache of the
+/// custom emoji.
+pub async fn sticker(
+    status: &types::EmojiStatus,
+    client_id: i32,
+) -> Result<Option<types::Sticker>, TdError> {
+    custom_emoji::sticker(status.custom_emoji_id, client_id).await
+}
@@ -0,0 +1,112 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::compat::{TDLIB_SCHEMA_COMMIT, TDLIB_SCHEMA_VERSION};
+use crate::types;
+use std::fmt;
+
+impl fmt::Display for types::Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for types::Error {}
+
+/// The error returned by the library.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TdError {
+    /// An error returned by TdLib.
+    Td(types::Error),
+    /// The TdLib linked at runtime is not the one the code was generated from.
+    IncompatibleTdLib {
+        /// The version of the linked TdLib.
+        version: String,
+        /// The commit of the linked TdLib.
+        commit_hash: String,
+    },
+    /// An error returned by the keyring of the platform.
+    #[cfg(feature = "keyring")]
+    Keyring(::keyring::Error),
+    /// TdLib may or may not have changed the encryption key of the database,
+    /// so both keys are kept in the keyring, see
+    /// [`keyring::change_database_encryption_key`](crate::keyring::change_database_encryption_key).
+    #[cfg(feature = "keyring")]
+    UnconfirmedKeyChange(types::Error),
+    /// An error returned while reading or writing a file or a store.
+    Io(std::io::Error),
+}
+
+impl TdError {
+    /// Returns an error made by the library instead of TdLib, with the code
+    /// TdLib would have returned.
+    pub(crate) fn invalid(code: i32, message: impl Into<String>) -> Self {
+        TdError::Td(td_struct!(types::Error {
+            code,
+            message: message.into()
+        }))
+    }
+}
+
+impl fmt::Display for TdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TdError::Td(e) => write!(f, "TdLib error {}", e),
+            TdError::IncompatibleTdLib {
+                version,
+                commit_hash,
+            } => write!(
+                f,
+                "TdLib {} ({}) is linked, but the code was generated from TdLib {} ({})",
+                version, commit_hash, TDLIB_SCHEMA_VERSION, TDLIB_SCHEMA_COMMIT
+            ),
+            #[cfg(feature = "keyring")]
+            TdError::Keyring(e) => write!(f, "Keyring error {}", e),
+            #[cfg(feature = "keyring")]
+            TdError::UnconfirmedKeyChange(e) => write!(
+                f,
+                "The encryption key of the database may not have been changed: {}",
+                e
+            ),
+            TdError::Io(e) => write!(f, "I/O error {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TdError::Td(e) => Some(e),
+            TdError::IncompatibleTdLib { .. } => None,
+            #[cfg(feature = "keyring")]
+            TdError::Keyring(e) => Some(e),
+            #[cfg(feature = "keyring")]
+            TdError::UnconfirmedKeyChange(e) => Some(e),
+            TdError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<types::Error> for TdError {
+    fn from(error: types::Error) -> Self {
+        TdError::Td(error)
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl From<::keyring::Error> for TdError {
+    fn from(error: ::keyring::Error) -> Self {
+        TdError::Keyring(error)
+    }
+}
+
+impl From<std::io::Error> for TdError {
+    fn from(error: std::io::Error) -> Self {
+        TdError::Io(error)
+    }
+}
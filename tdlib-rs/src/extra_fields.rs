@@ -0,0 +1,29 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+/// Deserialize the fields of an object which are not known to the schema.
+/// The TDLib specific fields (`@type`, `@extra` and `@client_id`) are
+/// skipped since they are already handled elsewhere.
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Map<String, Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut fields = Map::deserialize(deserializer)?;
+    fields.retain(|key, _| !key.starts_with('@'));
+
+    if !fields.is_empty() {
+        log::debug!(
+            "Received fields unknown to the schema: {:?}",
+            fields.keys().collect::<Vec<_>>()
+        );
+    }
+
+    Ok(fields)
+}
@@ -0,0 +1,218 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Keep the size of the downloaded files under a quota, deleting the least
+//! recently used ones.
+//!
+//! The files downloaded are tracked from `updateFile`, used when their
+//! download completes or when marked with [`touch`], while their total size
+//! is read from [`storage::fast_report`], so that the files downloaded
+//! before the tracking started count towards the quota. Only the tracked
+//! files are deleted, by a [`FileGc`] while [`FileGc::run`] is awaited.
+//!
+//! The files used recently and the one downloaded last are never
+//! deleted, and no file is deleted when the files not tracked already
+//! exceed the quota, since deleting the tracked ones would not be enough.
+use crate::enums::Update;
+use crate::ids::FileId;
+use crate::{functions, runtime, storage, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+#[derive(Clone, Copy, Debug)]
+struct Downloaded {
+    size: i64,
+    last_used: Instant,
+    completed: Instant,
+}
+
+static DOWNLOADED: Lazy<Mutex<HashMap<i32, HashMap<FileId, Downloaded>>>> =
+    Lazy::new(Mutex::default);
+
+static COMPLETED: Notify = Notify::const_new();
+
+/// Track the files downloaded by the client.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::File(update) = update {
+        let file = &update.file;
+        let mut downloaded = DOWNLOADED.lock().unwrap_or_else(|e| e.into_inner());
+        let files = downloaded.entry(client_id).or_default();
+        if file.local.is_downloading_completed {
+            let size = file.local.downloaded_size;
+            let now = Instant::now();
+            let completed = files.insert(
+                file.id,
+                Downloaded {
+                    size,
+                    last_used: now,
+                    completed: now,
+                },
+            );
+            drop(downloaded);
+            if completed.is_none() {
+                COMPLETED.notify_waiters();
+            }
+        } else if file.local.downloaded_size == 0 {
+            files.remove(&file.id);
+        }
+    }
+}
+
+/// Mark the downloaded file as used now, so that it is deleted after the
+/// files used before it.
+pub fn touch(file_id: FileId, client_id: i32) {
+    let mut downloaded = DOWNLOADED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(file) = downloaded
+        .get_mut(&client_id)
+        .and_then(|files| files.get_mut(&file_id))
+    {
+        file.last_used = Instant::now();
+    }
+}
+
+/// Returns the total size of the files downloaded by the client since the
+/// tracking started, in bytes.
+pub fn tracked_size(client_id: i32) -> i64 {
+    DOWNLOADED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .map_or(0, |files| files.values().map(|file| file.size).sum())
+}
+
+/// Returns the files downloaded by the client which can be deleted, the
+/// least recently used first: the ones not used for `min_age`, but the last
+/// one downloaded.
+fn evictable(client_id: i32, min_age: Duration) -> Vec<(FileId, i64)> {
+    let downloaded = DOWNLOADED.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(files) = downloaded.get(&client_id) else {
+        return Vec::new();
+    };
+    let latest = files
+        .iter()
+        .max_by_key(|(_, file)| file.completed)
+        .map(|(&file_id, _)| file_id);
+    let mut evictable = files
+        .iter()
+        .filter(|&(&file_id, file)| Some(file_id) != latest && file.last_used.elapsed() >= min_age)
+        .map(|(&file_id, file)| (file.last_used, file_id, file.size))
+        .collect::<Vec<_>>();
+    evictable.sort_by_key(|&(last_used, ..)| last_used);
+    evictable
+        .into_iter()
+        .map(|(_, file_id, size)| (file_id, size))
+        .collect()
+}
+
+fn forget(file_id: FileId, client_id: i32) {
+    if let Some(files) = DOWNLOADED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(&client_id)
+    {
+        files.remove(&file_id);
+    }
+}
+
+/// A collector of the downloaded files of a client, over a quota.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FileGc {
+    client_id: i32,
+    quota: i64,
+    interval: Duration,
+    min_age: Duration,
+}
+
+impl FileGc {
+    /// Start a collector keeping the size of the files of the client under
+    /// the quota, in bytes, checked after each download and every minute,
+    /// deleting the files not used for 5 minutes.
+    pub fn new(quota: i64, client_id: i32) -> Self {
+        Self {
+            client_id,
+            quota,
+            interval: Duration::from_secs(60),
+            min_age: Duration::from_secs(300),
+        }
+    }
+
+    /// Only delete the files not used for `min_age`, since their download
+    /// or since they were marked with [`touch`].
+    pub fn min_age(mut self, min_age: Duration) -> Self {
+        self.min_age = min_age;
+        self
+    }
+
+    /// Check the size of the files every `interval`, not only after the
+    /// downloads.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Delete the least recently used files until their size is under the
+    /// quota; returns the bytes freed. Nothing is deleted if the quota can't
+    /// be reached by deleting the files which can be.
+    pub async fn collect(&self) -> Result<i64, TdError> {
+        let size = storage::fast_report(self.client_id).await?.files.size;
+        let excess = size - self.quota;
+        if excess <= 0 {
+            return Ok(0);
+        }
+        let evictable = evictable(self.client_id, self.min_age);
+        let evictable_size = evictable.iter().map(|&(_, size)| size).sum::<i64>();
+        if evictable_size < excess {
+            log::warn!(
+                "The files of the client {} exceed the quota by {} bytes, but only {} can be deleted",
+                self.client_id,
+                excess,
+                evictable_size
+            );
+            return Ok(0);
+        }
+
+        let mut freed = 0;
+        for (file_id, file_size) in evictable {
+            if freed >= excess {
+                break;
+            }
+            // Still tracked if not deleted, to be evicted by a later run
+            functions::delete_file(file_id, self.client_id).await?;
+            forget(file_id, self.client_id);
+            freed += file_size;
+        }
+        Ok(freed)
+    }
+
+    /// Collect the files after each download and every interval, until the
+    /// future is dropped.
+    pub async fn run(&self) {
+        loop {
+            let mut completed = pin!(COMPLETED.notified());
+            completed.as_mut().enable();
+            if let Err(e) = self.collect().await {
+                log::warn!("Failed to collect the downloaded files: {}", e);
+            }
+
+            let mut sleep = pin!(runtime::sleep(self.interval));
+            poll_fn(|cx| {
+                if completed.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(());
+                }
+                sleep.as_mut().poll(cx)
+            })
+            .await;
+        }
+    }
+}
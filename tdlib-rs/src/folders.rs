@@ -0,0 +1,183 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The chat folders of each client, tracked from `updateChatFolders`, and
+//! helpers to manage them and their invite links, for the clients showing
+//! the folders as tabs.
+use crate::batch::Batch;
+use crate::enums::{self, Update};
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// The chat folders of a client, as last sent by `updateChatFolders`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChatFolders {
+    /// The folders, in the order in which they are shown.
+    pub folders: Vec<types::ChatFolderInfo>,
+    /// The position of the main chat list among the folders, 0-based.
+    pub main_chat_list_position: i32,
+    /// `true` if the folder tags are enabled.
+    pub are_tags_enabled: bool,
+}
+
+/// A chat folder with its pinned and included chats.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Folder {
+    /// The identifier of the folder.
+    pub id: i32,
+    /// The folder, as returned by `getChatFolder`.
+    pub folder: types::ChatFolder,
+    /// The pinned chats of the folder, in order.
+    pub pinned_chats: Vec<types::Chat>,
+    /// The other chats explicitly included in the folder.
+    pub included_chats: Vec<types::Chat>,
+}
+
+static FOLDERS: Lazy<RwLock<HashMap<i32, ChatFolders>>> = Lazy::new(RwLock::default);
+
+/// Returns the chat folders of the client, empty until TdLib sends them.
+pub fn chat_folders(client_id: i32) -> ChatFolders {
+    FOLDERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Keep the chat folders of the client up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::ChatFolders(update) = update {
+        let folders = ChatFolders {
+            folders: update.chat_folders.clone(),
+            main_chat_list_position: update.main_chat_list_position,
+            are_tags_enabled: update.are_tags_enabled,
+        };
+        FOLDERS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(client_id, folders);
+    }
+}
+
+/// Request the chats concurrently, in the given order.
+async fn get_chats(chat_ids: &[ChatId], client_id: i32) -> Result<Vec<types::Chat>, TdError> {
+    let mut batch = Batch::new();
+    for &chat_id in chat_ids {
+        batch.push(functions::get_chat(chat_id, client_id));
+    }
+    batch
+        .run()
+        .await
+        .into_iter()
+        .map(|chat| {
+            let enums::Chat::Chat(chat) = chat?;
+            Ok(chat)
+        })
+        .collect()
+}
+
+/// Returns the folder with its pinned and included chats.
+pub async fn folder(chat_folder_id: i32, client_id: i32) -> Result<Folder, TdError> {
+    let enums::ChatFolder::ChatFolder(folder) =
+        functions::get_chat_folder(chat_folder_id, client_id).await?;
+    let pinned_chats = get_chats(&folder.pinned_chat_ids, client_id).await?;
+    let included_chats = get_chats(&folder.included_chat_ids, client_id).await?;
+    Ok(Folder {
+        id: chat_folder_id,
+        folder,
+        pinned_chats,
+        included_chats,
+    })
+}
+
+/// Returns every folder of the client, in order, with their chats.
+pub async fn folders(client_id: i32) -> Result<Vec<Folder>, TdError> {
+    let mut folders = Vec::new();
+    for info in chat_folders(client_id).folders {
+        folders.push(folder(info.id, client_id).await?);
+    }
+    Ok(folders)
+}
+
+/// Create a folder, returning its information.
+pub async fn create(
+    folder: types::ChatFolder,
+    client_id: i32,
+) -> Result<types::ChatFolderInfo, TdError> {
+    let enums::ChatFolderInfo::ChatFolderInfo(info) =
+        functions::create_chat_folder(folder, client_id).await?;
+    Ok(info)
+}
+
+/// Replace a folder, returning its new information.
+pub async fn edit(
+    chat_folder_id: i32,
+    folder: types::ChatFolder,
+    client_id: i32,
+) -> Result<types::ChatFolderInfo, TdError> {
+    let enums::ChatFolderInfo::ChatFolderInfo(info) =
+        functions::edit_chat_folder(chat_folder_id, folder, client_id).await?;
+    Ok(info)
+}
+
+/// Delete a folder, leaving the given chats.
+pub async fn delete(
+    chat_folder_id: i32,
+    leave_chat_ids: Vec<ChatId>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::delete_chat_folder(chat_folder_id, leave_chat_ids, client_id).await?;
+    Ok(())
+}
+
+/// Create an invite link to a shareable folder, for the given chats.
+pub async fn create_invite_link(
+    chat_folder_id: i32,
+    name: String,
+    chat_ids: Vec<ChatId>,
+    client_id: i32,
+) -> Result<types::ChatFolderInviteLink, TdError> {
+    let enums::ChatFolderInviteLink::ChatFolderInviteLink(link) =
+        functions::create_chat_folder_invite_link(chat_folder_id, name, chat_ids, client_id)
+            .await?;
+    Ok(link)
+}
+
+/// Returns the invite links created by the client for a folder.
+pub async fn invite_links(
+    chat_folder_id: i32,
+    client_id: i32,
+) -> Result<Vec<types::ChatFolderInviteLink>, TdError> {
+    let enums::ChatFolderInviteLinks::ChatFolderInviteLinks(links) =
+        functions::get_chat_folder_invite_links(chat_folder_id, client_id).await?;
+    Ok(links.invite_links)
+}
+
+/// Returns the folder of an invite link and its chats.
+pub async fn check_invite_link(
+    invite_link: String,
+    client_id: i32,
+) -> Result<types::ChatFolderInviteLinkInfo, TdError> {
+    let enums::ChatFolderInviteLinkInfo::ChatFolderInviteLinkInfo(info) =
+        functions::check_chat_folder_invite_link(invite_link, client_id).await?;
+    Ok(info)
+}
+
+/// Add the folder of an invite link, joining the given chats.
+pub async fn add_by_invite_link(
+    invite_link: String,
+    chat_ids: Vec<ChatId>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::add_chat_folder_by_invite_link(invite_link, chat_ids, client_id).await?;
+    Ok(())
+}
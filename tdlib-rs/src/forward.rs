@@ -0,0 +1,195 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Forward and copy messages to another chat, built like
+//! `forward(from_chat_id, message_ids, chat_id).drop_author().send(client_id)`.
+use crate::enums::{self, InputMessageContent, InputMessageReplyTo};
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, rate_limit, types, TdError};
+
+/// The maximum number of messages forwarded by a single request.
+pub const MAX_FORWARDED_MESSAGES: usize = 100;
+
+/// Messages to forward, started by [`forward`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forward {
+    chat_id: ChatId,
+    message_thread_id: i64,
+    from_chat_id: ChatId,
+    message_ids: Vec<MessageId>,
+    options: Option<types::MessageSendOptions>,
+    send_copy: bool,
+    remove_caption: bool,
+}
+
+/// Forward the messages of `from_chat_id` to `chat_id`.
+pub fn forward(from_chat_id: ChatId, message_ids: Vec<MessageId>, chat_id: ChatId) -> Forward {
+    Forward {
+        chat_id,
+        message_thread_id: 0,
+        from_chat_id,
+        message_ids,
+        options: None,
+        send_copy: false,
+        remove_caption: false,
+    }
+}
+
+impl Forward {
+    /// Forward the messages to a thread of the chat.
+    pub fn in_thread(mut self, message_thread_id: i64) -> Self {
+        self.message_thread_id = message_thread_id;
+        self
+    }
+
+    /// Send the messages with the options instead of the default ones.
+    pub fn options(mut self, options: types::MessageSendOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Schedule the messages to be sent at the date and time.
+    #[cfg(feature = "chrono")]
+    pub fn schedule_at<Tz: chrono::TimeZone>(mut self, date_time: &chrono::DateTime<Tz>) -> Self {
+        self.options
+            .get_or_insert_with(Default::default)
+            .scheduling_state = Some(crate::dates::send_at(date_time));
+        self
+    }
+
+    /// Send copies of the messages, without reference to their sender.
+    pub fn drop_author(mut self) -> Self {
+        self.send_copy = true;
+        self
+    }
+
+    /// Remove the captions of the media of the copies; implies
+    /// [`Forward::drop_author`].
+    pub fn drop_captions(mut self) -> Self {
+        self.send_copy = true;
+        self.remove_caption = true;
+        self
+    }
+
+    /// Forward the messages, as many requests as needed, returning the new
+    /// messages in the increasing order of the identifiers of the forwarded
+    /// ones, with `None` for those which can't be forwarded. Each message is counted by the limiter enabled with
+    /// [`rate_limit::enable`] before its request is sent.
+    pub async fn send(mut self, client_id: i32) -> Result<Vec<Option<types::Message>>, TdError> {
+        self.message_ids.sort_unstable();
+        self.message_ids.dedup();
+
+        let mut messages = Vec::with_capacity(self.message_ids.len());
+        for message_ids in self.message_ids.chunks(MAX_FORWARDED_MESSAGES) {
+            for _ in message_ids {
+                rate_limit::acquire(self.chat_id, client_id).await;
+            }
+            let enums::Messages::Messages(forwarded) = functions::forward_messages(
+                self.chat_id,
+                self.message_thread_id,
+                self.from_chat_id,
+                message_ids.to_vec(),
+                self.options.clone(),
+                self.send_copy,
+                self.remove_caption,
+                client_id,
+            )
+            .await?;
+            messages.extend(forwarded.messages);
+        }
+        Ok(messages)
+    }
+}
+
+/// A message to copy, started by [`copy`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Copy {
+    chat_id: ChatId,
+    message_thread_id: i64,
+    reply_to: Option<InputMessageReplyTo>,
+    options: Option<types::MessageSendOptions>,
+    from_chat_id: ChatId,
+    message_id: MessageId,
+    copy_options: types::MessageCopyOptions,
+}
+
+/// Copy the message of `from_chat_id` to `chat_id`, without reference to its
+/// sender.
+pub fn copy(from_chat_id: ChatId, message_id: MessageId, chat_id: ChatId) -> Copy {
+    Copy {
+        chat_id,
+        message_thread_id: 0,
+        reply_to: None,
+        options: None,
+        from_chat_id,
+        message_id,
+        copy_options: td_struct!(types::MessageCopyOptions {
+            send_copy: true,
+            replace_caption: false,
+            new_caption: None
+        }),
+    }
+}
+
+impl Copy {
+    /// Send the copy to a thread of the chat.
+    pub fn in_thread(mut self, message_thread_id: i64) -> Self {
+        self.message_thread_id = message_thread_id;
+        self
+    }
+
+    /// Send the copy as a reply.
+    pub fn reply_to(mut self, reply_to: InputMessageReplyTo) -> Self {
+        self.reply_to = Some(reply_to);
+        self
+    }
+
+    /// Send the copy with the options instead of the default ones.
+    pub fn options(mut self, options: types::MessageSendOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Schedule the copy to be sent at the date and time.
+    #[cfg(feature = "chrono")]
+    pub fn schedule_at<Tz: chrono::TimeZone>(mut self, date_time: &chrono::DateTime<Tz>) -> Self {
+        self.options
+            .get_or_insert_with(Default::default)
+            .scheduling_state = Some(crate::dates::send_at(date_time));
+        self
+    }
+
+    /// Replace the caption of the media, or remove it if `None`.
+    pub fn caption(mut self, caption: Option<types::FormattedText>) -> Self {
+        self.copy_options.replace_caption = true;
+        self.copy_options.new_caption = caption;
+        self
+    }
+
+    /// Send the copy, returning the new message.
+    pub async fn send(self, client_id: i32) -> Result<types::Message, TdError> {
+        let content =
+            InputMessageContent::InputMessageForwarded(td_struct!(types::InputMessageForwarded {
+                from_chat_id: self.from_chat_id,
+                message_id: self.message_id,
+                in_game_share: false,
+                copy_options: Some(self.copy_options)
+            }));
+        let enums::Message::Message(message) = rate_limit::send_message(
+            self.chat_id,
+            self.message_thread_id,
+            self.reply_to,
+            self.options,
+            None,
+            content,
+            client_id,
+        )
+        .await?;
+        Ok(message)
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Send the HTML5 games of the bots and open them, and with
+//! `bots-only-api` keep their high score tables.
+use crate::enums::{self, CallbackQueryPayload, InputMessageContent};
+use crate::ids::{ChatId, MessageId, UserId};
+use crate::{functions, rate_limit, types, TdError};
+
+/// Returns the content of a message with the game of the bot.
+pub fn game(bot_user_id: UserId, game_short_name: impl Into<String>) -> InputMessageContent {
+    InputMessageContent::InputMessageGame(td_struct!(types::InputMessageGame {
+        bot_user_id,
+        game_short_name: game_short_name.into()
+    }))
+}
+
+/// Send a message with the game of the bot. Games are not supported in
+/// channels and secret chats.
+pub async fn send_game(
+    chat_id: ChatId,
+    bot_user_id: UserId,
+    game_short_name: impl Into<String>,
+    reply_to: Option<enums::InputMessageReplyTo>,
+    #[cfg(feature = "bots-only-api")] reply_markup: Option<enums::ReplyMarkup>,
+    client_id: i32,
+) -> Result<enums::Message, TdError> {
+    let content = game(bot_user_id, game_short_name);
+    #[cfg(not(feature = "bots-only-api"))]
+    let reply_markup = None;
+    let message =
+        rate_limit::send_message(chat_id, 0, reply_to, None, reply_markup, content, client_id)
+            .await?;
+    Ok(message)
+}
+
+/// Press the button of a message with a game, returning the URL of the game
+/// to open.
+pub async fn open_game(
+    chat_id: ChatId,
+    message_id: MessageId,
+    game_short_name: impl Into<String>,
+    client_id: i32,
+) -> Result<String, TdError> {
+    let payload = CallbackQueryPayload::Game(td_struct!(types::CallbackQueryPayloadGame {
+        game_short_name: game_short_name.into()
+    }));
+    let enums::CallbackQueryAnswer::CallbackQueryAnswer(answer) =
+        functions::get_callback_query_answer(chat_id, message_id, payload, client_id).await?;
+    Ok(answer.url)
+}
+
+/// The message with a game, sent by the bot either to a chat or with the
+/// inline mode.
+#[cfg(feature = "bots-only-api")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameMessage {
+    /// A message sent to a chat.
+    Message {
+        chat_id: ChatId,
+        message_id: MessageId,
+    },
+    /// An inline message, by its identifier.
+    Inline(String),
+}
+
+/// A new score of a user, set by [`GameScore::set`].
+#[cfg(feature = "bots-only-api")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameScore {
+    user_id: UserId,
+    score: i32,
+    force: bool,
+    edit_message: bool,
+}
+
+#[cfg(feature = "bots-only-api")]
+impl GameScore {
+    /// A new score of the user, which is set only if higher than the current
+    /// one and doesn't edit the message.
+    pub fn new(user_id: UserId, score: i32) -> Self {
+        Self {
+            user_id,
+            score,
+            force: false,
+            edit_message: false,
+        }
+    }
+
+    /// Set the score even if it's lower than the current one. A score of 0
+    /// removes the user from the high score table.
+    pub fn force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Edit the message with the new high score table.
+    pub fn edit_message(mut self) -> Self {
+        self.edit_message = true;
+        self
+    }
+
+    /// Set the score in the high score table of the game of the message.
+    pub async fn set(self, message: &GameMessage, client_id: i32) -> Result<(), TdError> {
+        match message {
+            GameMessage::Message {
+                chat_id,
+                message_id,
+            } => {
+                functions::set_game_score(
+                    *chat_id,
+                    *message_id,
+                    self.edit_message,
+                    self.user_id,
+                    self.score,
+                    self.force,
+                    client_id,
+                )
+                .await?;
+            }
+            GameMessage::Inline(inline_message_id) => {
+                functions::set_inline_game_score(
+                    inline_message_id.clone(),
+                    self.edit_message,
+                    self.user_id,
+                    self.score,
+                    self.force,
+                    client_id,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the high scores of the game of the message, around the user.
+#[cfg(feature = "bots-only-api")]
+pub async fn high_scores(
+    message: &GameMessage,
+    user_id: UserId,
+    client_id: i32,
+) -> Result<Vec<types::GameHighScore>, TdError> {
+    let enums::GameHighScores::GameHighScores(scores) = match message {
+        GameMessage::Message {
+            chat_id,
+            message_id,
+        } => functions::get_game_high_scores(*chat_id, *message_id, user_id, client_id).await?,
+        GameMessage::Inline(inline_message_id) => {
+            functions::get_inline_game_high_scores(inline_message_id.clone(), user_id, client_id)
+                .await?
+        }
+    };
+    Ok(scores.scores)
+}
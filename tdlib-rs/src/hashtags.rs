@@ -0,0 +1,123 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The hashtags, cashtags and mentions of the texts, read from their
+//! entities, and helpers to search the messages with them.
+use crate::enums::{self, SearchMessagesFilter, TextEntityType};
+use crate::ids::{ChatId, MessageId, UserId};
+use crate::{functions, types, utf16, TdError};
+
+/// Returns the parts of the text covered by the entities of the type.
+fn entity_texts(text: &types::FormattedText, r#type: &TextEntityType) -> Vec<String> {
+    text.entities
+        .iter()
+        .filter(|entity| entity.r#type == *r#type)
+        .filter_map(|entity| utf16::entity_text(text, entity).map(str::to_owned))
+        .collect()
+}
+
+/// Returns the hashtags of the text, with their `#`.
+pub fn hashtags(text: &types::FormattedText) -> Vec<String> {
+    entity_texts(text, &TextEntityType::Hashtag)
+}
+
+/// Returns the cashtags of the text, with their `$`.
+pub fn cashtags(text: &types::FormattedText) -> Vec<String> {
+    entity_texts(text, &TextEntityType::Cashtag)
+}
+
+/// Returns the mentions of the users by username in the text, with their
+/// `@`.
+pub fn mentions(text: &types::FormattedText) -> Vec<String> {
+    entity_texts(text, &TextEntityType::Mention)
+}
+
+/// Returns the users mentioned by name in the text, which have no username.
+pub fn mentioned_users(text: &types::FormattedText) -> Vec<UserId> {
+    text.entities
+        .iter()
+        .filter_map(|entity| match &entity.r#type {
+            TextEntityType::MentionName(mention) => Some(mention.user_id),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns at most `limit` messages of the chat with the hashtag or cashtag,
+/// the most recent first, starting from `from_message_id` or from the last
+/// message if 0.
+pub async fn search_hashtag(
+    chat_id: ChatId,
+    hashtag: String,
+    from_message_id: MessageId,
+    limit: i32,
+    client_id: i32,
+) -> Result<types::FoundChatMessages, TdError> {
+    let hashtag = if hashtag.starts_with(['#', '$']) {
+        hashtag
+    } else {
+        format!("#{hashtag}")
+    };
+    let enums::FoundChatMessages::FoundChatMessages(found) = functions::search_chat_messages(
+        chat_id,
+        hashtag,
+        None,
+        from_message_id,
+        0,
+        limit,
+        None,
+        0,
+        0,
+        client_id,
+    )
+    .await?;
+    Ok(found)
+}
+
+/// Returns at most `limit` messages of the chat mentioning the user, the
+/// most recent first, starting from `from_message_id` or from the last
+/// message if 0.
+pub async fn search_mentions(
+    chat_id: ChatId,
+    from_message_id: MessageId,
+    limit: i32,
+    client_id: i32,
+) -> Result<types::FoundChatMessages, TdError> {
+    let enums::FoundChatMessages::FoundChatMessages(found) = functions::search_chat_messages(
+        chat_id,
+        String::new(),
+        None,
+        from_message_id,
+        0,
+        limit,
+        Some(SearchMessagesFilter::Mention),
+        0,
+        0,
+        client_id,
+    )
+    .await?;
+    Ok(found)
+}
+
+/// Returns at most `limit` hashtags recently used by the user starting with
+/// the prefix, without their `#`.
+pub async fn recent_hashtags(
+    prefix: String,
+    limit: i32,
+    client_id: i32,
+) -> Result<Vec<String>, TdError> {
+    let enums::Hashtags::Hashtags(hashtags) =
+        functions::search_hashtags(prefix, limit, client_id).await?;
+    Ok(hashtags.hashtags)
+}
+
+/// Remove the hashtag from the hashtags recently used by the user.
+pub async fn remove_recent_hashtag(hashtag: String, client_id: i32) -> Result<(), TdError> {
+    functions::remove_recent_hashtag(hashtag, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,178 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Callbacks invoked by the library when something unexpected is received
+//! from TdLib, so that applications can handle it instead of losing it.
+use crate::redact;
+use once_cell::sync::Lazy;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+
+type DeserializationFailureHook = Arc<dyn Fn(&DeserializationFailure) + Send + Sync>;
+
+type OrphanedResponseHook = Arc<dyn Fn(&OrphanedResponse) + Send + Sync>;
+
+static DESERIALIZATION_FAILURE_HOOK: Lazy<RwLock<Option<DeserializationFailureHook>>> =
+    Lazy::new(RwLock::default);
+
+static ORPHANED_RESPONSE_HOOK: Lazy<RwLock<Option<OrphanedResponseHook>>> =
+    Lazy::new(RwLock::default);
+
+/// A JSON received from TdLib which could not be deserialized.
+#[derive(Debug)]
+pub struct DeserializationFailure<'a> {
+    /// The raw JSON received from TdLib
+    pub json: &'a str,
+    /// The name of the type the JSON was being deserialized into
+    pub type_name: &'static str,
+    /// The error returned by the deserializer
+    pub error: &'a (dyn Error + 'static),
+}
+
+/// Set the callback invoked whenever an update or a response received from
+/// TdLib fails to be deserialized, replacing the previous one. Without a
+/// callback the failure is only logged as a warning.
+pub fn set_deserialization_failure_hook<F>(hook: F)
+where
+    F: Fn(&DeserializationFailure) + Send + Sync + 'static,
+{
+    *DESERIALIZATION_FAILURE_HOOK
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Remove the callback set with [`set_deserialization_failure_hook`].
+pub fn remove_deserialization_failure_hook() {
+    *DESERIALIZATION_FAILURE_HOOK
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub(crate) fn deserialization_failure(
+    json: &str,
+    type_name: &'static str,
+    error: &(dyn Error + 'static),
+) {
+    let failure = DeserializationFailure {
+        json,
+        type_name,
+        error,
+    };
+
+    // Called once the lock is released, so that it can set another hook
+    let hook = DESERIALIZATION_FAILURE_HOOK
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    match hook {
+        Some(hook) => hook(&failure),
+        None => log::warn!(
+            "Received an unknown {}: {}\nReason: {}",
+            failure.type_name,
+            redact::redact(failure.json),
+            failure.error
+        ),
+    }
+}
+
+/// Why a response received from TdLib has no request waiting for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrphanReason {
+    /// The future of the request was dropped before the response.
+    Dropped,
+    /// No request was sent with the `@extra` of the response, or it timed
+    /// out before the response.
+    Unknown,
+}
+
+/// A response received from TdLib with no request waiting for it.
+#[derive(Debug)]
+pub struct OrphanedResponse<'a> {
+    /// The raw JSON received from TdLib
+    pub json: &'a str,
+    /// The `@extra` of the response
+    pub extra: u32,
+    /// The client the response was received for
+    pub client_id: i32,
+    /// Why no request was waiting for the response
+    pub reason: OrphanReason,
+}
+
+/// Set the callback invoked whenever a response is received from TdLib for
+/// a request which isn't waiting for it anymore, or which was never sent,
+/// replacing the previous one. Without a callback the response is only
+/// logged as a warning.
+pub fn set_orphaned_response_hook<F>(hook: F)
+where
+    F: Fn(&OrphanedResponse) + Send + Sync + 'static,
+{
+    *ORPHANED_RESPONSE_HOOK
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Remove the callback set with [`set_orphaned_response_hook`].
+pub fn remove_orphaned_response_hook() {
+    *ORPHANED_RESPONSE_HOOK
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub(crate) fn orphaned_response(json: &str, extra: u32, client_id: i32, reason: OrphanReason) {
+    let response = OrphanedResponse {
+        json,
+        extra,
+        client_id,
+        reason,
+    };
+
+    let hook = ORPHANED_RESPONSE_HOOK
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    match hook {
+        Some(hook) => hook(&response),
+        None => match response.reason {
+            OrphanReason::Dropped => log::warn!("Got a response of an unaccessible request"),
+            OrphanReason::Unknown => log::warn!("Got a response of an unknown request"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn check_hook_removing_itself() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        set_deserialization_failure_hook(|_| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            remove_deserialization_failure_hook();
+        });
+
+        let error = serde_json::from_str::<i32>("").unwrap_err();
+        deserialization_failure("", "i32", &error);
+        deserialization_failure("", "i32", &error);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn check_orphaned_hook_removing_itself() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        set_orphaned_response_hook(|_| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            remove_orphaned_response_hook();
+        });
+
+        orphaned_response("{}", 1, 0, OrphanReason::Unknown);
+        orphaned_response("{}", 1, 0, OrphanReason::Unknown);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The user of each client, fetched once and then kept up to date with the
+//! received updates, so that it can be compared against without a request.
+use crate::enums::{AuthorizationState, Update, UserType};
+use crate::ids::UserId;
+use crate::{enums, functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+static USERS: Lazy<RwLock<HashMap<i32, types::User>>> = Lazy::new(RwLock::default);
+
+/// Returns the user of the client, requesting it only the first time.
+pub async fn me(client_id: i32) -> Result<types::User, TdError> {
+    if let Some(user) = USERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+    {
+        return Ok(user.clone());
+    }
+
+    let enums::User::User(user) = functions::get_me(client_id).await?;
+    USERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(client_id, user.clone());
+    Ok(user)
+}
+
+/// Returns the identifier of the user of the client.
+pub async fn my_id(client_id: i32) -> Result<UserId, TdError> {
+    Ok(me(client_id).await?.id)
+}
+
+/// Returns `true` if the user of the client is a bot.
+pub async fn is_bot(client_id: i32) -> Result<bool, TdError> {
+    Ok(matches!(me(client_id).await?.r#type, UserType::Bot(_)))
+}
+
+/// Keep the user of the client up to date: replace it when it changes and
+/// forget it when the client logs out.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::User(update) => {
+            let mut users = USERS.write().unwrap_or_else(|e| e.into_inner());
+            if let Some(user) = users.get_mut(&client_id) {
+                if user.id == update.user.id {
+                    *user = update.user.clone();
+                }
+            }
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(
+                update.authorization_state,
+                AuthorizationState::LoggingOut
+                    | AuthorizationState::Closing
+                    | AuthorizationState::Closed
+            ) {
+                USERS
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&client_id);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The identifiers of chats, users, messages and files. With the `typed-ids`
+//! feature they are newtypes, otherwise they are aliases of the bare integers
+//! used by the generated code, so that the library can name them in both cases.
+#[cfg(feature = "typed-ids")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "typed-ids")]
+use std::fmt;
+
+/// Defines the identifier of an entity: a newtype around the integer, which
+/// is serialized exactly as the integer itself, or an alias of the integer.
+macro_rules! id_type {
+    ($(#[$doc:meta])* $name:ident($inner:ty)) => {
+        $(#[$doc])*
+        #[cfg(feature = "typed-ids")]
+        #[derive(
+            Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        $(#[$doc])*
+        #[cfg(not(feature = "typed-ids"))]
+        #[allow(dead_code)]
+        pub type $name = $inner;
+
+        #[cfg(feature = "typed-ids")]
+        impl From<$inner> for $name {
+            fn from(id: $inner) -> Self {
+                $name(id)
+            }
+        }
+
+        #[cfg(feature = "typed-ids")]
+        impl From<$name> for $inner {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        #[cfg(feature = "typed-ids")]
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// The identifier of a chat.
+    ChatId(i64)
+);
+
+id_type!(
+    /// The identifier of a user.
+    UserId(i64)
+);
+
+id_type!(
+    /// The identifier of a message.
+    MessageId(i64)
+);
+
+id_type!(
+    /// The identifier of a file.
+    FileId(i32)
+);
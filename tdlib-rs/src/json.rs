@@ -0,0 +1,69 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use serde::de::DeserializeOwned;
+
+/// The error returned by the JSON backend in use.
+#[cfg(not(feature = "simd-json"))]
+pub(crate) type Error = serde_json::Error;
+/// The error returned by the JSON backend in use.
+#[cfg(feature = "simd-json")]
+pub(crate) type Error = simd_json::Error;
+
+/// Deserialize an instance of type `T` from a string of JSON text,
+/// without building an intermediate `serde_json::Value`.
+#[cfg(not(feature = "simd-json"))]
+pub(crate) fn from_str<T: DeserializeOwned>(json: &str) -> Result<T, Error> {
+    serde_json::from_str(json)
+}
+
+/// Deserialize an instance of type `T` from a string of JSON text,
+/// without building an intermediate `serde_json::Value`.
+#[cfg(feature = "simd-json")]
+pub(crate) fn from_str<T: DeserializeOwned>(json: &str) -> Result<T, Error> {
+    // simd-json parses the input in place, so it needs its own copy
+    let mut bytes = json.as_bytes().to_vec();
+    simd_json::serde::from_slice(&mut bytes)
+}
+
+/// A JSON text exactly as received from TdLib.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RawJson(String);
+
+impl RawJson {
+    pub(crate) fn new(json: String) -> Self {
+        RawJson(json)
+    }
+
+    /// Returns the JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the JSON text, consuming the `RawJson`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for RawJson {
+    fn from(json: String) -> Self {
+        RawJson(json)
+    }
+}
+
+impl AsRef<str> for RawJson {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for RawJson {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
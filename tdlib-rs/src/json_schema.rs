@@ -0,0 +1,102 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Export the types and the functions of the [`schema`](crate::schema) as
+//! a JSON Schema, so that the services exchanging their JSON with an
+//! application built on the library can validate it and generate their
+//! clients.
+//!
+//! Every type and function is a definition named as its `@type`, and every
+//! abstract type, like `Update`, a definition matching one of its kinds.
+//! The 64-bit integers are strings, as in the JSON of TdLib.
+//!
+//! ```rust,no_run
+//! let schema = tdlib_rs::json_schema::document();
+//! std::fs::write("tdlib.schema.json", schema.to_string()).unwrap();
+//! ```
+use crate::schema::{self, Definition};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// The draft of JSON Schema the document follows.
+pub const DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/definitions/{}", name) })
+}
+
+/// Returns the schema of a value of the TL type.
+fn ty(ty: &str) -> Value {
+    if let Some(item) = ty
+        .strip_prefix("vector<")
+        .and_then(|ty| ty.strip_suffix('>'))
+    {
+        return json!({ "type": "array", "items": self::ty(item) });
+    }
+    match ty {
+        "Bool" => json!({ "type": "boolean" }),
+        "int32" | "int53" => json!({ "type": "integer" }),
+        "int64" => json!({ "type": "string", "pattern": "^-?[0-9]+$" }),
+        "double" => json!({ "type": "number" }),
+        "string" => json!({ "type": "string" }),
+        "bytes" => json!({ "type": "string", "contentEncoding": "base64" }),
+        name => reference(name),
+    }
+}
+
+/// Returns the schema of the object of the type or function.
+pub fn definition(definition: &Definition) -> Value {
+    let mut properties = Map::new();
+    properties.insert("@type".into(), json!({ "const": definition.name }));
+    let mut required = vec![Value::from("@type")];
+    for field in definition.fields {
+        let mut schema = ty(field.ty);
+        if field.optional {
+            schema = json!({ "anyOf": [schema, { "type": "null" }] });
+        } else {
+            required.push(field.name.into());
+        }
+        if let Value::Object(schema) = &mut schema {
+            schema.insert("description".into(), field.description.into());
+        }
+        properties.insert(field.name.into(), schema);
+    }
+    json!({
+        "type": "object",
+        "description": definition.description,
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// Returns the JSON Schema of every type and function of the schema.
+pub fn document() -> Value {
+    let mut definitions = Map::new();
+    let mut kinds: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+    for ty in schema::types() {
+        definitions.insert(ty.name.into(), definition(ty));
+        kinds.entry(ty.ty).or_default().push(reference(ty.name));
+    }
+    for (ty, kinds) in kinds {
+        definitions.insert(ty.into(), json!({ "oneOf": kinds }));
+    }
+    for function in schema::functions() {
+        let mut schema = definition(function);
+        if let Value::Object(schema) = &mut schema {
+            if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+                properties.insert("@extra".into(), json!({}));
+            }
+            schema.insert("x-result".into(), function.ty.into());
+        }
+        definitions.insert(function.name.into(), schema);
+    }
+    json!({
+        "$schema": DRAFT,
+        "definitions": definitions,
+    })
+}
@@ -0,0 +1,160 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Keep the encryption key of the TdLib database in the keyring of the
+//! platform (Secret Service, Keychain or Credential Manager) instead of the
+//! configuration of the application.
+//!
+//! The key of each database is stored under the [`SERVICE`] service, with the
+//! database directory as the account; it is generated on first use. The
+//! previous key is kept along with it while TdLib may or may not have
+//! changed the key of the database, see [`previous_database_encryption_key`].
+use crate::{functions, TdError};
+use ::keyring::Entry;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// The keyring service under which the keys are stored.
+pub const SERVICE: &str = "tdlib-rs";
+
+/// The length in bytes of the generated keys.
+const KEY_LENGTH: usize = 32;
+
+fn entry(database_directory: &str) -> Result<Entry, TdError> {
+    Ok(Entry::new(SERVICE, database_directory)?)
+}
+
+/// Returns the entry of the key used before the last change of unknown
+/// outcome.
+fn previous_entry(database_directory: &str) -> Result<Entry, TdError> {
+    Ok(Entry::new(
+        SERVICE,
+        &format!("{}#previous", database_directory),
+    )?)
+}
+
+fn password(entry: &Entry) -> Result<Option<String>, TdError> {
+    match entry.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(::keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn generate_key() -> Result<String, TdError> {
+    let mut key = [0; KEY_LENGTH];
+    getrandom::getrandom(&mut key).map_err(|e| ::keyring::Error::PlatformFailure(Box::new(e)))?;
+    Ok(STANDARD.encode(key))
+}
+
+/// Returns the encryption key of the database, as expected by TdLib, storing
+/// a newly generated one if the keyring has none.
+pub fn database_encryption_key(database_directory: &str) -> Result<String, TdError> {
+    let entry = entry(database_directory)?;
+    match entry.get_password() {
+        Ok(key) => Ok(key),
+        Err(::keyring::Error::NoEntry) => {
+            let key = generate_key()?;
+            entry.set_password(&key)?;
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Same as [`functions::set_tdlib_parameters`], but the encryption key of
+/// the database is the one stored in the keyring.
+#[allow(clippy::too_many_arguments)]
+pub async fn set_tdlib_parameters(
+    use_test_dc: bool,
+    database_directory: String,
+    files_directory: String,
+    use_file_database: bool,
+    use_chat_info_database: bool,
+    use_message_database: bool,
+    use_secret_chats: bool,
+    api_id: i32,
+    api_hash: String,
+    system_language_code: String,
+    device_model: String,
+    system_version: String,
+    application_version: String,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let database_encryption_key = database_encryption_key(&database_directory)?;
+    functions::set_tdlib_parameters(
+        use_test_dc,
+        database_directory,
+        files_directory,
+        database_encryption_key,
+        use_file_database,
+        use_chat_info_database,
+        use_message_database,
+        use_secret_chats,
+        api_id,
+        api_hash,
+        system_language_code,
+        device_model,
+        system_version,
+        application_version,
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Returns the key the database was encrypted with before the last change
+/// which failed with [`TdError::UnconfirmedKeyChange`], to open the database
+/// if that change wasn't applied by TdLib; `None` once a change succeeded.
+pub fn previous_database_encryption_key(
+    database_directory: &str,
+) -> Result<Option<String>, TdError> {
+    password(&previous_entry(database_directory)?)
+}
+
+/// Encrypt the database with a newly generated key, stored in the keyring
+/// before TdLib changes it, so that the key of the database is never lost.
+///
+/// The previous key is stored again only if TdLib rejects the change. If
+/// the outcome is unknown, for example because the request timed out or
+/// TdLib failed with an internal error, the new key is kept in the keyring
+/// and the previous one is returned by [`previous_database_encryption_key`],
+/// and [`TdError::UnconfirmedKeyChange`] is returned.
+pub async fn change_database_encryption_key(
+    database_directory: &str,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let entry = entry(database_directory)?;
+    let previous = password(&entry)?;
+    let key = generate_key()?;
+    entry.set_password(&key)?;
+
+    if let Err(e) = functions::set_database_encryption_key(key, client_id).await {
+        if !(400..500).contains(&e.code) {
+            // Without a stored key, the database wasn't encrypted
+            let previous = previous.as_deref().unwrap_or_default();
+            previous_entry(database_directory)?.set_password(previous)?;
+            return Err(TdError::UnconfirmedKeyChange(e));
+        }
+        let restored = match &previous {
+            Some(previous) => entry.set_password(previous),
+            None => entry.delete_credential(),
+        };
+        if let Err(restore_error) = restored {
+            log::error!(
+                "Failed to restore the encryption key of the database {} in the keyring: {}",
+                database_directory,
+                restore_error
+            );
+        }
+        return Err(e.into());
+    }
+    match previous_entry(database_directory)?.delete_credential() {
+        Ok(()) | Err(::keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
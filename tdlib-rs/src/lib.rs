@@ -7,22 +7,147 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
+#[macro_use]
+mod macros;
+
+mod accessors;
+pub mod account;
+pub mod action_bar;
+pub mod admin_log;
+pub mod archive;
+pub mod auto_delete;
+pub mod backgrounds;
+pub mod batch;
+pub mod block_list;
+pub mod boosts;
+#[cfg(feature = "bot-api")]
+pub mod bot_api;
 pub mod build;
+pub mod calls;
+pub mod chat_lists;
+pub mod client_api;
+pub mod client_options;
+mod compat;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod connection;
+pub mod content;
+pub mod custom_emoji;
+#[cfg(feature = "chrono")]
+pub mod dates;
+pub mod debug;
+pub mod delete;
+pub mod drafts;
+pub mod edit;
+pub mod emoji_status;
+mod error;
+#[cfg(feature = "extra-fields")]
+mod extra_fields;
+pub mod file_gc;
+pub mod folders;
+pub mod forward;
+pub mod games;
 mod generated;
+pub mod hashtags;
+pub mod hooks;
+pub mod identity;
+mod ids;
+mod json;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+#[cfg(feature = "keyring")]
+pub mod keyring;
+pub mod link_preview;
+pub mod links;
+pub mod markdown;
+pub mod names;
+pub mod notifications;
 mod observer;
+pub mod ordered;
+pub mod outbox;
+pub mod passport;
+pub mod payments;
+pub mod prelude;
+pub mod premium;
+pub mod priority;
+pub mod privacy;
+#[cfg(feature = "image")]
+mod probe;
+pub mod profile;
+pub mod profile_photo;
+pub mod quick_replies;
+pub mod rate_limit;
+pub mod redact;
+#[doc(hidden)]
+pub mod registry;
+pub mod render;
+pub mod response_cache;
+pub mod retry;
+mod runtime;
+pub mod saved_messages;
+pub mod schema;
+pub mod search;
+pub mod secret_chats;
+pub mod service_messages;
+pub mod sessions;
+pub mod single_flight;
+pub mod slow_requests;
+pub mod storage;
+pub mod tasks;
 mod tdjson;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod translate;
+pub mod unread;
+pub mod updates;
+pub mod usernames;
+pub mod utf16;
+pub mod views;
+pub mod web_apps;
 
+pub use compat::{check_compatibility, OnMismatch, TDLIB_SCHEMA_COMMIT, TDLIB_SCHEMA_VERSION};
+pub use error::TdError;
+#[cfg(feature = "blocking")]
+pub use generated::blocking;
+pub use generated::handler::UpdateHandler;
 pub use generated::{enums, functions, types};
+#[cfg(feature = "typed-ids")]
+pub use ids::{ChatId, FileId, MessageId, UserId};
+pub use json::RawJson;
 
 use enums::Update;
+use observer::Response;
 use once_cell::sync::Lazy;
-use serde_json::Value;
-use tokio::{sync::oneshot::error::TryRecvError, time::sleep};
-use std::{sync::atomic::{AtomicU32, Ordering}, time::Duration};
 use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    sync::{Mutex, TryLockError},
+    task::Poll,
+    time::{Duration, Instant},
+};
 
 static EXTRA_COUNTER: AtomicU32 = AtomicU32::new(0);
 static OBSERVER: Lazy<observer::Observer> = Lazy::new(observer::Observer::new);
+static RECEIVE_TIMEOUT_MS: AtomicU64 = AtomicU64::new(2000);
+
+/// Set how long [`receive`], [`receive_raw`] and the receive loop of the
+/// `updates` module wait for TdLib before returning `None`, 2 seconds by
+/// default. The responses and updates are returned as soon as received in
+/// any case: a shorter timeout only lets the concurrent calls to `receive`
+/// take turns sooner, at the cost of more wakeups while idle.
+pub fn set_receive_timeout(timeout: Duration) {
+    let timeout = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+    RECEIVE_TIMEOUT_MS.store(timeout, Ordering::Relaxed);
+}
+
+/// Returns the timeout set with [`set_receive_timeout`], in seconds.
+pub(crate) fn receive_timeout_secs() -> f64 {
+    RECEIVE_TIMEOUT_MS.load(Ordering::Relaxed) as f64 / 1000.0
+}
 
 /// Create a TdLib client returning its id. Note that to start receiving
 /// updates for a client you need to send at least a request with it first.
@@ -35,25 +160,158 @@ pub fn create_client() -> i32 {
 /// Note that to start receiving updates for a client you need to send
 /// at least a request with it first.
 pub fn receive() -> Option<(Update, i32)> {
-    let response = tdjson::receive(2.0);
+    check_receive_loop();
+    receive_timeout(receive_timeout_secs()).map(|(update, _, client_id)| (update, client_id))
+}
+
+/// Receive a single update or response from TdLib like [`receive`], but
+/// also return the JSON of the update exactly as received, for example to
+/// archive it.
+pub fn receive_raw() -> Option<(Update, RawJson, i32)> {
+    check_receive_loop();
+    receive_timeout(receive_timeout_secs())
+}
+
+/// Receive the updates from TdLib like [`receive`], waiting for the first
+/// one, then for the ones received at most `max_wait` after it, and
+/// returning at most `max_items` of them; empty if no update was received
+/// within the timeout of [`receive`].
+pub fn receive_batch(max_items: usize, max_wait: Duration) -> Vec<(Update, i32)> {
+    let max_items = max_items.max(1);
+    let mut updates = Vec::new();
+    let mut deadline: Option<Instant> = None;
+    while updates.len() < max_items {
+        let timeout = match deadline {
+            None => receive_timeout_secs(),
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                remaining.as_secs_f64()
+            }
+        };
+        check_receive_loop();
+        match receive_timeout(timeout) {
+            Some((update, _, client_id)) => {
+                updates.push((update, client_id));
+                deadline.get_or_insert_with(|| Instant::now() + max_wait);
+            }
+            // Like `receive`, give up if a response or nothing was received
+            // before the first update
+            None if deadline.is_none() => break,
+            None => {}
+        }
+    }
+    updates
+}
+
+/// Receive a single update or response from TdLib like [`receive`], but
+/// return immediately with `None` if nothing is pending, so that TdLib can be
+/// polled without ever blocking the current thread.
+pub fn try_receive() -> Option<(Update, i32)> {
+    check_receive_loop();
+    receive_timeout(0.0).map(|(update, _, client_id)| (update, client_id))
+}
+
+/// Warn once if the updates are received while the receive loop of the
+/// `updates` module is running, since each update is then received by
+/// only one of the two.
+fn check_receive_loop() {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    if updates::is_running() && !WARNED.swap(true, Ordering::Relaxed) {
+        log::warn!(
+            "Receiving while the receive loop of the `updates` module is running splits the \
+             updates between them, subscribe to the loop instead"
+        );
+    }
+}
+
+/// Pass the update to the modules keeping a state of the clients.
+fn observe(update: &Update, client_id: i32) {
+    identity::observe(update, client_id);
+    folders::observe(update, client_id);
+    quick_replies::observe(update, client_id);
+    saved_messages::observe(update, client_id);
+    secret_chats::observe(update, client_id);
+    calls::observe(update, client_id);
+    profile::observe(update, client_id);
+    backgrounds::observe(update, client_id);
+    outbox::observe(update, client_id);
+    connection::observe(update, client_id);
+    tasks::observe(update, client_id);
+    client_options::observe(update, client_id);
+    file_gc::observe(update, client_id);
+    response_cache::observe(update, client_id);
+    chat_lists::observe(update, client_id);
+    names::observe(update, client_id);
+    notifications::observe(update, client_id);
+    unread::observe(update, client_id);
+    drafts::observe(update, client_id);
+}
+
+/// Receive a single update or response from TdLib, waiting at most `timeout`
+/// seconds for it. TdLib must not be received from concurrently, so the
+/// concurrent calls wait for each other, but with a `timeout` of zero which
+/// returns `None` at once if another thread is receiving.
+pub(crate) fn receive_timeout(timeout: f64) -> Option<(Update, RawJson, i32)> {
+    static RECEIVING: Mutex<()> = Mutex::new(());
+    let _receiving = if timeout == 0.0 {
+        match RECEIVING.try_lock() {
+            Ok(receiving) => receiving,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        }
+    } else {
+        RECEIVING.lock().unwrap_or_else(|e| e.into_inner())
+    };
+
+    let response = tdjson::receive(timeout);
     if let Some(response_str) = response {
-        let response: Value = serde_json::from_str(&response_str).unwrap();
+        let envelope: Envelope = match json::from_str(&response_str) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                hooks::deserialization_failure(
+                    &response_str,
+                    std::any::type_name::<Envelope>(),
+                    &e,
+                );
+                // Don't leave waiting forever the request of the response
+                let value = serde_json::from_str::<Value>(&response_str).unwrap_or_default();
+                let extra = value["@extra"]
+                    .as_u64()
+                    .and_then(|extra| u32::try_from(extra).ok());
+                let client_id = value["@client_id"]
+                    .as_i64()
+                    .and_then(|id| i32::try_from(id).ok());
+                if let Some(extra) = extra {
+                    let client_id = client_id.unwrap_or_default();
+                    OBSERVER.notify(extra, client_id, Response::malformed());
+                }
+                return None;
+            }
+        };
 
-        match response.get("@extra") {
-            Some(_) => {
-                OBSERVER.notify(response);
+        match envelope.extra {
+            Some(extra) => {
+                OBSERVER.notify(
+                    extra,
+                    envelope.client_id,
+                    Response::new(envelope.ty, response_str),
+                );
             }
             None => {
-                let client_id = response["@client_id"].as_i64().unwrap() as i32;
-                match serde_json::from_value(response) {
+                debug::tap(&response_str, envelope.client_id);
+                match json::from_str(&response_str) {
                     Ok(update) => {
-                        return Some((update, client_id));
+                        observe(&update, envelope.client_id);
+                        return Some((update, RawJson::new(response_str), envelope.client_id));
                     }
                     Err(e) => {
-                        log::warn!(
-                            "Received an unknown response: {}\nReason: {}",
-                            response_str,
-                            e
+                        hooks::deserialization_failure(
+                            &response_str,
+                            std::any::type_name::<Update>(),
+                            &e,
                         );
                     }
                 }
@@ -64,41 +322,147 @@ pub fn receive() -> Option<(Update, i32)> {
     None
 }
 
+/// Receive a single update or response from TdLib like [`receive`] and, if
+/// it's an update, pass it to the [`UpdateHandler`]. Returns `true` if an
+/// update was handled.
+pub fn receive_and_handle<H: UpdateHandler + ?Sized>(handler: &mut H) -> bool {
+    match receive() {
+        Some((update, client_id)) => {
+            handler.handle_update(update, client_id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The fields needed to route a response received from TdLib, the others
+/// are skipped without being parsed. The full deserialization of a response
+/// is left to the function waiting for it.
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(rename = "@extra")]
+    extra: Option<u32>,
+    #[serde(rename = "@client_id")]
+    client_id: i32,
+    #[serde(rename = "@type")]
+    ty: String,
+}
+
 static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"retry after (\d+)").unwrap());
 
-pub(crate) async fn send_request(client_id: i32, mut request: Value) -> Value {
+pub(crate) async fn send_request(client_id: i32, request: Value) -> Response {
+    let slot = match response_cache::lookup(client_id, &request) {
+        Some(response_cache::Lookup::Hit(response)) => return response,
+        Some(response_cache::Lookup::Miss(slot)) => Some(slot),
+        None => None,
+    };
+    let response = send_request_coalesced(client_id, request).await;
+    if let Some(slot) = slot {
+        slot.store(&response);
+    }
+    response
+}
+
+async fn send_request_coalesced(client_id: i32, request: Value) -> Response {
+    loop {
+        match single_flight::join(client_id, &request) {
+            None => return send_request_once(client_id, request).await,
+            Some(single_flight::Flight::Leader(leader)) => {
+                let response = send_request_once(client_id, request).await;
+                leader.finish(&response);
+                return response;
+            }
+            Some(single_flight::Flight::Follower(receiver)) => {
+                // The identical request was dropped before its response
+                if let Ok(response) = receiver.await {
+                    return response;
+                }
+            }
+        }
+    }
+}
+
+/// Send the request to TdLib, or to the fake client with the identifier.
+fn send_to_td(client_id: i32, request: &Value) {
+    #[cfg(feature = "testing")]
+    if testing::send(client_id, request) {
+        return;
+    }
+    tdjson::send(client_id, request.to_string());
+}
+
+async fn send_request_once(client_id: i32, mut request: Value) -> Response {
+    // Read before the first await, while polled by the caller
+    let priority = priority::current();
+    let options = client_options::client_options(client_id);
+    let policy = options.retry_policy.unwrap_or_else(retry::policy);
+    let mut retries = 0;
     loop {
+        let permit = priority::acquire(client_id, priority).await;
         let extra = EXTRA_COUNTER.fetch_add(1, Ordering::Relaxed);
-        request["@extra"] = serde_json::to_value(extra).unwrap();
-
-        let mut receiver = OBSERVER.subscribe(extra);
-        tdjson::send(client_id, request.to_string());
-
-        loop {
-            match receiver.try_recv() {
-                Ok(v) => {
-                    // println!("req{:?} res{:?}",request,v);
-                    if v["code"].as_i64() == Some(429) {
-                        if let Some(message_reason) = v["message"].as_str() {
-                            if let Some(captures) = RE.captures(message_reason) {
-                                if let Some(second_str) = captures.get(1) {
-                                    let seconds = second_str.as_str().parse().unwrap();
-                                    println!("Wait for {} seconds", seconds);
-                                    sleep(Duration::from_secs(seconds)).await;
-                                    break;
-                                }
-                            }
-                        }
+        let Some(receiver) = OBSERVER.subscribe(extra) else {
+            log::warn!("The @extra {} is still pending, skipping it", extra);
+            continue;
+        };
+        request["@extra"] = Value::from(extra);
+
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("Sending request {}", redact::redact_value(&request));
+        }
+        send_to_td(client_id, &request);
+        let sent = Instant::now();
+
+        let response = match options.request_timeout {
+            Some(timeout) => {
+                let mut receiver = pin!(receiver);
+                let mut sleep = pin!(runtime::sleep(timeout));
+                poll_fn(|cx| {
+                    if let Poll::Ready(response) = receiver.as_mut().poll(cx) {
+                        return Poll::Ready(Some(
+                            response.unwrap_or_else(|_| Response::abandoned()),
+                        ));
                     }
-                    return v
-                }
-                Err(TryRecvError::Empty) => {
-                    sleep(Duration::from_millis(10)).await;
-                }
-                Err(TryRecvError::Closed) => {
-                    panic!("Closed");
+                    sleep.as_mut().poll(cx).map(|_| None)
+                })
+                .await
+            }
+            None => Some(receiver.await.unwrap_or_else(|_| Response::abandoned())),
+        };
+        let timed_out = response.is_none();
+        let response = response.unwrap_or_else(|| {
+            OBSERVER.unsubscribe(extra);
+            Response::timed_out()
+        });
+        drop(permit);
+        slow_requests::record(
+            request["@type"].as_str().unwrap_or_default(),
+            client_id,
+            sent.elapsed(),
+            options.slow_request_threshold,
+        );
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("Received response {}", redact::redact(response.json()));
+        }
+        // TdLib may still be running the request timed out, so it is never
+        // sent again
+        if response.is_error() && !timed_out {
+            let error = response.error();
+            if error.code == 429 {
+                let seconds = RE
+                    .captures(&error.message)
+                    .and_then(|captures| captures.get(1)?.as_str().parse().ok());
+                if let Some(seconds) = seconds {
+                    log::info!("Waiting {} seconds before retrying the request", seconds);
+                    runtime::sleep(Duration::from_secs(seconds)).await;
+                    continue;
                 }
             }
+            if error.is_retryable() && retries < policy.max_retries {
+                runtime::sleep(policy.backoff(retries)).await;
+                retries += 1;
+                continue;
+            }
         }
+        return response;
     }
 }
@@ -0,0 +1,73 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Build the options of the link previews of the text messages, used to
+//! send them with [`LinkPreviewBuilder::text`] or to edit them with
+//! [`edit::edit_text_with_link_preview`](crate::edit::edit_text_with_link_preview).
+use crate::enums::InputMessageContent;
+use crate::types;
+
+/// Build the options of a link preview.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinkPreviewBuilder {
+    options: types::LinkPreviewOptions,
+}
+
+impl LinkPreviewBuilder {
+    /// Start the options of a preview of the first URL of the text, below
+    /// the text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Don't show a link preview.
+    pub fn disabled(mut self) -> Self {
+        self.options.is_disabled = true;
+        self
+    }
+
+    /// Show the preview of the URL instead of the first one of the text.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.options.url = url.into();
+        self
+    }
+
+    /// Show the media of the preview small; only with [`Self::url`].
+    pub fn small_media(mut self) -> Self {
+        self.options.force_small_media = true;
+        self.options.force_large_media = false;
+        self
+    }
+
+    /// Show the media of the preview large; only with [`Self::url`].
+    pub fn large_media(mut self) -> Self {
+        self.options.force_large_media = true;
+        self.options.force_small_media = false;
+        self
+    }
+
+    /// Show the preview above the text instead of below.
+    pub fn above_text(mut self) -> Self {
+        self.options.show_above_text = true;
+        self
+    }
+
+    /// Returns the options of the link preview.
+    pub fn build(self) -> types::LinkPreviewOptions {
+        self.options
+    }
+
+    /// Returns the content of a text message with the link preview, to send.
+    pub fn text(self, text: types::FormattedText) -> InputMessageContent {
+        InputMessageContent::InputMessageText(td_struct!(types::InputMessageText {
+            text,
+            link_preview_options: Some(self.options),
+            clear_draft: false
+        }))
+    }
+}
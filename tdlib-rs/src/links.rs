@@ -0,0 +1,237 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Resolve the internal links of Telegram, such as `https://t.me/username`
+//! or `tg://resolve?domain=username`, into what they point to, making the
+//! follow-up requests which `getInternalLinkType` leaves to the caller,
+//! and make or resolve the links to messages.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::links;
+//!
+//! # async fn run(client_id: i32) -> Result<(), tdlib_rs::TdError> {
+//! # let (chat_id, message_id) = (Default::default(), Default::default());
+//! let link = links::message_link(chat_id, message_id)
+//!     .media_timestamp(90)
+//!     .get(client_id)
+//!     .await?;
+//! let target = links::resolve_message_link(link.link, client_id).await?;
+//! println!("{} at {}s", target.chat.title, target.media_timestamp);
+//! # Ok(())
+//! # }
+//! ```
+use crate::enums::{self, InternalLinkType, ProxyType};
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, types, TdError};
+
+/// What an internal link points to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Link {
+    /// A public chat, with the text to put in its draft if any.
+    Chat {
+        chat: Box<types::Chat>,
+        draft_text: String,
+    },
+    /// A message, or a forum topic.
+    Message(Box<types::MessageLinkInfo>),
+    /// A user found by phone number, with the text to put in the draft of the
+    /// chat with them if any.
+    UserByPhone {
+        user: Box<types::User>,
+        draft_text: String,
+    },
+    /// A user found by a temporary token.
+    UserByToken(Box<types::User>),
+    /// A sticker set.
+    StickerSet(Box<types::StickerSet>),
+    /// A proxy to add to the client.
+    Proxy {
+        server: String,
+        port: i32,
+        r#type: ProxyType,
+    },
+    /// An invite link to a chat.
+    ChatInvite(Box<types::ChatInviteLinkInfo>),
+    /// The start of a bot, with the parameter to send to it.
+    BotStart {
+        bot: Box<types::Chat>,
+        start_parameter: String,
+        autostart: bool,
+    },
+    /// Any other link, to be acted on as returned by `getInternalLinkType`.
+    Other(InternalLinkType),
+}
+
+/// Resolve the link, failing if it's not an internal link or what it points
+/// to can't be found.
+pub async fn resolve_link(url: String, client_id: i32) -> Result<Link, TdError> {
+    let link = match functions::get_internal_link_type(url, client_id).await? {
+        InternalLinkType::PublicChat(link) => {
+            let enums::Chat::Chat(chat) =
+                functions::search_public_chat(link.chat_username, client_id).await?;
+            Link::Chat {
+                chat: Box::new(chat),
+                draft_text: link.draft_text,
+            }
+        }
+        InternalLinkType::Message(link) => {
+            let enums::MessageLinkInfo::MessageLinkInfo(info) =
+                functions::get_message_link_info(link.url, client_id).await?;
+            Link::Message(Box::new(info))
+        }
+        InternalLinkType::UserPhoneNumber(link) => {
+            let enums::User::User(user) =
+                functions::search_user_by_phone_number(link.phone_number, client_id).await?;
+            Link::UserByPhone {
+                user: Box::new(user),
+                draft_text: link.draft_text,
+            }
+        }
+        InternalLinkType::UserToken(link) => {
+            let enums::User::User(user) =
+                functions::search_user_by_token(link.token, client_id).await?;
+            Link::UserByToken(Box::new(user))
+        }
+        InternalLinkType::StickerSet(link) => {
+            let enums::StickerSet::StickerSet(sticker_set) =
+                functions::search_sticker_set(link.sticker_set_name, client_id).await?;
+            Link::StickerSet(Box::new(sticker_set))
+        }
+        InternalLinkType::Proxy(link) => Link::Proxy {
+            server: link.server,
+            port: link.port,
+            r#type: link.r#type,
+        },
+        InternalLinkType::ChatInvite(link) => {
+            let enums::ChatInviteLinkInfo::ChatInviteLinkInfo(info) =
+                functions::check_chat_invite_link(link.invite_link, client_id).await?;
+            Link::ChatInvite(Box::new(info))
+        }
+        InternalLinkType::BotStart(link) => {
+            let enums::Chat::Chat(bot) =
+                functions::search_public_chat(link.bot_username, client_id).await?;
+            Link::BotStart {
+                bot: Box::new(bot),
+                start_parameter: link.start_parameter,
+                autostart: link.autostart,
+            }
+        }
+        link => Link::Other(link),
+    };
+    Ok(link)
+}
+
+/// A link to a message to get, with its options.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageLinkBuilder {
+    chat_id: ChatId,
+    message_id: MessageId,
+    media_timestamp: i32,
+    for_album: bool,
+    in_message_thread: bool,
+}
+
+/// Start the link to the message of the chat, as returned by
+/// `getMessageLink`.
+pub fn message_link(chat_id: ChatId, message_id: MessageId) -> MessageLinkBuilder {
+    MessageLinkBuilder {
+        chat_id,
+        message_id,
+        media_timestamp: 0,
+        for_album: false,
+        in_message_thread: false,
+    }
+}
+
+impl MessageLinkBuilder {
+    /// Start playing the media of the message at the timestamp, in seconds.
+    pub fn media_timestamp(mut self, media_timestamp: i32) -> Self {
+        self.media_timestamp = media_timestamp;
+        self
+    }
+
+    /// Link the whole album of the message.
+    pub fn for_album(mut self) -> Self {
+        self.for_album = true;
+        self
+    }
+
+    /// Link the message as a comment of a channel post, in its message
+    /// thread or forum topic.
+    pub fn in_message_thread(mut self) -> Self {
+        self.in_message_thread = true;
+        self
+    }
+
+    /// Get the link. Only the sent messages of the supergroups and the
+    /// channels have a link.
+    pub async fn get(self, client_id: i32) -> Result<types::MessageLink, TdError> {
+        let enums::MessageLink::MessageLink(link) = functions::get_message_link(
+            self.chat_id,
+            self.message_id,
+            self.media_timestamp,
+            self.for_album,
+            self.in_message_thread,
+            client_id,
+        )
+        .await?;
+        Ok(link)
+    }
+}
+
+impl types::Message {
+    /// Returns the link to the message working for the users who aren't
+    /// members of its chat, or `None` if the chat isn't public.
+    pub async fn public_link(&self, client_id: i32) -> Result<Option<String>, TdError> {
+        let link = message_link(self.chat_id, self.id).get(client_id).await?;
+        Ok(link.is_public.then_some(link.link))
+    }
+}
+
+/// The message a link points to, ready to be shown or jumped to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageLinkTarget {
+    /// The chat of the message.
+    pub chat: Box<types::Chat>,
+    /// The message, unless it wasn't found, like a deleted one.
+    pub message: Option<Box<types::Message>>,
+    /// The message thread in which to open the message, or the forum topic
+    /// to open if the message wasn't found, if any.
+    pub message_thread_id: Option<i64>,
+    /// The timestamp from which to start playing the media of the message,
+    /// in seconds, 0 if not specified.
+    pub media_timestamp: i32,
+    /// `true` if the whole album of the message is linked.
+    pub for_album: bool,
+    /// `true` if the link works for the users who aren't members of the chat.
+    pub is_public: bool,
+}
+
+/// Resolve the link to a message, failing if its chat can't be found.
+pub async fn resolve_message_link(
+    url: String,
+    client_id: i32,
+) -> Result<MessageLinkTarget, TdError> {
+    let enums::MessageLinkInfo::MessageLinkInfo(info) =
+        functions::get_message_link_info(url, client_id).await?;
+    if info.chat_id == ChatId::default() {
+        return Err(TdError::invalid(
+            404,
+            "The chat of the message link was not found",
+        ));
+    }
+    let enums::Chat::Chat(chat) = functions::get_chat(info.chat_id, client_id).await?;
+    Ok(MessageLinkTarget {
+        chat: Box::new(chat),
+        message: info.message.map(Box::new),
+        message_thread_id: (info.message_thread_id != 0).then_some(info.message_thread_id),
+        media_timestamp: info.media_timestamp,
+        for_album: info.for_album,
+        is_public: info.is_public,
+    })
+}
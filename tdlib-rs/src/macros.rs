@@ -0,0 +1,59 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Construct a generated struct, initializing its `extra` field when the
+/// `extra-fields` feature is enabled.
+#[allow(unused_macros)]
+macro_rules! td_struct {
+    ($($ty:ident)::+ { $($field:ident $(: $value:expr)?),* $(,)? }) => {
+        $($ty)::+ {
+            $($field $(: $value)?,)*
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        }
+    };
+}
+
+/// Match an [`Update`](crate::enums::Update) against the given variants,
+/// without writing the path of each one. The updates of the other variants
+/// are ignored, so the arms must evaluate to `()`:
+///
+/// ```rust
+/// use tdlib_rs::{enums::Update, handle_updates};
+///
+/// fn handle_update(update: Update) {
+///     handle_updates!(update, {
+///         NewMessage(m) if m.message.is_outgoing => println!("Sent {}", m.message.id),
+///         NewMessage(m) => println!("Received {}", m.message.id),
+///         ChatAction(a) => println!("Action in {}", a.chat_id),
+///     });
+/// }
+/// ```
+///
+/// Prefix the update with `exhaustive` to handle every variant instead, and
+/// let the compiler check that none is missing. In this case the arms can
+/// evaluate to any type.
+#[macro_export]
+macro_rules! handle_updates {
+    (exhaustive $update:expr, {
+        $($variant:ident $(($($binding:pat),*))? $(if $guard:expr)? => $body:expr),* $(,)?
+    }) => {
+        match $update {
+            $($crate::enums::Update::$variant $(($($binding),*))? $(if $guard)? => $body,)*
+        }
+    };
+    ($update:expr, {
+        $($variant:ident $(($($binding:pat),*))? $(if $guard:expr)? => $body:expr),* $(,)?
+    }) => {
+        match $update {
+            $($crate::enums::Update::$variant $(($($binding),*))? $(if $guard)? => $body,)*
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+    };
+}
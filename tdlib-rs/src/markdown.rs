@@ -0,0 +1,586 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parse the texts written in the MarkdownV2 of the Bot API into formatted
+//! texts without TdLib, like `parseTextEntities` with the version 2 of
+//! `textParseModeMarkdown`, so that they can be built before a client
+//! exists and without a round-trip to TdLib.
+//!
+//! The syntax is the one of TdLib: `*bold*`, `_italic_`, `__underline__`,
+//! `~strikethrough~`, `||spoiler||`, `[text](url)`,
+//! `[mention](tg://user?id=123)`, `![👍](tg://emoji?id=123)`, `` `code` ``,
+//! ```` ```language pre``` ```` and the lines of a block quote starting with
+//! `>`. Any character with a code between 1 and 126 can be escaped with a
+//! `\`, and the characters `_*[]()~`>#+-=|{}.!` must be outside of the
+//! entities.
+//!
+//! ```rust
+//! use tdlib_rs::enums::TextEntityType;
+//! use tdlib_rs::markdown;
+//!
+//! let text = markdown::parse("*Hello*, _world_\\!").unwrap();
+//! assert_eq!(text.text, "Hello, world!");
+//! assert_eq!(text.entities[0].r#type, TextEntityType::Bold);
+//! assert_eq!((text.entities[1].offset, text.entities[1].length), (7, 5));
+//!
+//! assert!(markdown::parse("1. Not escaped").is_err());
+//! ```
+use crate::enums::TextEntityType;
+use crate::ids::UserId;
+use crate::types;
+use std::cmp::Reverse;
+use std::fmt;
+
+/// The characters which must be escaped outside of the entities.
+pub const RESERVED: &str = "_*[]()~`>#+-=|{}.!";
+
+/// The error returned when a text isn't valid MarkdownV2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset in the text of the invalid character, or entity.
+    pub offset: usize,
+    /// What is invalid, as in the errors of TdLib.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Can't parse entities: {} at byte offset {}",
+            self.message, self.offset
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The entities which may contain other entities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Spoiler,
+    TextUrl,
+    CustomEmoji,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Bold => "Bold",
+            Kind::Italic => "Italic",
+            Kind::Underline => "Underline",
+            Kind::Strikethrough => "Strikethrough",
+            Kind::Spoiler => "Spoiler",
+            Kind::TextUrl => "TextUrl",
+            Kind::CustomEmoji => "CustomEmoji",
+        }
+    }
+}
+
+/// An entity whose end isn't parsed yet.
+struct Nested {
+    kind: Kind,
+    /// The offset of the entity in the text, in UTF-16 code units.
+    offset: i32,
+    /// The byte offset of the entity in the parsed text.
+    start: usize,
+    /// The byte offset of the entity in the markdown.
+    source: usize,
+}
+
+struct Parser<'a> {
+    markdown: &'a str,
+    chars: Vec<(usize, char)>,
+    i: usize,
+    text: String,
+    offset: i32,
+    entities: Vec<types::TextEntity>,
+    nested: Vec<Nested>,
+    quote: Option<i32>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(markdown: &'a str) -> Self {
+        Self {
+            markdown,
+            chars: markdown.char_indices().collect(),
+            i: 0,
+            text: String::new(),
+            offset: 0,
+            entities: Vec::new(),
+            nested: Vec::new(),
+            quote: None,
+        }
+    }
+
+    fn peek(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.i + ahead).map(|&(_, c)| c)
+    }
+
+    fn source(&self) -> usize {
+        self.chars
+            .get(self.i)
+            .map_or(self.markdown.len(), |&(offset, _)| offset)
+    }
+
+    fn error(&self, offset: usize, message: String) -> ParseError {
+        ParseError { offset, message }
+    }
+
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.offset += c.len_utf16() as i32;
+    }
+
+    /// Add the entity from the offset to the current one, unless empty.
+    fn entity(&mut self, offset: i32, r#type: TextEntityType) {
+        if self.offset > offset {
+            self.entities.push(td_struct!(types::TextEntity {
+                offset,
+                length: self.offset - offset,
+                r#type
+            }));
+        }
+    }
+
+    /// Returns the escaped character at the current one, if any.
+    fn escaped(&self) -> Option<char> {
+        if self.peek(0) != Some('\\') {
+            return None;
+        }
+        self.peek(1).filter(|&c| (1..=126).contains(&(c as u32)))
+    }
+
+    /// Parse the text until the delimiter, as in code and pre entities, whose
+    /// content is not parsed as markdown.
+    fn verbatim(&mut self, delimiter: &str, name: &str, source: usize) -> Result<(), ParseError> {
+        let count = delimiter.chars().count();
+        loop {
+            if self.markdown[self.source()..].starts_with(delimiter) {
+                self.i += count;
+                return Ok(());
+            }
+            if let Some(c) = self.escaped() {
+                self.push(c);
+                self.i += 2;
+                continue;
+            }
+            match self.peek(0) {
+                Some(c) => {
+                    self.push(c);
+                    self.i += 1;
+                }
+                None => {
+                    return Err(self.error(source, format!("Can't find end of {} entity", name)))
+                }
+            }
+        }
+    }
+
+    /// Parse the URL of a text URL or a custom emoji, after its `](`.
+    fn url(&mut self) -> Result<String, ParseError> {
+        let source = self.source();
+        let mut url = String::new();
+        loop {
+            if let Some(c) = self.escaped() {
+                url.push(c);
+                self.i += 2;
+                continue;
+            }
+            match self.peek(0) {
+                Some(')') => {
+                    self.i += 1;
+                    return Ok(url);
+                }
+                Some(c) => {
+                    url.push(c);
+                    self.i += 1;
+                }
+                None => return Err(self.error(source, "Can't find end of a URL".into())),
+            }
+        }
+    }
+
+    /// Returns `true` if the current character ends the innermost entity.
+    fn ends_entity(&self, c: char) -> bool {
+        let Some(nested) = self.nested.last() else {
+            return false;
+        };
+        match nested.kind {
+            Kind::Bold => c == '*',
+            Kind::Italic => c == '_' && self.peek(1) != Some('_'),
+            Kind::Underline => c == '_' && self.peek(1) == Some('_'),
+            Kind::Strikethrough => c == '~',
+            Kind::Spoiler => c == '|' && self.peek(1) == Some('|'),
+            Kind::TextUrl | Kind::CustomEmoji => c == ']',
+        }
+    }
+
+    #[allow(clippy::useless_conversion)]
+    fn end_entity(&mut self) -> Result<(), ParseError> {
+        let Some(nested) = self.nested.pop() else {
+            return Ok(());
+        };
+        let r#type = match nested.kind {
+            Kind::Bold => TextEntityType::Bold,
+            Kind::Italic => TextEntityType::Italic,
+            Kind::Underline => {
+                self.i += 1;
+                TextEntityType::Underline
+            }
+            Kind::Strikethrough => TextEntityType::Strikethrough,
+            Kind::Spoiler => {
+                self.i += 1;
+                TextEntityType::Spoiler
+            }
+            Kind::TextUrl => {
+                let url = if self.peek(1) == Some('(') {
+                    self.i += 2;
+                    self.url()?
+                } else {
+                    self.i += 1;
+                    self.text[nested.start..].to_owned()
+                };
+                let user_id = url
+                    .strip_prefix("tg://user?id=")
+                    .and_then(|id| id.parse::<i64>().ok());
+                match user_id {
+                    Some(user_id) => {
+                        TextEntityType::MentionName(td_struct!(types::TextEntityTypeMentionName {
+                            user_id: UserId::from(user_id)
+                        }))
+                    }
+                    None if url.is_empty() => return Ok(()),
+                    None => {
+                        TextEntityType::TextUrl(td_struct!(types::TextEntityTypeTextUrl { url }))
+                    }
+                }
+            }
+            Kind::CustomEmoji => {
+                let url = if self.peek(1) == Some('(') {
+                    self.i += 2;
+                    self.url()?
+                } else {
+                    String::new()
+                };
+                let custom_emoji_id = url
+                    .strip_prefix("tg://emoji?id=")
+                    .and_then(|id| id.parse::<i64>().ok())
+                    .ok_or_else(|| {
+                        self.error(
+                            nested.source,
+                            "Custom emoji entity must contain a tg://emoji URL".into(),
+                        )
+                    })?;
+                TextEntityType::CustomEmoji(td_struct!(types::TextEntityTypeCustomEmoji {
+                    custom_emoji_id
+                }))
+            }
+        };
+        if !matches!(nested.kind, Kind::TextUrl | Kind::CustomEmoji) {
+            self.i += 1;
+        }
+        self.entity(nested.offset, r#type);
+        Ok(())
+    }
+
+    /// Start the entity beginning at the current character, if any.
+    fn begin_entity(&mut self, c: char) -> Result<bool, ParseError> {
+        let source = self.source();
+        let (kind, length) = match (c, self.peek(1)) {
+            ('_', Some('_')) => (Kind::Underline, 2),
+            ('_', _) => (Kind::Italic, 1),
+            ('*', _) => (Kind::Bold, 1),
+            ('~', _) => (Kind::Strikethrough, 1),
+            ('|', Some('|')) => (Kind::Spoiler, 2),
+            ('[', _) => (Kind::TextUrl, 1),
+            ('!', Some('[')) => (Kind::CustomEmoji, 2),
+            ('`', _) => {
+                self.code(source)?;
+                return Ok(true);
+            }
+            _ => return Ok(false),
+        };
+        self.nested.push(Nested {
+            kind,
+            offset: self.offset,
+            start: self.text.len(),
+            source,
+        });
+        self.i += length;
+        Ok(true)
+    }
+
+    /// Parse a code or a pre entity, starting at its first backtick.
+    fn code(&mut self, source: usize) -> Result<(), ParseError> {
+        if !self.markdown[source..].starts_with("```") {
+            self.i += 1;
+            let offset = self.offset;
+            self.verbatim("`", "Code", source)?;
+            self.entity(offset, TextEntityType::Code);
+            return Ok(());
+        }
+
+        self.i += 3;
+        let mut language = String::new();
+        let mut end = self.i;
+        while let Some(&(_, c)) = self.chars.get(end) {
+            if c.is_whitespace() || c == '`' {
+                break;
+            }
+            end += 1;
+        }
+        if end != self.i && self.chars.get(end).is_some_and(|&(_, c)| c != '`') {
+            language = self.chars[self.i..end].iter().map(|&(_, c)| c).collect();
+            self.i = end;
+        }
+        // A new line after the language isn't part of the text
+        match (self.peek(0), self.peek(1)) {
+            (Some('\r'), Some('\n')) | (Some('\n'), Some('\r')) => self.i += 2,
+            (Some('\r' | '\n'), _) => self.i += 1,
+            _ => {}
+        }
+
+        let offset = self.offset;
+        self.verbatim("```", "Pre", source)?;
+        if language.is_empty() {
+            self.entity(offset, TextEntityType::Pre);
+        } else {
+            self.entity(
+                offset,
+                TextEntityType::PreCode(td_struct!(types::TextEntityTypePreCode { language })),
+            );
+        }
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<types::FormattedText, ParseError> {
+        while let Some(c) = self.peek(0) {
+            let line_start = self.i == 0 || self.chars[self.i - 1].1 == '\n';
+            if line_start && c == '>' {
+                self.quote.get_or_insert(self.offset);
+                self.i += 1;
+                continue;
+            }
+            if c == '\n' {
+                if let Some(offset) = self.quote {
+                    if self.peek(1) != Some('>') {
+                        self.entity(offset, TextEntityType::BlockQuote);
+                        self.quote = None;
+                    }
+                }
+                self.push(c);
+                self.i += 1;
+                continue;
+            }
+            if let Some(c) = self.escaped() {
+                self.push(c);
+                self.i += 2;
+                continue;
+            }
+            if self.ends_entity(c) {
+                self.end_entity()?;
+                continue;
+            }
+            if self.begin_entity(c)? {
+                continue;
+            }
+            if RESERVED.contains(c) {
+                return Err(self.error(
+                    self.source(),
+                    format!(
+                        "Character '{}' is reserved and must be escaped with the preceding '\\'",
+                        c
+                    ),
+                ));
+            }
+            self.push(c);
+            self.i += 1;
+        }
+
+        if let Some(nested) = self.nested.last() {
+            return Err(self.error(
+                nested.source,
+                format!("Can't find end of {} entity", nested.kind.name()),
+            ));
+        }
+        if let Some(offset) = self.quote {
+            self.entity(offset, TextEntityType::BlockQuote);
+        }
+
+        // The entities are added from the innermost, when they end
+        let mut entities = self.entities.into_iter().enumerate().collect::<Vec<_>>();
+        entities.sort_by_key(|(index, entity)| (entity.offset, Reverse((entity.length, *index))));
+        let entities = entities.into_iter().map(|(_, entity)| entity).collect();
+        Ok(td_struct!(types::FormattedText {
+            text: self.text,
+            entities
+        }))
+    }
+}
+
+/// Parse the text written in MarkdownV2 into a formatted text, whose
+/// entities are sorted by offset, the outermost first.
+///
+/// ```rust
+/// use tdlib_rs::enums::TextEntityType;
+/// use tdlib_rs::markdown;
+///
+/// let text = markdown::parse("*[Docs](https://core.telegram.org)*\n```rust\nfn main() {}```").unwrap();
+/// assert_eq!(text.text, "Docs\nfn main() {}");
+/// assert_eq!(text.entities[0].r#type, TextEntityType::Bold);
+/// assert!(matches!(&text.entities[1].r#type, TextEntityType::TextUrl(url) if url.url == "https://core.telegram.org"));
+/// assert!(matches!(&text.entities[2].r#type, TextEntityType::PreCode(pre) if pre.language == "rust"));
+/// ```
+pub fn parse(markdown: &str) -> Result<types::FormattedText, ParseError> {
+    Parser::new(markdown).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the offset, the length and the type of each entity of the
+    /// parsed text.
+    fn entities(text: &types::FormattedText) -> Vec<(i32, i32, TextEntityType)> {
+        text.entities
+            .iter()
+            .map(|entity| (entity.offset, entity.length, entity.r#type.clone()))
+            .collect()
+    }
+
+    fn error(markdown: &str) -> (usize, String) {
+        let error = parse(markdown).unwrap_err();
+        (error.offset, error.message)
+    }
+
+    #[test]
+    fn check_nesting() {
+        let text = parse("*bold _italic_ bold*").unwrap();
+        assert_eq!(text.text, "bold italic bold");
+        assert_eq!(
+            entities(&text),
+            [
+                (0, 16, TextEntityType::Bold),
+                (5, 6, TextEntityType::Italic)
+            ]
+        );
+
+        let text = parse("__u__ _i_ ||s|| ~st~").unwrap();
+        assert_eq!(text.text, "u i s st");
+        assert_eq!(
+            entities(&text),
+            [
+                (0, 1, TextEntityType::Underline),
+                (2, 1, TextEntityType::Italic),
+                (4, 1, TextEntityType::Spoiler),
+                (6, 2, TextEntityType::Strikethrough),
+            ]
+        );
+
+        // The offsets are in UTF-16 code units
+        let text = parse("😀 *bold*").unwrap();
+        assert_eq!(entities(&text), [(3, 4, TextEntityType::Bold)]);
+    }
+
+    #[test]
+    fn check_escapes() {
+        let text = parse("\\*not bold\\* \\a").unwrap();
+        assert_eq!(text.text, "*not bold* a");
+        assert!(text.entities.is_empty());
+
+        let text = parse("`a\\`b`").unwrap();
+        assert_eq!(text.text, "a`b");
+        assert_eq!(entities(&text), [(0, 3, TextEntityType::Code)]);
+
+        let text = parse("[link](https://example.com/\\))").unwrap();
+        assert_eq!(text.text, "link");
+        assert_eq!(
+            entities(&text),
+            [(
+                0,
+                4,
+                TextEntityType::TextUrl(td_struct!(types::TextEntityTypeTextUrl {
+                    url: "https://example.com/)".into()
+                }))
+            )]
+        );
+    }
+
+    #[test]
+    fn check_block_quotes() {
+        let text = parse(">quote\n>line\nafter").unwrap();
+        assert_eq!(text.text, "quote\nline\nafter");
+        assert_eq!(entities(&text), [(0, 10, TextEntityType::BlockQuote)]);
+
+        // An empty line ends the block quote
+        let text = parse(">a\n\n>b").unwrap();
+        assert_eq!(text.text, "a\n\nb");
+        assert_eq!(
+            entities(&text),
+            [
+                (0, 1, TextEntityType::BlockQuote),
+                (3, 1, TextEntityType::BlockQuote),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_pre() {
+        let text = parse("```rust\nfn main() {}\n```").unwrap();
+        assert_eq!(text.text, "fn main() {}\n");
+        let language = td_struct!(types::TextEntityTypePreCode {
+            language: "rust".into()
+        });
+        assert_eq!(
+            entities(&text),
+            [(0, 13, TextEntityType::PreCode(language))]
+        );
+
+        let text = parse("```\ncode```").unwrap();
+        assert_eq!(text.text, "code");
+        assert_eq!(entities(&text), [(0, 4, TextEntityType::Pre)]);
+    }
+
+    #[test]
+    #[allow(clippy::useless_conversion)]
+    fn check_mentions() {
+        let text = parse("[m](tg://user?id=42)").unwrap();
+        let TextEntityType::MentionName(mention) = &text.entities[0].r#type else {
+            panic!("not a mention: {:?}", text.entities);
+        };
+        assert_eq!(mention.user_id, UserId::from(42));
+
+        let text = parse("![👍](tg://emoji?id=5)").unwrap();
+        assert_eq!(text.text, "👍");
+        assert_eq!(text.entities[0].length, 2);
+    }
+
+    #[test]
+    fn check_errors() {
+        let reserved = "Character '.' is reserved and must be escaped with the preceding '\\'";
+        assert_eq!(error("1. x"), (1, reserved.into()));
+        assert_eq!(
+            error("*unclosed"),
+            (0, "Can't find end of Bold entity".into())
+        );
+        // The innermost entity not closed is reported
+        assert_eq!(
+            error("a _b *c_ d*"),
+            (10, "Can't find end of Bold entity".into())
+        );
+        assert_eq!(
+            parse("😀 *a").unwrap_err().to_string(),
+            "Can't parse entities: Can't find end of Bold entity at byte offset 5"
+        );
+    }
+}
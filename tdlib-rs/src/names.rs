@@ -0,0 +1,92 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The names of the users and the titles of the chats sent by TdLib to each
+//! client, kept up to date with the received updates, so that they can be
+//! shown without a request.
+use crate::enums::{AuthorizationState, MessageSender, Update};
+use crate::ids::{ChatId, UserId};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct Names {
+    users: HashMap<UserId, String>,
+    chats: HashMap<ChatId, String>,
+}
+
+static NAMES: Lazy<RwLock<HashMap<i32, Names>>> = Lazy::new(RwLock::default);
+
+/// Keep the names of the client up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    let mut names = NAMES.write().unwrap_or_else(|e| e.into_inner());
+    match update {
+        Update::User(update) => {
+            let name = format!("{} {}", update.user.first_name, update.user.last_name);
+            names
+                .entry(client_id)
+                .or_default()
+                .users
+                .insert(update.user.id, name.trim().to_owned());
+        }
+        Update::NewChat(update) => {
+            names
+                .entry(client_id)
+                .or_default()
+                .chats
+                .insert(update.chat.id, update.chat.title.clone());
+        }
+        Update::ChatTitle(update) => {
+            names
+                .entry(client_id)
+                .or_default()
+                .chats
+                .insert(update.chat_id, update.title.clone());
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                names.remove(&client_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the full name of the user, if sent by TdLib; it is empty for the
+/// deleted accounts.
+pub fn user_name(user_id: UserId, client_id: i32) -> Option<String> {
+    NAMES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)?
+        .users
+        .get(&user_id)
+        .cloned()
+}
+
+/// Returns the title of the chat, if sent by TdLib.
+pub fn chat_title(chat_id: ChatId, client_id: i32) -> Option<String> {
+    NAMES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)?
+        .chats
+        .get(&chat_id)
+        .cloned()
+}
+
+/// Returns the name of the user or the title of the chat sending a message,
+/// if sent by TdLib.
+pub fn sender_name(sender: &MessageSender, client_id: i32) -> Option<String> {
+    match sender {
+        MessageSender::User(sender) => user_name(sender.user_id, client_id),
+        MessageSender::Chat(sender) => chat_title(sender.chat_id, client_id),
+        MessageSender::Unsupported => None,
+    }
+}
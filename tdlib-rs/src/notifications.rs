@@ -0,0 +1,494 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The active notifications of the clients, tracked from
+//! `updateActiveNotifications`, `updateNotificationGroup` and
+//! `updateNotification`, and reported as they are added, edited and removed
+//! to show them as native notifications.
+//!
+//! TdLib only sends these updates when the `notification_group_count_max`
+//! option is set, to the number of groups to show at most. The notifications
+//! restored from the previous launch of the application are reported as
+//! added, without a sound.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::notifications::{self, NotificationChange};
+//!
+//! notifications::set_hook(|event| match &event.change {
+//!     NotificationChange::Added(notification) => {
+//!         println!("Show the notification {} of {}", notification.id, event.chat_id)
+//!     }
+//!     NotificationChange::Edited(notification) => {
+//!         println!("Update the notification {}", notification.id)
+//!     }
+//!     NotificationChange::Removed(notification_id) => {
+//!         println!("Hide the notification {}", notification_id)
+//!     }
+//! });
+//! ```
+use crate::enums::{AuthorizationState, NotificationGroupType, Update};
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+type NotificationHook = Arc<dyn Fn(&NotificationEvent) + Send + Sync>;
+
+/// A group of the active notifications, with the settings sent along with
+/// its changes.
+struct Group {
+    group: types::NotificationGroup,
+    notification_settings_chat_id: ChatId,
+    notification_sound_id: i64,
+}
+
+#[derive(Default)]
+struct Notifications {
+    groups: HashMap<i32, Group>,
+    have_pending: bool,
+}
+
+static NOTIFICATIONS: Lazy<RwLock<HashMap<i32, Notifications>>> = Lazy::new(RwLock::default);
+
+static HOOK: Lazy<RwLock<Option<NotificationHook>>> = Lazy::new(RwLock::default);
+
+/// A change of the active notifications of a client.
+#[derive(Clone, Debug)]
+pub struct NotificationEvent {
+    /// The client of the notification
+    pub client_id: i32,
+    /// The group of the notification
+    pub group_id: i32,
+    /// The type of the group
+    pub group_type: NotificationGroupType,
+    /// The chat of the notifications of the group
+    pub chat_id: ChatId,
+    /// The chat whose notification settings apply to the notification
+    pub notification_settings_chat_id: ChatId,
+    /// The sound to play for the notification, 0 if none
+    pub notification_sound_id: i64,
+    /// How the notification changed
+    pub change: NotificationChange,
+}
+
+/// How a notification changed.
+#[derive(Clone, Debug)]
+pub enum NotificationChange {
+    /// The notification is to be shown
+    Added(types::Notification),
+    /// The notification shown changed
+    Edited(types::Notification),
+    /// The notification with the identifier is to be hidden
+    Removed(i32),
+}
+
+/// Set the callback invoked with every change of the active notifications,
+/// replacing the previous one.
+pub fn set_hook<F>(hook: F)
+where
+    F: Fn(&NotificationEvent) + Send + Sync + 'static,
+{
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Remove the callback set with [`set_hook`].
+pub fn remove_hook() {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the events of the changes of the group.
+fn events(
+    client_id: i32,
+    group: &Group,
+    changes: impl IntoIterator<Item = NotificationChange>,
+) -> Vec<NotificationEvent> {
+    changes
+        .into_iter()
+        .map(|change| NotificationEvent {
+            client_id,
+            group_id: group.group.id,
+            group_type: group.group.r#type.clone(),
+            chat_id: group.group.chat_id,
+            notification_settings_chat_id: group.notification_settings_chat_id,
+            notification_sound_id: group.notification_sound_id,
+            change,
+        })
+        .collect()
+}
+
+/// Keep the active notifications of the client up to date, and report
+/// their changes.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    let mut changed = Vec::new();
+    {
+        let mut notifications = NOTIFICATIONS.write().unwrap_or_else(|e| e.into_inner());
+        match update {
+            Update::ActiveNotifications(update) => {
+                // Replace the groups, reporting only what differs from the
+                // notifications reported before
+                let state = notifications.entry(client_id).or_default();
+                let mut previous = std::mem::take(&mut state.groups);
+                for group in &update.groups {
+                    let old = previous.remove(&group.id);
+                    let old_notifications = old
+                        .as_ref()
+                        .map(|old| old.group.notifications.as_slice())
+                        .unwrap_or_default();
+                    let mut changes = old_notifications
+                        .iter()
+                        .filter(|old| !group.notifications.iter().any(|new| new.id == old.id))
+                        .map(|old| NotificationChange::Removed(old.id))
+                        .collect::<Vec<_>>();
+                    for notification in &group.notifications {
+                        match old_notifications
+                            .iter()
+                            .find(|old| old.id == notification.id)
+                        {
+                            None => changes.push(NotificationChange::Added(notification.clone())),
+                            Some(old) if old != notification => {
+                                changes.push(NotificationChange::Edited(notification.clone()))
+                            }
+                            Some(_) => {}
+                        }
+                    }
+                    let group = Group {
+                        group: group.clone(),
+                        notification_settings_chat_id: old
+                            .as_ref()
+                            .map_or(group.chat_id, |old| old.notification_settings_chat_id),
+                        notification_sound_id: old.map_or(0, |old| old.notification_sound_id),
+                    };
+                    changed.extend(events(client_id, &group, changes));
+                    state.groups.insert(group.group.id, group);
+                }
+                let mut removed = previous.into_values().collect::<Vec<_>>();
+                removed.sort_by_key(|group| group.group.id);
+                for group in removed {
+                    let changes = group
+                        .group
+                        .notifications
+                        .iter()
+                        .map(|notification| NotificationChange::Removed(notification.id));
+                    changed.extend(events(client_id, &group, changes));
+                }
+            }
+            Update::NotificationGroup(update) => {
+                let state = notifications.entry(client_id).or_default();
+                let group = state
+                    .groups
+                    .entry(update.notification_group_id)
+                    .or_insert_with(|| Group {
+                        group: td_struct!(types::NotificationGroup {
+                            id: update.notification_group_id,
+                            r#type: update.r#type.clone(),
+                            chat_id: update.chat_id,
+                            total_count: 0,
+                            notifications: Vec::new()
+                        }),
+                        notification_settings_chat_id: update.notification_settings_chat_id,
+                        notification_sound_id: update.notification_sound_id,
+                    });
+                group.group.r#type = update.r#type.clone();
+                group.group.chat_id = update.chat_id;
+                group.group.total_count = update.total_count;
+                group.notification_settings_chat_id = update.notification_settings_chat_id;
+                group.notification_sound_id = update.notification_sound_id;
+
+                let notifications = &mut group.group.notifications;
+                notifications.retain(|notification| {
+                    !update.removed_notification_ids.contains(&notification.id)
+                });
+                notifications.extend(update.added_notifications.iter().cloned());
+                notifications.sort_by_key(|notification| notification.id);
+
+                let removed = update
+                    .removed_notification_ids
+                    .iter()
+                    .map(|&id| NotificationChange::Removed(id));
+                let added = update
+                    .added_notifications
+                    .iter()
+                    .cloned()
+                    .map(NotificationChange::Added);
+                changed.extend(events(client_id, group, removed.chain(added)));
+                if group.group.notifications.is_empty() {
+                    state.groups.remove(&update.notification_group_id);
+                }
+            }
+            Update::Notification(update) => {
+                let group = notifications
+                    .get_mut(&client_id)
+                    .and_then(|state| state.groups.get_mut(&update.notification_group_id));
+                if let Some(group) = group {
+                    let notification = group
+                        .group
+                        .notifications
+                        .iter_mut()
+                        .find(|notification| notification.id == update.notification.id);
+                    if let Some(notification) = notification {
+                        *notification = update.notification.clone();
+                        let edited = NotificationChange::Edited(update.notification.clone());
+                        changed.extend(events(client_id, group, [edited]));
+                    }
+                }
+            }
+            Update::HavePendingNotifications(update) => {
+                notifications.entry(client_id).or_default().have_pending =
+                    update.have_delayed_notifications || update.have_unreceived_notifications;
+            }
+            Update::AuthorizationState(update) => {
+                if matches!(update.authorization_state, AuthorizationState::Closed) {
+                    notifications.remove(&client_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if changed.is_empty() {
+        return;
+    }
+    // Called once the lock is released, so that it can set another hook
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        for event in &changed {
+            hook(event);
+        }
+    }
+}
+
+/// Returns the active notification groups of the client, by identifier.
+pub fn active(client_id: i32) -> Vec<types::NotificationGroup> {
+    let notifications = NOTIFICATIONS.read().unwrap_or_else(|e| e.into_inner());
+    let mut groups = notifications
+        .get(&client_id)
+        .map(|state| {
+            state
+                .groups
+                .values()
+                .map(|group| group.group.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    groups.sort_by_key(|group| group.id);
+    groups
+}
+
+/// Returns the active notification group, if any.
+pub fn group(group_id: i32, client_id: i32) -> Option<types::NotificationGroup> {
+    NOTIFICATIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)?
+        .groups
+        .get(&group_id)
+        .map(|group| group.group.clone())
+}
+
+/// Whether TdLib has notifications to send soon, in which case the
+/// application should not be stopped yet.
+pub fn have_pending(client_id: i32) -> bool {
+    NOTIFICATIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .is_some_and(|state| state.have_pending)
+}
+
+/// Remove the active notification, for instance when it is dismissed.
+pub async fn remove(group_id: i32, notification_id: i32, client_id: i32) -> Result<(), TdError> {
+    functions::remove_notification(group_id, notification_id, client_id).await?;
+    Ok(())
+}
+
+/// Remove the notifications of the group up to `max_notification_id`.
+pub async fn remove_group(
+    group_id: i32,
+    max_notification_id: i32,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::remove_notification_group(group_id, max_notification_id, client_id).await?;
+    Ok(())
+}
+
+/// Remove the active notifications of the chat, for instance when it is
+/// opened.
+pub async fn remove_chat(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    let groups = active(client_id)
+        .into_iter()
+        .filter(|group| group.chat_id == chat_id);
+    for group in groups {
+        remove_active_group(&group, client_id).await?;
+    }
+    Ok(())
+}
+
+/// Remove every active notification of the client.
+pub async fn remove_all(client_id: i32) -> Result<(), TdError> {
+    for group in active(client_id) {
+        remove_active_group(&group, client_id).await?;
+    }
+    Ok(())
+}
+
+async fn remove_active_group(
+    group: &types::NotificationGroup,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let max_id = group
+        .notifications
+        .iter()
+        .map(|notification| notification.id)
+        .max()
+        .unwrap_or_default();
+    remove_group(group.id, max_id, client_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::NotificationType;
+    use std::sync::Mutex;
+
+    /// The changes reported for each client by the hook.
+    static CHANGES: Lazy<Mutex<HashMap<i32, Vec<NotificationChange>>>> = Lazy::new(Mutex::default);
+
+    /// Returns the changes reported for the client since the last call,
+    /// setting the hook recording them on first use.
+    fn changes(client_id: i32) -> Vec<String> {
+        static HOOK_SET: std::sync::Once = std::sync::Once::new();
+        HOOK_SET.call_once(|| {
+            set_hook(|event| {
+                CHANGES
+                    .lock()
+                    .unwrap()
+                    .entry(event.client_id)
+                    .or_default()
+                    .push(event.change.clone());
+            })
+        });
+        let changes = CHANGES.lock().unwrap().remove(&client_id);
+        changes
+            .unwrap_or_default()
+            .into_iter()
+            .map(|change| match change {
+                NotificationChange::Added(notification) => format!("+{}", notification.id),
+                NotificationChange::Edited(notification) => format!("~{}", notification.id),
+                NotificationChange::Removed(id) => format!("-{}", id),
+            })
+            .collect()
+    }
+
+    fn notification(id: i32, date: i32) -> types::Notification {
+        td_struct!(types::Notification {
+            id,
+            date,
+            is_silent: false,
+            r#type: NotificationType::NewSecretChat
+        })
+    }
+
+    fn notification_group(
+        id: i32,
+        notifications: Vec<types::Notification>,
+    ) -> types::NotificationGroup {
+        td_struct!(types::NotificationGroup {
+            id,
+            r#type: NotificationGroupType::Messages,
+            chat_id: Default::default(),
+            total_count: notifications.len() as i32,
+            notifications
+        })
+    }
+
+    fn active_notifications(groups: Vec<types::NotificationGroup>, client_id: i32) {
+        let update = td_struct!(types::UpdateActiveNotifications { groups });
+        observe(&Update::ActiveNotifications(update), client_id);
+    }
+
+    fn ids(client_id: i32) -> Vec<(i32, Vec<i32>)> {
+        active(client_id)
+            .into_iter()
+            .map(|group| {
+                let ids = group.notifications.iter().map(|n| n.id).collect();
+                (group.id, ids)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_group_updates() {
+        let client_id = -2001;
+        changes(client_id);
+        let update = td_struct!(types::UpdateNotificationGroup {
+            notification_group_id: 1,
+            r#type: NotificationGroupType::Messages,
+            chat_id: Default::default(),
+            notification_settings_chat_id: Default::default(),
+            notification_sound_id: 0,
+            total_count: 2,
+            added_notifications: vec![notification(1, 0), notification(2, 0)],
+            removed_notification_ids: Vec::new()
+        });
+        observe(&Update::NotificationGroup(update), client_id);
+        assert_eq!(changes(client_id), ["+1", "+2"]);
+
+        let update = td_struct!(types::UpdateNotification {
+            notification_group_id: 1,
+            notification: notification(2, 10)
+        });
+        observe(&Update::Notification(update), client_id);
+        assert_eq!(changes(client_id), ["~2"]);
+        assert_eq!(group(1, client_id).unwrap().notifications[1].date, 10);
+
+        let update = td_struct!(types::UpdateNotificationGroup {
+            notification_group_id: 1,
+            r#type: NotificationGroupType::Messages,
+            chat_id: Default::default(),
+            notification_settings_chat_id: Default::default(),
+            notification_sound_id: 0,
+            total_count: 0,
+            added_notifications: Vec::new(),
+            removed_notification_ids: vec![1, 2]
+        });
+        observe(&Update::NotificationGroup(update), client_id);
+        assert_eq!(changes(client_id), ["-1", "-2"]);
+        assert!(active(client_id).is_empty());
+    }
+
+    #[test]
+    fn check_active_notifications() {
+        let client_id = -2002;
+        changes(client_id);
+        active_notifications(
+            vec![
+                notification_group(1, vec![notification(1, 0), notification(2, 0)]),
+                notification_group(2, vec![notification(3, 0)]),
+            ],
+            client_id,
+        );
+        assert_eq!(changes(client_id), ["+1", "+2", "+3"]);
+
+        // Only the differences are reported, and the groups not sent again
+        // are removed
+        active_notifications(
+            vec![notification_group(
+                1,
+                vec![notification(2, 10), notification(4, 0)],
+            )],
+            client_id,
+        );
+        assert_eq!(changes(client_id), ["-1", "~2", "+4", "-3"]);
+        assert_eq!(ids(client_id), [(1, vec![2, 4])]);
+
+        active_notifications(Vec::new(), client_id);
+        assert_eq!(changes(client_id), ["-2", "-4"]);
+        assert!(active(client_id).is_empty());
+    }
+}
@@ -7,39 +7,133 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
-use serde_json::Value;
+use crate::hooks::{self, OrphanReason};
+use crate::registry::Registry;
+use crate::{json, retry, types};
+use serde::de::DeserializeOwned;
 use tokio::sync::oneshot;
-use std::collections::HashMap;
-use std::sync::RwLock;
+
+/// A response received from TdLib. It is kept as raw JSON until the
+/// consumer deserializes it into the expected type.
+#[derive(Clone)]
+pub(crate) struct Response {
+    ty: String,
+    json: String,
+}
+
+impl Response {
+    pub fn new(ty: String, json: String) -> Self {
+        Response { ty, json }
+    }
+
+    /// Returns an error response made by the library instead of TdLib.
+    fn from_error(code: i32, message: &str) -> Self {
+        let error = td_struct!(types::Error {
+            code,
+            message: message.into()
+        });
+        Response {
+            ty: "error".into(),
+            json: serde_json::to_string(&error).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the response of a request which will never be answered, since
+    /// the library stopped waiting for it.
+    pub fn abandoned() -> Self {
+        Self::from_error(
+            retry::UNKNOWN_OUTCOME,
+            "The request was abandoned before its response",
+        )
+    }
+
+    /// Returns the response of a request whose response couldn't be parsed.
+    pub fn malformed() -> Self {
+        Self::from_error(retry::UNKNOWN_OUTCOME, "The response of TdLib is malformed")
+    }
+
+    /// Returns the response of a request not answered within its timeout.
+    pub fn timed_out() -> Self {
+        Self::from_error(retry::UNKNOWN_OUTCOME, "Request timeout")
+    }
+
+    /// Returns `true` if the `@type` of the response is `error`.
+    pub fn is_error(&self) -> bool {
+        self.ty == "error"
+    }
+
+    /// Returns the JSON of the response, as received from TdLib.
+    pub fn json(&self) -> &str {
+        &self.json
+    }
+
+    /// Returns the JSON of the response, consuming it.
+    pub fn into_json(self) -> String {
+        self.json
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, json::Error> {
+        json::from_str(&self.json).inspect_err(|e| {
+            hooks::deserialization_failure(&self.json, std::any::type_name::<T>(), e)
+        })
+    }
+
+    /// Returns the error of an error response, or an error describing why
+    /// it couldn't be deserialized.
+    pub fn error(&self) -> types::Error {
+        self.deserialize().unwrap_or_else(|e| malformed(&e))
+    }
+
+    /// Returns the deserialized response, or an error describing why it
+    /// couldn't be deserialized.
+    pub fn result<T: DeserializeOwned>(&self) -> Result<T, types::Error> {
+        self.deserialize().map_err(|e| malformed(&e))
+    }
+}
+
+/// Returns the error reported for a response which couldn't be deserialized,
+/// so that a malformed response fails its request instead of panicking.
+fn malformed(error: &json::Error) -> types::Error {
+    td_struct!(types::Error {
+        code: retry::UNKNOWN_OUTCOME,
+        message: format!("Failed to deserialize the response: {}", error)
+    })
+}
 
 pub(super) struct Observer {
-    requests: RwLock<HashMap<u32, oneshot::Sender<Value>>>,
+    requests: Registry<oneshot::Sender<Response>>,
 }
 
 impl Observer {
     pub fn new() -> Self {
         Observer {
-            requests: RwLock::default(),
+            requests: Registry::new(),
         }
     }
 
-    pub fn subscribe(&self, extra: u32) -> oneshot::Receiver<Value> {
+    /// Register a request waiting for the response with the `@extra`, or
+    /// returns `None` if a request is already waiting for it, since the
+    /// counter of the `@extra` wrapped around while it was pending.
+    pub fn subscribe(&self, extra: u32) -> Option<oneshot::Receiver<Response>> {
         let (sender, receiver) = oneshot::channel();
-        self.requests.write().unwrap().insert(extra, sender);
-        receiver
-    }
-
-    pub fn notify(&self, response: Value) {
-        let extra = response["@extra"].as_u64().unwrap() as u32;
-        match self.requests.write().unwrap().remove(&extra) {
-            Some(sender) => {
-                if sender.send(response).is_err() {
-                    log::warn!("Got a response of an unaccessible request");
-                }
-            }
-            None => {
-                log::warn!("Got a response of an unknown request");
-            }
-        }
+        self.requests.try_insert(extra, sender).ok()?;
+        Some(receiver)
+    }
+
+    /// Stop waiting for the response with the `@extra`, so that its slot is
+    /// freed even if TdLib never answers.
+    pub fn unsubscribe(&self, extra: u32) {
+        self.requests.remove(extra);
+    }
+
+    pub fn notify(&self, extra: u32, client_id: i32, response: Response) {
+        let (response, reason) = match self.requests.remove(extra) {
+            Some(sender) => match sender.send(response) {
+                Ok(()) => return,
+                Err(response) => (response, OrphanReason::Dropped),
+            },
+            None => (response, OrphanReason::Unknown),
+        };
+        hooks::orphaned_response(&response.json, extra, client_id, reason);
     }
 }
@@ -0,0 +1,146 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Send the messages of each chat in order.
+//!
+//! The requests of concurrent functions can reach TdLib in any order, so two
+//! messages sent concurrently to the same chat can be delivered swapped. The
+//! functions of this module queue the requests of each chat, sending one only
+//! when the previous one has been answered, while the requests to different
+//! chats are still sent concurrently.
+//!
+//! The messages are also delayed by the limiter enabled with
+//! `rate_limit::enable`, if any.
+use crate::ids::{ChatId, MessageId};
+use crate::{enums, functions, rate_limit, types};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Mutex as AsyncMutex;
+
+type ChatQueue = Arc<AsyncMutex<()>>;
+
+static QUEUES: Lazy<Mutex<HashMap<(i32, ChatId), ChatQueue>>> = Lazy::new(Mutex::default);
+
+/// Removes the queue of the chat when no request is using it anymore.
+struct QueueGuard {
+    key: (i32, ChatId),
+    queue: ChatQueue,
+}
+
+impl Drop for QueueGuard {
+    fn drop(&mut self) {
+        let mut queues = QUEUES.lock().unwrap_or_else(|e| e.into_inner());
+        // The map and this guard are the only owners left
+        if Arc::strong_count(&self.queue) == 2 {
+            queues.remove(&self.key);
+        }
+    }
+}
+
+/// Wait for the requests previously queued for the chat, then run the
+/// future. The future must send a single request to the chat, for example
+/// the one of a function of the `functions` module, which sends its request
+/// only when it is first polled.
+pub async fn in_chat_order<F: Future>(chat_id: ChatId, client_id: i32, future: F) -> F::Output {
+    let key = (client_id, chat_id);
+    let queue = {
+        let mut queues = QUEUES.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(queues.entry(key).or_default())
+    };
+    let guard = QueueGuard { key, queue };
+
+    let output = {
+        let _turn = guard.queue.lock().await;
+        rate_limit::acquire(chat_id, client_id).await;
+        future.await
+    };
+    drop(guard);
+    output
+}
+
+/// Same as [`functions::send_message`], but the message is sent after the
+/// ones previously sent to the chat through this module.
+pub async fn send_message(
+    chat_id: ChatId,
+    message_thread_id: i64,
+    reply_to: Option<enums::InputMessageReplyTo>,
+    options: Option<types::MessageSendOptions>,
+    #[cfg(feature = "bots-only-api")] reply_markup: Option<enums::ReplyMarkup>,
+    input_message_content: enums::InputMessageContent,
+    client_id: i32,
+) -> Result<enums::Message, types::Error> {
+    #[cfg(not(feature = "bots-only-api"))]
+    let reply_markup = None;
+    let future = rate_limit::send_message_request(
+        chat_id,
+        message_thread_id,
+        reply_to,
+        options,
+        reply_markup,
+        input_message_content,
+        client_id,
+    );
+    in_chat_order(chat_id, client_id, future).await
+}
+
+/// Same as [`functions::send_message_album`], but the messages are sent after
+/// the ones previously sent to the chat through this module.
+pub async fn send_message_album(
+    chat_id: ChatId,
+    message_thread_id: i64,
+    reply_to: Option<enums::InputMessageReplyTo>,
+    options: Option<types::MessageSendOptions>,
+    input_message_contents: Vec<enums::InputMessageContent>,
+    client_id: i32,
+) -> Result<enums::Messages, types::Error> {
+    in_chat_order(
+        chat_id,
+        client_id,
+        functions::send_message_album(
+            chat_id,
+            message_thread_id,
+            reply_to,
+            options,
+            input_message_contents,
+            client_id,
+        ),
+    )
+    .await
+}
+
+/// Same as [`functions::forward_messages`], but the messages are forwarded
+/// after the ones previously sent to the chat through this module.
+#[allow(clippy::too_many_arguments)]
+pub async fn forward_messages(
+    chat_id: ChatId,
+    message_thread_id: i64,
+    from_chat_id: ChatId,
+    message_ids: Vec<MessageId>,
+    options: Option<types::MessageSendOptions>,
+    send_copy: bool,
+    remove_caption: bool,
+    client_id: i32,
+) -> Result<enums::Messages, types::Error> {
+    in_chat_order(
+        chat_id,
+        client_id,
+        functions::forward_messages(
+            chat_id,
+            message_thread_id,
+            from_chat_id,
+            message_ids,
+            options,
+            send_copy,
+            remove_caption,
+            client_id,
+        ),
+    )
+    .await
+}
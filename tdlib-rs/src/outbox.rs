@@ -0,0 +1,303 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An optional outbox journaling the messages before sending them, so that
+//! the messages not yet sent when the application stopped are sent again
+//! on startup with [`Outbox::resend`].
+//!
+//! A message is removed from the store once TdLib sends
+//! `updateMessageSendSucceeded` or `updateMessageSendFailed` for it, which
+//! gives an at-least-once delivery: a message sent right before a crash may
+//! be sent twice. The messages are stored as JSON files by [`FileStore`],
+//! or in any database implementing [`OutboxStore`].
+//!
+//! When `sendMessage` itself fails, the message is only removed if the
+//! error is definitive, like an invalid chat: after a timeout or a network
+//! error it is kept, since TdLib may have accepted it, and sent again by
+//! the next [`Outbox::resend`].
+use crate::enums::{self, InputMessageContent, Update};
+use crate::ids::{ChatId, MessageId};
+use crate::{rate_limit, types, TdError};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A message journaled by an [`Outbox`] until it is sent.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OutboxEntry {
+    /// The identifier of the entry in the store, growing with time.
+    pub id: u64,
+    /// The chat to send the message to.
+    pub chat_id: ChatId,
+    /// The content of the message.
+    pub content: InputMessageContent,
+}
+
+/// A store of the messages of an [`Outbox`], which must keep them across
+/// restarts.
+pub trait OutboxStore: Send + Sync {
+    /// Store the entry, before its message is sent.
+    fn save(&self, entry: &OutboxEntry) -> io::Result<()>;
+
+    /// Remove the entry, once its message is sent or failed; removing an
+    /// entry which isn't stored must succeed.
+    fn remove(&self, id: u64) -> io::Result<()>;
+
+    /// Returns the stored entries, ordered by identifier.
+    fn load(&self) -> io::Result<Vec<OutboxEntry>>;
+}
+
+/// A store keeping each entry in a JSON file of a directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileStore {
+    directory: PathBuf,
+}
+
+impl FileStore {
+    /// Store the entries in the directory, created if missing.
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(Self { directory })
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.directory.join(format!("{id}.json"))
+    }
+}
+
+impl OutboxStore for FileStore {
+    fn save(&self, entry: &OutboxEntry) -> io::Result<()> {
+        // Write a temporary file first, so that a crash never leaves a
+        // truncated entry
+        let temporary = self.directory.join(format!("{}.json.tmp", entry.id));
+        fs::write(&temporary, serde_json::to_vec(entry)?)?;
+        fs::rename(temporary, self.path(entry.id))
+    }
+
+    fn remove(&self, id: u64) -> io::Result<()> {
+        match fs::remove_file(self.path(id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn load(&self) -> io::Result<Vec<OutboxEntry>> {
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.directory)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+            match serde_json::from_slice(&fs::read(&path)?) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => log::warn!("Skipping the outbox entry {}: {}", path.display(), e),
+            }
+        }
+        entries.sort_by_key(|entry: &OutboxEntry| entry.id);
+        Ok(entries)
+    }
+}
+
+struct Pending {
+    store: Arc<dyn OutboxStore>,
+    entry_id: u64,
+}
+
+/// The messages being sent, by client and temporary message identifier.
+#[derive(Default)]
+struct State {
+    pending: HashMap<(i32, MessageId), Pending>,
+    /// The messages whose sending ended before `sendMessage` returned,
+    /// recorded only while requests are in flight.
+    ended: HashMap<(i32, MessageId), Option<types::Error>>,
+    in_flight: HashMap<i32, usize>,
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(Mutex::default);
+
+fn remove(store: &dyn OutboxStore, entry_id: u64, error: Option<&types::Error>) {
+    if let Some(error) = error {
+        log::warn!("The outbox entry {} failed to be sent: {}", entry_id, error);
+    }
+    if let Err(e) = store.remove(entry_id) {
+        log::warn!("The outbox entry {} failed to be removed: {}", entry_id, e);
+    }
+}
+
+/// Returns `true` if the error of `sendMessage` means that the message was
+/// not sent and never will be, unlike a flood wait, a timeout or a network
+/// error after which TdLib may have sent it anyway.
+fn is_definitive(error: &types::Error) -> bool {
+    (400..500).contains(&error.code) && error.is_permanent()
+}
+
+fn ended(client_id: i32, message_id: MessageId, error: Option<&types::Error>) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let key = (client_id, message_id);
+    if let Some(pending) = state.pending.remove(&key) {
+        drop(state);
+        remove(&*pending.store, pending.entry_id, error);
+    } else if state.in_flight.get(&client_id).is_some_and(|&n| n > 0) {
+        state.ended.insert(key, error.cloned());
+    }
+}
+
+/// Remove the messages of the outboxes from their store once sent.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::MessageSendSucceeded(update) => ended(client_id, update.old_message_id, None),
+        Update::MessageSendFailed(update) => {
+            ended(client_id, update.old_message_id, Some(&update.error))
+        }
+        _ => {}
+    }
+}
+
+fn next_entry_id() -> u64 {
+    static LAST: AtomicU64 = AtomicU64::new(0);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let previous = LAST
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or_default();
+    now.max(previous + 1)
+}
+
+/// An outbox of a client, journaling its messages in a store.
+#[derive(Clone)]
+pub struct Outbox {
+    store: Arc<dyn OutboxStore>,
+    client_id: i32,
+}
+
+impl Outbox {
+    /// Start an outbox of the client journaling in the store.
+    pub fn new(store: impl OutboxStore + 'static, client_id: i32) -> Self {
+        Self {
+            store: Arc::new(store),
+            client_id,
+        }
+    }
+
+    /// Journal the message, then send it, returning the message being sent.
+    pub async fn send(
+        &self,
+        chat_id: ChatId,
+        content: InputMessageContent,
+    ) -> Result<types::Message, TdError> {
+        let entry = OutboxEntry {
+            id: next_entry_id(),
+            chat_id,
+            content,
+        };
+        self.store.save(&entry)?;
+        self.dispatch(entry).await
+    }
+
+    /// Send again the messages of the store, journaled before a restart;
+    /// returns the number of messages sent. The messages being sent by the
+    /// outbox of the client are skipped.
+    pub async fn resend(&self) -> Result<usize, TdError> {
+        let mut entries = self.store.load()?;
+        {
+            let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+            entries.retain(|entry| {
+                !state.pending.iter().any(|((client_id, _), pending)| {
+                    *client_id == self.client_id && pending.entry_id == entry.id
+                })
+            });
+        }
+        let count = entries.len();
+        for entry in entries {
+            self.dispatch(entry).await?;
+        }
+        Ok(count)
+    }
+
+    /// Returns the messages journaled and not sent yet.
+    pub fn unsent(&self) -> Result<Vec<OutboxEntry>, TdError> {
+        Ok(self.store.load()?)
+    }
+
+    async fn dispatch(&self, entry: OutboxEntry) -> Result<types::Message, TdError> {
+        {
+            let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+            *state.in_flight.entry(self.client_id).or_default() += 1;
+        }
+        let result = rate_limit::send_message(
+            entry.chat_id,
+            0,
+            None,
+            None,
+            None,
+            entry.content,
+            self.client_id,
+        )
+        .await;
+
+        // The entry to remove from the store, with the error of its message,
+        // removed once the state is unlocked
+        let mut ended = None;
+        let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+        let in_flight = state.in_flight.entry(self.client_id).or_default();
+        *in_flight -= 1;
+        if *in_flight == 0 {
+            state.in_flight.remove(&self.client_id);
+        }
+        let result = match result {
+            Ok(enums::Message::Message(message)) => {
+                let key = (self.client_id, message.id);
+                match state.ended.remove(&key) {
+                    Some(error) => ended = Some(error),
+                    None if message.sending_state.is_none() => ended = Some(None),
+                    None => {
+                        let pending = Pending {
+                            store: self.store.clone(),
+                            entry_id: entry.id,
+                        };
+                        state.pending.insert(key, pending);
+                    }
+                }
+                Ok(message)
+            }
+            Err(error) => {
+                if is_definitive(&error) {
+                    ended = Some(Some(error.clone()));
+                } else {
+                    log::warn!(
+                        "The outbox entry {} may not have been sent, keeping it: {}",
+                        entry.id,
+                        error
+                    );
+                }
+                Err(error.into())
+            }
+        };
+        if !state.in_flight.contains_key(&self.client_id) {
+            let client_id = self.client_id;
+            state.ended.retain(|(id, _), _| *id != client_id);
+        }
+        drop(state);
+
+        if let Some(error) = ended {
+            remove(&*self.store, entry.id, error.as_ref());
+        }
+        result
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Manage the Telegram Passport of the user and share it with the bots
+//! asking for it, and with `bots-only-api` report the errors of a shared
+//! passport.
+//!
+//! The passport is encrypted with the password of the two-step verification,
+//! so most functions need it; the user must have set one. The data shared
+//! with a bot is encrypted with its public key: the bot receives it in a
+//! `messagePassportDataReceived` and must decrypt the credentials with its
+//! private key, which is not done by TdLib.
+use crate::enums::{self, InputPassportElement, PassportElement, PassportElementType};
+use crate::ids::UserId;
+use crate::{functions, types, TdError};
+
+/// Returns the type of a passport element.
+pub fn element_type(element: &PassportElement) -> PassportElementType {
+    match element {
+        PassportElement::PersonalDetails(_) => PassportElementType::PersonalDetails,
+        PassportElement::Passport(_) => PassportElementType::Passport,
+        PassportElement::DriverLicense(_) => PassportElementType::DriverLicense,
+        PassportElement::IdentityCard(_) => PassportElementType::IdentityCard,
+        PassportElement::InternalPassport(_) => PassportElementType::InternalPassport,
+        PassportElement::Address(_) => PassportElementType::Address,
+        PassportElement::UtilityBill(_) => PassportElementType::UtilityBill,
+        PassportElement::BankStatement(_) => PassportElementType::BankStatement,
+        PassportElement::RentalAgreement(_) => PassportElementType::RentalAgreement,
+        PassportElement::PassportRegistration(_) => PassportElementType::PassportRegistration,
+        PassportElement::TemporaryRegistration(_) => PassportElementType::TemporaryRegistration,
+        PassportElement::PhoneNumber(_) => PassportElementType::PhoneNumber,
+        PassportElement::EmailAddress(_) => PassportElementType::EmailAddress,
+        PassportElement::Unsupported => PassportElementType::Unsupported,
+    }
+}
+
+/// Returns an element of the passport.
+pub async fn element(
+    r#type: PassportElementType,
+    password: String,
+    client_id: i32,
+) -> Result<PassportElement, TdError> {
+    Ok(functions::get_passport_element(r#type, password, client_id).await?)
+}
+
+/// Returns every element of the passport.
+pub async fn elements(password: String, client_id: i32) -> Result<Vec<PassportElement>, TdError> {
+    let enums::PassportElements::PassportElements(elements) =
+        functions::get_all_passport_elements(password, client_id).await?;
+    Ok(elements.elements)
+}
+
+/// Add or replace an element of the passport. The phone number and the email
+/// address must be verified first.
+pub async fn set_element(
+    element: InputPassportElement,
+    password: String,
+    client_id: i32,
+) -> Result<PassportElement, TdError> {
+    Ok(functions::set_passport_element(element, password, client_id).await?)
+}
+
+/// Delete an element of the passport.
+pub async fn delete_element(r#type: PassportElementType, client_id: i32) -> Result<(), TdError> {
+    functions::delete_passport_element(r#type, client_id).await?;
+    Ok(())
+}
+
+/// A request of a bot for elements of the passport, with the elements of
+/// the passport which can be shared with it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuthorizationForm {
+    /// The form, with the elements required by the bot.
+    pub form: types::PassportAuthorizationForm,
+    /// The elements available for the form and their errors.
+    pub available: types::PassportElementsWithErrors,
+}
+
+impl AuthorizationForm {
+    fn is_available(&self, r#type: &PassportElementType) -> bool {
+        self.available
+            .elements
+            .iter()
+            .any(|element| element_type(element) == *r#type)
+    }
+
+    /// Returns the required elements which none of the available elements is
+    /// suitable for, to be added to the passport before sending the form.
+    pub fn missing(&self) -> Vec<&types::PassportRequiredElement> {
+        self.form
+            .required_elements
+            .iter()
+            .filter(|required| {
+                !required
+                    .suitable_elements
+                    .iter()
+                    .any(|suitable| self.is_available(&suitable.r#type))
+            })
+            .collect()
+    }
+
+    /// Returns the types of the elements to share, choosing the first
+    /// available one for each required element, or `None` if some are
+    /// missing.
+    pub fn types(&self) -> Option<Vec<PassportElementType>> {
+        self.form
+            .required_elements
+            .iter()
+            .map(|required| {
+                required
+                    .suitable_elements
+                    .iter()
+                    .find(|suitable| self.is_available(&suitable.r#type))
+                    .map(|suitable| suitable.r#type.clone())
+            })
+            .collect()
+    }
+}
+
+/// Returns the form requested by a bot, as found in an
+/// `internalLinkTypePassportDataRequest`, with the available elements.
+pub async fn authorization_form(
+    bot_user_id: UserId,
+    scope: String,
+    public_key: String,
+    nonce: String,
+    password: String,
+    client_id: i32,
+) -> Result<AuthorizationForm, TdError> {
+    let enums::PassportAuthorizationForm::PassportAuthorizationForm(form) =
+        functions::get_passport_authorization_form(
+            bot_user_id,
+            scope,
+            public_key,
+            nonce,
+            client_id,
+        )
+        .await?;
+    let enums::PassportElementsWithErrors::PassportElementsWithErrors(available) =
+        functions::get_passport_authorization_form_available_elements(form.id, password, client_id)
+            .await?;
+    Ok(AuthorizationForm { form, available })
+}
+
+/// Share the elements of the given types with the bot of the form.
+pub async fn send_authorization_form(
+    form: &AuthorizationForm,
+    types: Vec<PassportElementType>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::send_passport_authorization_form(form.form.id, types, client_id).await?;
+    Ok(())
+}
+
+/// Report the errors of the elements shared by a user, who can't share them
+/// again until they are fixed.
+#[cfg(feature = "bots-only-api")]
+pub async fn set_element_errors(
+    user_id: UserId,
+    errors: Vec<types::InputPassportElementError>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::set_passport_element_errors(user_id, errors, client_id).await?;
+    Ok(())
+}
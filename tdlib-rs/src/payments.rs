@@ -0,0 +1,244 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Pay the invoices, and with `bots-only-api` answer the queries of the
+//! users paying the invoices of the bot.
+//!
+//! A user pays an invoice by getting its [`payment_form`], validating the
+//! order information with [`validate_order_info`] if the invoice needs it,
+//! then sending the form with [`pay`].
+use crate::enums::{self, InputCredentials, InputInvoice};
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, types, TdError};
+
+/// Build an invoice, to be sent in an `inputMessageInvoice`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InvoiceBuilder {
+    invoice: types::Invoice,
+}
+
+impl InvoiceBuilder {
+    /// Start an invoice in the currency, as an ISO 4217 code.
+    pub fn new(currency: impl Into<String>) -> Self {
+        Self {
+            invoice: types::Invoice {
+                currency: currency.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Add a part of the price, in the smallest units of the currency.
+    pub fn price(mut self, label: impl Into<String>, amount: i64) -> Self {
+        self.invoice.price_parts.push(labeled_price(label, amount));
+        self
+    }
+
+    /// Allow a tip of at most `max_amount`, suggesting the given amounts.
+    pub fn tip(mut self, max_amount: i64, suggested_amounts: Vec<i64>) -> Self {
+        self.invoice.max_tip_amount = max_amount;
+        self.invoice.suggested_tip_amounts = suggested_amounts;
+        self
+    }
+
+    /// Set the URL of the terms of service of the product.
+    pub fn terms_of_service_url(mut self, url: impl Into<String>) -> Self {
+        self.invoice.terms_of_service_url = url.into();
+        self
+    }
+
+    /// Set the URL of the terms of service of a recurring payment.
+    pub fn recurring_payment_terms_of_service_url(mut self, url: impl Into<String>) -> Self {
+        self.invoice.recurring_payment_terms_of_service_url = url.into();
+        self
+    }
+
+    /// Make the invoice a test one.
+    pub fn test(mut self) -> Self {
+        self.invoice.is_test = true;
+        self
+    }
+
+    /// Ask the user for their name.
+    pub fn need_name(mut self) -> Self {
+        self.invoice.need_name = true;
+        self
+    }
+
+    /// Ask the user for their phone number, sending it to the provider if
+    /// `send_to_provider`.
+    pub fn need_phone_number(mut self, send_to_provider: bool) -> Self {
+        self.invoice.need_phone_number = true;
+        self.invoice.send_phone_number_to_provider = send_to_provider;
+        self
+    }
+
+    /// Ask the user for their email address, sending it to the provider if
+    /// `send_to_provider`.
+    pub fn need_email_address(mut self, send_to_provider: bool) -> Self {
+        self.invoice.need_email_address = true;
+        self.invoice.send_email_address_to_provider = send_to_provider;
+        self
+    }
+
+    /// Ask the user for their shipping address.
+    pub fn need_shipping_address(mut self) -> Self {
+        self.invoice.need_shipping_address = true;
+        self
+    }
+
+    /// Make the price depend on the shipping method, answered by the bot to
+    /// the shipping queries.
+    pub fn flexible(mut self) -> Self {
+        self.invoice.is_flexible = true;
+        self
+    }
+
+    /// Returns the invoice.
+    pub fn build(self) -> types::Invoice {
+        self.invoice
+    }
+}
+
+/// Returns a part of a price, in the smallest units of the currency.
+pub fn labeled_price(label: impl Into<String>, amount: i64) -> types::LabeledPricePart {
+    td_struct!(types::LabeledPricePart {
+        label: label.into(),
+        amount
+    })
+}
+
+/// Returns a shipping method, with the parts of its price.
+pub fn shipping_option(
+    id: impl Into<String>,
+    title: impl Into<String>,
+    price_parts: Vec<types::LabeledPricePart>,
+) -> types::ShippingOption {
+    td_struct!(types::ShippingOption {
+        id: id.into(),
+        title: title.into(),
+        price_parts
+    })
+}
+
+/// Returns the invoice of a message.
+pub fn message_invoice(chat_id: ChatId, message_id: MessageId) -> InputInvoice {
+    InputInvoice::Message(td_struct!(types::InputInvoiceMessage {
+        chat_id,
+        message_id
+    }))
+}
+
+/// Returns the invoice of a link, by its name.
+pub fn named_invoice(name: impl Into<String>) -> InputInvoice {
+    InputInvoice::Name(td_struct!(types::InputInvoiceName { name: name.into() }))
+}
+
+/// Returns the payment form of the invoice.
+pub async fn payment_form(
+    input_invoice: InputInvoice,
+    theme: Option<types::ThemeParameters>,
+    client_id: i32,
+) -> Result<types::PaymentForm, TdError> {
+    let enums::PaymentForm::PaymentForm(form) =
+        functions::get_payment_form(input_invoice, theme, client_id).await?;
+    Ok(form)
+}
+
+/// Validate the order information entered by the user, returning the
+/// shipping methods available for it.
+pub async fn validate_order_info(
+    input_invoice: InputInvoice,
+    order_info: Option<types::OrderInfo>,
+    allow_save: bool,
+    client_id: i32,
+) -> Result<types::ValidatedOrderInfo, TdError> {
+    let enums::ValidatedOrderInfo::ValidatedOrderInfo(info) =
+        functions::validate_order_info(input_invoice, order_info, allow_save, client_id).await?;
+    Ok(info)
+}
+
+/// Pay the invoice with its payment form, the validated order information
+/// and the chosen shipping method if needed, and the given tip. An empty
+/// `verification_url` in the result means the payment needs no further step.
+pub async fn pay(
+    input_invoice: InputInvoice,
+    form: &types::PaymentForm,
+    order_info: Option<&types::ValidatedOrderInfo>,
+    shipping_option_id: Option<String>,
+    credentials: InputCredentials,
+    tip_amount: i64,
+    client_id: i32,
+) -> Result<types::PaymentResult, TdError> {
+    let enums::PaymentResult::PaymentResult(result) = functions::send_payment_form(
+        input_invoice,
+        form.id,
+        order_info
+            .map(|info| info.order_info_id.clone())
+            .unwrap_or_default(),
+        shipping_option_id.unwrap_or_default(),
+        credentials,
+        tip_amount,
+        client_id,
+    )
+    .await?;
+    Ok(result)
+}
+
+/// Answer a pre-checkout query, accepting the payment or refusing it with
+/// the error message shown to the user.
+#[cfg(feature = "bots-only-api")]
+pub async fn answer_pre_checkout_query(
+    pre_checkout_query_id: i64,
+    answer: Result<(), String>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::answer_pre_checkout_query(
+        pre_checkout_query_id,
+        answer.err().unwrap_or_default(),
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Answer a shipping query with the available shipping methods, or with the
+/// error message shown to the user if the address can't be shipped to.
+#[cfg(feature = "bots-only-api")]
+pub async fn answer_shipping_query(
+    shipping_query_id: i64,
+    answer: Result<Vec<types::ShippingOption>, String>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let (shipping_options, error_message) = match answer {
+        Ok(shipping_options) => (shipping_options, String::new()),
+        Err(error_message) => (Vec::new(), error_message),
+    };
+    functions::answer_shipping_query(
+        shipping_query_id,
+        shipping_options,
+        error_message,
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Create a link to pay an invoice, described by an `inputMessageInvoice`.
+#[cfg(feature = "bots-only-api")]
+pub async fn create_invoice_link(
+    invoice: types::InputMessageInvoice,
+    client_id: i32,
+) -> Result<String, TdError> {
+    let enums::HttpUrl::HttpUrl(url) = functions::create_invoice_link(
+        enums::InputMessageContent::InputMessageInvoice(Box::new(invoice)),
+        client_id,
+    )
+    .await?;
+    Ok(url.url)
+}
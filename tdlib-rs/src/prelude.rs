@@ -0,0 +1,33 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The prelude re-exports the items used by almost every application, so
+//! that they can be imported at once:
+//!
+//! ```rust,no_run
+//! use tdlib_rs::prelude::*;
+//!
+//! fn handle_update(update: Update) {
+//!     if let Update::NewMessage(update) = update {
+//!         if let MessageContent::MessageText(text) = update.message.content {
+//!             println!("{}", text.text.text);
+//!         }
+//!     }
+//! }
+//! ```
+pub use crate::enums::{
+    AuthorizationState, ChatList, ChatType, InputMessageContent, MessageContent, MessageSender,
+    TextEntityType, Update,
+};
+pub use crate::types::{Error, FormattedText};
+pub use crate::{
+    create_client, enums, functions, receive, receive_and_handle, try_receive, types, TdError,
+    UpdateHandler,
+};
+#[cfg(feature = "typed-ids")]
+pub use crate::{ChatId, FileId, MessageId, UserId};
@@ -0,0 +1,91 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The limits of Telegram Premium and the gift codes to give it to other
+//! users.
+use crate::enums::{self, InputInvoice, PremiumLimitType, TelegramPaymentPurpose};
+use crate::ids::{ChatId, UserId};
+use crate::{functions, identity, types, TdError};
+
+/// Returns a limit, with its value with and without Telegram Premium.
+pub async fn limit(
+    limit_type: PremiumLimitType,
+    client_id: i32,
+) -> Result<types::PremiumLimit, TdError> {
+    let enums::PremiumLimit::PremiumLimit(limit) =
+        functions::get_premium_limit(limit_type, client_id).await?;
+    Ok(limit)
+}
+
+/// Returns the value of a limit for the user of the client, depending on
+/// whether they have Telegram Premium.
+pub async fn my_limit(limit_type: PremiumLimitType, client_id: i32) -> Result<i32, TdError> {
+    let limit = limit(limit_type, client_id).await?;
+    if identity::me(client_id).await?.is_premium {
+        Ok(limit.premium_value)
+    } else {
+        Ok(limit.default_value)
+    }
+}
+
+/// Returns the state of the subscription to Telegram Premium, with the
+/// options to buy it.
+pub async fn state(client_id: i32) -> Result<types::PremiumState, TdError> {
+    let enums::PremiumState::PremiumState(state) = functions::get_premium_state(client_id).await?;
+    Ok(state)
+}
+
+/// Returns the options to buy gift codes, boosting the given chat if any.
+pub async fn gift_code_payment_options(
+    boosted_chat_id: Option<ChatId>,
+    client_id: i32,
+) -> Result<Vec<types::PremiumGiftCodePaymentOption>, TdError> {
+    let enums::PremiumGiftCodePaymentOptions::PremiumGiftCodePaymentOptions(options) =
+        functions::get_premium_gift_code_payment_options(
+            boosted_chat_id.unwrap_or_default(),
+            client_id,
+        )
+        .await?;
+    Ok(options.options)
+}
+
+/// Returns the invoice to buy gift codes for the users with one of the
+/// options of [`gift_code_payment_options`], to be paid with the `payments`
+/// module.
+pub fn gift_codes_invoice(
+    option: &types::PremiumGiftCodePaymentOption,
+    user_ids: Vec<UserId>,
+    boosted_chat_id: Option<ChatId>,
+) -> InputInvoice {
+    let purpose = TelegramPaymentPurpose::PremiumGiftCodes(td_struct!(
+        types::TelegramPaymentPurposePremiumGiftCodes {
+            boosted_chat_id: boosted_chat_id.unwrap_or_default(),
+            currency: option.currency.clone(),
+            amount: option.amount,
+            user_ids,
+            month_count: option.month_count
+        }
+    ));
+    InputInvoice::Telegram(td_struct!(types::InputInvoiceTelegram { purpose }))
+}
+
+/// Returns the information of a gift code.
+pub async fn check_gift_code(
+    code: String,
+    client_id: i32,
+) -> Result<types::PremiumGiftCodeInfo, TdError> {
+    let enums::PremiumGiftCodeInfo::PremiumGiftCodeInfo(info) =
+        functions::check_premium_gift_code(code, client_id).await?;
+    Ok(info)
+}
+
+/// Apply a gift code to the user of the client.
+pub async fn apply_gift_code(code: String, client_id: i32) -> Result<(), TdError> {
+    functions::apply_premium_gift_code(code, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The priority of the requests, so that the bulk jobs, like the export of
+//! a chat history, don't delay the requests of the user.
+//!
+//! The requests sent by a future wrapped with [`background`] are sent only
+//! while no interactive request of the same client is waiting for its
+//! response, and at most [`set_max_background`] at a time; the interactive
+//! requests, the default, are always sent immediately.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::{functions, priority};
+//!
+//! # async fn export(client_id: i32) {
+//! # let (chat_id, from_message_id) = (Default::default(), Default::default());
+//! let history = priority::background(functions::get_chat_history(
+//!     chat_id,
+//!     from_message_id,
+//!     0,
+//!     100,
+//!     false,
+//!     client_id,
+//! ))
+//! .await;
+//! # }
+//! ```
+use once_cell::sync::Lazy;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// The default number of background requests of a client sent at a time.
+pub const DEFAULT_MAX_BACKGROUND: usize = 2;
+
+/// The priority of a request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// A request of the user, sent immediately.
+    #[default]
+    Interactive,
+    /// A bulk request, sent once the interactive ones are answered.
+    Background,
+}
+
+thread_local! {
+    static CURRENT: Cell<Priority> = const { Cell::new(Priority::Interactive) };
+}
+
+/// Returns the priority of the requests sent from the current poll.
+pub fn current() -> Priority {
+    CURRENT.with(Cell::get)
+}
+
+/// Run the future with the requests it sends at the priority.
+pub async fn with_priority<F: Future>(priority: Priority, future: F) -> F::Output {
+    let mut future = pin!(future);
+    poll_fn(|cx| {
+        let previous = CURRENT.with(|current| current.replace(priority));
+        let output = future.as_mut().poll(cx);
+        CURRENT.with(|current| current.set(previous));
+        output
+    })
+    .await
+}
+
+/// Run the future with the requests it sends in the background.
+pub async fn background<F: Future>(future: F) -> F::Output {
+    with_priority(Priority::Background, future).await
+}
+
+/// Run the future with the requests it sends as interactive, even inside a
+/// [`background`] future.
+pub async fn interactive<F: Future>(future: F) -> F::Output {
+    with_priority(Priority::Interactive, future).await
+}
+
+#[derive(Default)]
+struct Lanes {
+    interactive: usize,
+    background: usize,
+    max_background: Option<usize>,
+}
+
+static LANES: Lazy<Mutex<HashMap<i32, Lanes>>> = Lazy::new(Mutex::default);
+
+static RELEASED: Notify = Notify::const_new();
+
+fn with_lanes<T>(client_id: i32, f: impl FnOnce(&mut Lanes) -> T) -> T {
+    let mut lanes = LANES.lock().unwrap_or_else(|e| e.into_inner());
+    f(lanes.entry(client_id).or_default())
+}
+
+/// Set how many background requests of the client are sent at a time,
+/// [`DEFAULT_MAX_BACKGROUND`] by default, at least one.
+pub fn set_max_background(client_id: i32, max_background: usize) {
+    with_lanes(client_id, |lanes| {
+        lanes.max_background = Some(max_background.max(1))
+    });
+    RELEASED.notify_waiters();
+}
+
+/// A request sent, until its response is received.
+pub(crate) struct Permit {
+    client_id: i32,
+    priority: Priority,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        with_lanes(self.client_id, |lanes| match self.priority {
+            Priority::Interactive => lanes.interactive -= 1,
+            Priority::Background => lanes.background -= 1,
+        });
+        RELEASED.notify_waiters();
+    }
+}
+
+/// Wait until a request of the client at the priority can be sent.
+pub(crate) async fn acquire(client_id: i32, priority: Priority) -> Permit {
+    loop {
+        let mut released = pin!(RELEASED.notified());
+        released.as_mut().enable();
+        let acquired = with_lanes(client_id, |lanes| match priority {
+            Priority::Interactive => {
+                lanes.interactive += 1;
+                true
+            }
+            Priority::Background => {
+                let max_background = lanes.max_background.unwrap_or(DEFAULT_MAX_BACKGROUND);
+                let free = lanes.interactive == 0 && lanes.background < max_background;
+                if free {
+                    lanes.background += 1;
+                }
+                free
+            }
+        });
+        if acquired {
+            return Permit {
+                client_id,
+                priority,
+            };
+        }
+        released.await;
+    }
+}
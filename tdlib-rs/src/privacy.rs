@@ -0,0 +1,195 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The privacy settings of the user, as an audience with exceptions instead
+//! of the ordered rules of TdLib.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::enums::UserPrivacySetting;
+//! use tdlib_rs::privacy::{self, Audience, PrivacyRules};
+//!
+//! # async fn hide_phone_number(client_id: i32) -> Result<(), tdlib_rs::TdError> {
+//! # let friend = Default::default();
+//! let rules = PrivacyRules::new(Audience::Nobody).allow_users(vec![friend]);
+//! privacy::set(UserPrivacySetting::ShowPhoneNumber, rules, client_id).await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::enums::{self, UserPrivacySetting, UserPrivacySettingRule};
+use crate::ids::{ChatId, UserId};
+use crate::{functions, types, TdError};
+
+/// Who is allowed by a setting, apart from its exceptions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Audience {
+    /// Every user.
+    Everybody,
+    /// The contacts of the user.
+    Contacts,
+    /// No user.
+    #[default]
+    Nobody,
+}
+
+/// The rules of a privacy setting.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrivacyRules {
+    /// Who is allowed, apart from the exceptions.
+    pub audience: Audience,
+    /// The users allowed whatever the audience.
+    pub allowed_users: Vec<UserId>,
+    /// The chats whose members are allowed whatever the audience.
+    pub allowed_chat_members: Vec<ChatId>,
+    /// Whether the Telegram Premium users are allowed whatever the audience.
+    pub allow_premium_users: bool,
+    /// The users denied whatever the audience, even if allowed as members of
+    /// a chat.
+    pub restricted_users: Vec<UserId>,
+    /// The chats whose members are denied whatever the audience.
+    pub restricted_chat_members: Vec<ChatId>,
+}
+
+impl PrivacyRules {
+    /// Create the rules allowing the audience, without exceptions.
+    pub fn new(audience: Audience) -> Self {
+        Self {
+            audience,
+            ..Default::default()
+        }
+    }
+
+    /// Allow the users.
+    pub fn allow_users(mut self, user_ids: Vec<UserId>) -> Self {
+        self.allowed_users.extend(user_ids);
+        self
+    }
+
+    /// Allow the members of the basic groups and supergroups.
+    pub fn allow_chat_members(mut self, chat_ids: Vec<ChatId>) -> Self {
+        self.allowed_chat_members.extend(chat_ids);
+        self
+    }
+
+    /// Allow the Telegram Premium users.
+    pub fn allow_premium_users(mut self) -> Self {
+        self.allow_premium_users = true;
+        self
+    }
+
+    /// Deny the users.
+    pub fn restrict_users(mut self, user_ids: Vec<UserId>) -> Self {
+        self.restricted_users.extend(user_ids);
+        self
+    }
+
+    /// Deny the members of the basic groups and supergroups.
+    pub fn restrict_chat_members(mut self, chat_ids: Vec<ChatId>) -> Self {
+        self.restricted_chat_members.extend(chat_ids);
+        self
+    }
+
+    /// Returns the rules of TdLib, the exceptions of the users first, then
+    /// those of the chats, then the audience.
+    pub fn to_rules(&self) -> Vec<UserPrivacySettingRule> {
+        let mut rules = Vec::new();
+        if !self.restricted_users.is_empty() {
+            rules.push(UserPrivacySettingRule::RestrictUsers(td_struct!(
+                types::UserPrivacySettingRuleRestrictUsers {
+                    user_ids: self.restricted_users.clone()
+                }
+            )));
+        }
+        if !self.allowed_users.is_empty() {
+            rules.push(UserPrivacySettingRule::AllowUsers(td_struct!(
+                types::UserPrivacySettingRuleAllowUsers {
+                    user_ids: self.allowed_users.clone()
+                }
+            )));
+        }
+        if !self.restricted_chat_members.is_empty() {
+            rules.push(UserPrivacySettingRule::RestrictChatMembers(td_struct!(
+                types::UserPrivacySettingRuleRestrictChatMembers {
+                    chat_ids: self.restricted_chat_members.clone()
+                }
+            )));
+        }
+        if !self.allowed_chat_members.is_empty() {
+            rules.push(UserPrivacySettingRule::AllowChatMembers(td_struct!(
+                types::UserPrivacySettingRuleAllowChatMembers {
+                    chat_ids: self.allowed_chat_members.clone()
+                }
+            )));
+        }
+        if self.allow_premium_users {
+            rules.push(UserPrivacySettingRule::AllowPremiumUsers);
+        }
+        rules.push(match self.audience {
+            Audience::Everybody => UserPrivacySettingRule::AllowAll,
+            Audience::Contacts => UserPrivacySettingRule::AllowContacts,
+            Audience::Nobody => UserPrivacySettingRule::RestrictAll,
+        });
+        rules
+    }
+
+    /// Returns the rules read from those of TdLib. The audience is the one of
+    /// the first rule about every user or the contacts, or nobody if none.
+    pub fn from_rules(rules: Vec<UserPrivacySettingRule>) -> Self {
+        let mut privacy = Self::default();
+        let mut audience = None;
+        for rule in rules {
+            match rule {
+                UserPrivacySettingRule::AllowAll => {
+                    audience.get_or_insert(Audience::Everybody);
+                }
+                UserPrivacySettingRule::AllowContacts => {
+                    audience.get_or_insert(Audience::Contacts);
+                }
+                UserPrivacySettingRule::RestrictAll => {
+                    audience.get_or_insert(Audience::Nobody);
+                }
+                UserPrivacySettingRule::RestrictContacts => {}
+                UserPrivacySettingRule::AllowPremiumUsers => privacy.allow_premium_users = true,
+                UserPrivacySettingRule::AllowUsers(rule) => {
+                    privacy.allowed_users.extend(rule.user_ids);
+                }
+                UserPrivacySettingRule::AllowChatMembers(rule) => {
+                    privacy.allowed_chat_members.extend(rule.chat_ids);
+                }
+                UserPrivacySettingRule::RestrictUsers(rule) => {
+                    privacy.restricted_users.extend(rule.user_ids);
+                }
+                UserPrivacySettingRule::RestrictChatMembers(rule) => {
+                    privacy.restricted_chat_members.extend(rule.chat_ids);
+                }
+                UserPrivacySettingRule::Unsupported => {}
+            }
+        }
+        privacy.audience = audience.unwrap_or_default();
+        privacy
+    }
+}
+
+/// Returns the rules of the setting.
+pub async fn get(setting: UserPrivacySetting, client_id: i32) -> Result<PrivacyRules, TdError> {
+    let enums::UserPrivacySettingRules::UserPrivacySettingRules(rules) =
+        functions::get_user_privacy_setting_rules(setting, client_id).await?;
+    Ok(PrivacyRules::from_rules(rules.rules))
+}
+
+/// Replace the rules of the setting.
+pub async fn set(
+    setting: UserPrivacySetting,
+    rules: PrivacyRules,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let rules = td_struct!(types::UserPrivacySettingRules {
+        rules: rules.to_rules()
+    });
+    functions::set_user_privacy_setting_rules(setting, rules, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,170 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Probe the dimensions of the local photos and videos, and generate the
+//! thumbnails of the photos, before they are sent.
+use crate::enums::InputFile;
+use crate::types;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The largest side of the thumbnails, as required by Telegram.
+const THUMBNAIL_SIZE: u32 = 320;
+
+/// Returns the width and the height of the image.
+pub(crate) fn image_dimensions(path: &Path) -> Option<(i32, i32)> {
+    match image::image_dimensions(path) {
+        Ok((width, height)) => Some((width as i32, height as i32)),
+        Err(e) => {
+            log::warn!("Failed to probe the image {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn thumbnail_path(path: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    std::env::temp_dir()
+        .join("tdlib-rs-thumbnails")
+        .join(format!("{:016x}.jpg", hasher.finish()))
+}
+
+/// Returns a JPEG thumbnail of the image, written in the temporary
+/// directory.
+pub(crate) fn image_thumbnail(path: &Path) -> Option<types::InputThumbnail> {
+    let write = || -> image::ImageResult<(PathBuf, u32, u32)> {
+        let thumbnail = image::open(path)?
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+            .into_rgb8();
+        let thumbnail_path = thumbnail_path(path);
+        if let Some(dir) = thumbnail_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        thumbnail.save_with_format(&thumbnail_path, image::ImageFormat::Jpeg)?;
+        Ok((thumbnail_path, thumbnail.width(), thumbnail.height()))
+    };
+    match write() {
+        Ok((thumbnail_path, width, height)) => Some(td_struct!(types::InputThumbnail {
+            thumbnail: InputFile::Local(td_struct!(types::InputFileLocal {
+                path: thumbnail_path.to_string_lossy().into_owned(),
+            })),
+            width: width as i32,
+            height: height as i32,
+        })),
+        Err(e) => {
+            log::warn!(
+                "Failed to generate the thumbnail of {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// The dimensions and the duration of a video.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct VideoInfo {
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) duration: i32,
+}
+
+/// Returns the dimensions and the duration of the MP4 or QuickTime video,
+/// read from its `mvhd` and `tkhd` boxes.
+pub(crate) fn video_info(path: &Path) -> Option<VideoInfo> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let (moov, moov_len) = find_box(&mut file, 0, len, b"moov").ok()??;
+    let mut info = VideoInfo::default();
+
+    if let Some((mvhd, _)) = find_box(&mut file, moov, moov_len, b"mvhd").ok()? {
+        let version = read_bytes::<4>(&mut file, mvhd).ok()?[0];
+        let (timescale, duration) = if version == 1 {
+            let timescale = u32::from_be_bytes(read_bytes(&mut file, mvhd + 20).ok()?);
+            let duration = u64::from_be_bytes(read_bytes(&mut file, mvhd + 24).ok()?);
+            (timescale, duration)
+        } else {
+            let timescale = u32::from_be_bytes(read_bytes(&mut file, mvhd + 12).ok()?);
+            let duration = u32::from_be_bytes(read_bytes(&mut file, mvhd + 16).ok()?) as u64;
+            (timescale, duration)
+        };
+        if timescale != 0 {
+            info.duration = (duration / timescale as u64) as i32;
+        }
+    }
+
+    // The dimensions are those of the first track having some, the video
+    let mut offset = moov;
+    while let Some((trak, trak_len)) =
+        find_box(&mut file, offset, moov + moov_len - offset, b"trak")
+            .ok()
+            .flatten()
+    {
+        offset = trak + trak_len;
+        let Some((tkhd, _)) = find_box(&mut file, trak, trak_len, b"tkhd").ok().flatten() else {
+            continue;
+        };
+        let version = read_bytes::<4>(&mut file, tkhd).ok()?[0];
+        let dimensions = tkhd + if version == 1 { 88 } else { 76 };
+        let dimensions = read_bytes::<8>(&mut file, dimensions).ok()?;
+        let width =
+            u32::from_be_bytes([dimensions[0], dimensions[1], dimensions[2], dimensions[3]]);
+        let height =
+            u32::from_be_bytes([dimensions[4], dimensions[5], dimensions[6], dimensions[7]]);
+        // The dimensions are 16.16 fixed-point numbers
+        if width >> 16 != 0 && height >> 16 != 0 {
+            info.width = (width >> 16) as i32;
+            info.height = (height >> 16) as i32;
+            break;
+        }
+    }
+    Some(info)
+}
+
+fn read_bytes<const N: usize>(file: &mut File, offset: u64) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Returns the offset and the length of the content of the first box of
+/// the kind among the boxes in the range.
+fn find_box(
+    file: &mut File,
+    start: u64,
+    len: u64,
+    kind: &[u8; 4],
+) -> io::Result<Option<(u64, u64)>> {
+    let end = start + len;
+    let mut offset = start;
+    while offset + 8 <= end {
+        let header = read_bytes::<8>(file, offset)?;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            size = u64::from_be_bytes(read_bytes(file, offset + 8)?);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - offset;
+        }
+        if size < header_len || offset + size > end {
+            return Ok(None);
+        }
+        if &header[4..] == kind {
+            return Ok(Some((offset + header_len, size - header_len)));
+        }
+        offset += size;
+    }
+    Ok(None)
+}
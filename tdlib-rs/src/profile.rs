@@ -0,0 +1,134 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The profile of the user: name, bio, birthdate and personal chat, checked
+//! before any request, and the full information of the users, kept up to
+//! date from `updateUserFullInfo` once requested.
+use crate::enums::{self, Update};
+use crate::ids::{ChatId, UserId};
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// The maximum length of the first and the last name, in characters.
+pub const MAX_NAME_LENGTH: usize = 64;
+
+static FULL_INFOS: Lazy<RwLock<HashMap<(i32, UserId), types::UserFullInfo>>> =
+    Lazy::new(RwLock::default);
+
+/// Keep the full information of the users up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::UserFullInfo(update) = update {
+        FULL_INFOS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((client_id, update.user_id), update.user_full_info.clone());
+    }
+}
+
+/// Returns the full information of the user, if already requested.
+pub fn cached_full_info(user_id: UserId, client_id: i32) -> Option<types::UserFullInfo> {
+    FULL_INFOS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(client_id, user_id))
+        .cloned()
+}
+
+/// Returns the full information of the user, requesting it only the first
+/// time.
+pub async fn full_info(user_id: UserId, client_id: i32) -> Result<types::UserFullInfo, TdError> {
+    if let Some(full_info) = cached_full_info(user_id, client_id) {
+        return Ok(full_info);
+    }
+    let enums::UserFullInfo::UserFullInfo(full_info) =
+        functions::get_user_full_info(user_id, client_id).await?;
+    FULL_INFOS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert((client_id, user_id), full_info.clone());
+    Ok(full_info)
+}
+
+fn days_in_month(month: i32, year: i32) -> i32 {
+    match month {
+        2 if year == 0 || (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the birthdate, failing if the day isn't one of the month, or the
+/// month isn't from 1 to 12. The year is 0 if unknown, allowing February 29.
+pub fn birthdate(day: i32, month: i32, year: i32) -> Result<types::Birthdate, TdError> {
+    if !(1..=12).contains(&month) {
+        return Err(TdError::invalid(400, "The month must be from 1 to 12"));
+    }
+    if !(1..=days_in_month(month, year)).contains(&day) {
+        return Err(TdError::invalid(400, "The day is not a day of the month"));
+    }
+    if year < 0 {
+        return Err(TdError::invalid(
+            400,
+            "The year must be positive, or 0 if unknown",
+        ));
+    }
+    Ok(td_struct!(types::Birthdate { day, month, year }))
+}
+
+/// Change the birthdate of the user, or remove it if `None`. Fails without
+/// a request if the birthdate isn't valid, as checked by [`birthdate`].
+pub async fn set_birthdate(
+    birthdate: Option<types::Birthdate>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let birthdate = birthdate
+        .map(|date| self::birthdate(date.day, date.month, date.year))
+        .transpose()?;
+    functions::set_birthdate(birthdate, client_id).await?;
+    Ok(())
+}
+
+/// Change the chat shown on the profile of the user, or remove it if
+/// `None`.
+pub async fn set_personal_chat(chat_id: Option<ChatId>, client_id: i32) -> Result<(), TdError> {
+    functions::set_personal_chat(chat_id.unwrap_or_default(), client_id).await?;
+    Ok(())
+}
+
+/// Change the name of the user. Fails without a request if the first name
+/// is empty or if either is longer than [`MAX_NAME_LENGTH`].
+pub async fn set_name(
+    first_name: String,
+    last_name: String,
+    client_id: i32,
+) -> Result<(), TdError> {
+    if first_name.is_empty() {
+        return Err(TdError::invalid(400, "The first name must not be empty"));
+    }
+    if first_name.chars().count() > MAX_NAME_LENGTH || last_name.chars().count() > MAX_NAME_LENGTH {
+        return Err(TdError::invalid(
+            400,
+            "The names must be at most 64 characters long",
+        ));
+    }
+    functions::set_name(first_name, last_name, client_id).await?;
+    Ok(())
+}
+
+/// Change the bio of the user. Fails without a request if it contains a
+/// line feed.
+pub async fn set_bio(bio: String, client_id: i32) -> Result<(), TdError> {
+    if bio.contains('\n') {
+        return Err(TdError::invalid(400, "The bio must not contain line feeds"));
+    }
+    functions::set_bio(bio, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Change the profile photo of the user, and download the photos of the
+//! users and the chats.
+use crate::enums::{self, InputChatPhoto, InputFile, MessageSender};
+use crate::{functions, identity, types, TdError};
+
+/// The priority of the downloads of the photos, from 1 to 32.
+const DOWNLOAD_PRIORITY: i32 = 16;
+
+/// The size of a photo to download.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PhotoSize {
+    /// The photo of 160x160 pixels.
+    #[default]
+    Small,
+    /// The photo of 640x640 pixels.
+    Big,
+}
+
+fn local_file(path: String) -> InputFile {
+    InputFile::Local(td_struct!(types::InputFileLocal { path }))
+}
+
+/// Set the photo at the path as the profile photo of the user.
+pub async fn set_profile_photo(path: impl Into<String>, client_id: i32) -> Result<(), TdError> {
+    let photo = InputChatPhoto::Static(td_struct!(types::InputChatPhotoStatic {
+        photo: local_file(path.into())
+    }));
+    functions::set_profile_photo(photo, false, client_id).await?;
+    Ok(())
+}
+
+/// Set the MPEG4 animation at the path as the profile photo of the user,
+/// shown as a still image at `main_frame_timestamp`, in seconds.
+pub async fn set_profile_animation(
+    path: impl Into<String>,
+    main_frame_timestamp: f64,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let photo = InputChatPhoto::Animation(td_struct!(types::InputChatPhotoAnimation {
+        animation: local_file(path.into()),
+        main_frame_timestamp
+    }));
+    functions::set_profile_photo(photo, false, client_id).await?;
+    Ok(())
+}
+
+/// Delete the current profile photo of the user, if any. The previous photo
+/// becomes the current one.
+pub async fn delete_profile_photo(client_id: i32) -> Result<(), TdError> {
+    if let Some(photo) = identity::me(client_id).await?.profile_photo {
+        functions::delete_profile_photo(photo.id, client_id).await?;
+    }
+    Ok(())
+}
+
+/// Download the photo of the user or the chat, returning the path of its
+/// local file, or `None` if there is no photo. Waits until the download is
+/// completed, immediately if already downloaded.
+pub async fn download_avatar(
+    sender: &MessageSender,
+    size: PhotoSize,
+    client_id: i32,
+) -> Result<Option<String>, TdError> {
+    let (small, big) = match sender {
+        MessageSender::User(sender) => {
+            let enums::User::User(user) = functions::get_user(sender.user_id, client_id).await?;
+            match user.profile_photo {
+                Some(photo) => (photo.small, photo.big),
+                None => return Ok(None),
+            }
+        }
+        MessageSender::Chat(sender) => {
+            let enums::Chat::Chat(chat) = functions::get_chat(sender.chat_id, client_id).await?;
+            match chat.photo {
+                Some(photo) => (photo.small, photo.big),
+                None => return Ok(None),
+            }
+        }
+        MessageSender::Unsupported => return Ok(None),
+    };
+    let file = match size {
+        PhotoSize::Small => small,
+        PhotoSize::Big => big,
+    };
+    if file.local.is_downloading_completed {
+        return Ok(Some(file.local.path));
+    }
+
+    let enums::File::File(file) =
+        functions::download_file(file.id, DOWNLOAD_PRIORITY, 0, 0, true, client_id).await?;
+    Ok(Some(file.local.path))
+}
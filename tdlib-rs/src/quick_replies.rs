@@ -0,0 +1,173 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The quick reply shortcuts of the business accounts, tracked from the
+//! updates sent for them, and helpers to manage and send them.
+//!
+//! TdLib sends the shortcuts only as updates, after [`load_shortcuts`], and
+//! the messages of a shortcut after [`load_messages`].
+use crate::enums::{self, InputMessageContent, Update};
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, rate_limit, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct QuickReplies {
+    shortcuts: Vec<types::QuickReplyShortcut>,
+    messages: HashMap<i32, Vec<types::QuickReplyMessage>>,
+}
+
+static QUICK_REPLIES: Lazy<RwLock<HashMap<i32, QuickReplies>>> = Lazy::new(RwLock::default);
+
+/// Returns the shortcuts of the client, in order.
+pub fn shortcuts(client_id: i32) -> Vec<types::QuickReplyShortcut> {
+    QUICK_REPLIES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .map(|quick_replies| quick_replies.shortcuts.clone())
+        .unwrap_or_default()
+}
+
+/// Returns the shortcut with the name, if any.
+pub fn shortcut(name: &str, client_id: i32) -> Option<types::QuickReplyShortcut> {
+    shortcuts(client_id)
+        .into_iter()
+        .find(|shortcut| shortcut.name == name)
+}
+
+/// Returns the messages of the shortcut, if they were loaded.
+pub fn messages(shortcut_id: i32, client_id: i32) -> Option<Vec<types::QuickReplyMessage>> {
+    QUICK_REPLIES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)?
+        .messages
+        .get(&shortcut_id)
+        .cloned()
+}
+
+/// Keep the shortcuts of the client and their messages up to date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if !matches!(
+        update,
+        Update::QuickReplyShortcut(_)
+            | Update::QuickReplyShortcutDeleted(_)
+            | Update::QuickReplyShortcuts(_)
+            | Update::QuickReplyShortcutMessages(_)
+    ) {
+        return;
+    }
+
+    let mut quick_replies = QUICK_REPLIES.write().unwrap_or_else(|e| e.into_inner());
+    let quick_replies = quick_replies.entry(client_id).or_default();
+    match update {
+        Update::QuickReplyShortcut(update) => {
+            let shortcuts = &mut quick_replies.shortcuts;
+            match shortcuts.iter_mut().find(|s| s.id == update.shortcut.id) {
+                Some(shortcut) => *shortcut = update.shortcut.clone(),
+                None => shortcuts.push(update.shortcut.clone()),
+            }
+        }
+        Update::QuickReplyShortcutDeleted(update) => {
+            quick_replies
+                .shortcuts
+                .retain(|shortcut| shortcut.id != update.shortcut_id);
+            quick_replies.messages.remove(&update.shortcut_id);
+        }
+        Update::QuickReplyShortcuts(update) => {
+            let mut shortcuts = std::mem::take(&mut quick_replies.shortcuts);
+            for id in &update.shortcut_ids {
+                if let Some(index) = shortcuts.iter().position(|s| s.id == *id) {
+                    quick_replies.shortcuts.push(shortcuts.swap_remove(index));
+                }
+            }
+            quick_replies
+                .messages
+                .retain(|id, _| update.shortcut_ids.contains(id));
+        }
+        Update::QuickReplyShortcutMessages(update) => {
+            quick_replies
+                .messages
+                .insert(update.shortcut_id, update.messages.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Ask TdLib to send the shortcuts, tracked by [`shortcuts`].
+pub async fn load_shortcuts(client_id: i32) -> Result<(), TdError> {
+    functions::load_quick_reply_shortcuts(client_id).await?;
+    Ok(())
+}
+
+/// Ask TdLib to send the messages of a shortcut, tracked by [`messages`].
+pub async fn load_messages(shortcut_id: i32, client_id: i32) -> Result<(), TdError> {
+    functions::load_quick_reply_shortcut_messages(shortcut_id, client_id).await?;
+    Ok(())
+}
+
+/// Send the messages of a shortcut to the chat, which must be a private chat
+/// with a regular user. Each message is counted by the limiter enabled with
+/// [`rate_limit::enable`] before the request is sent.
+pub async fn send_shortcut(
+    chat_id: ChatId,
+    shortcut_id: i32,
+    client_id: i32,
+) -> Result<Vec<types::Message>, TdError> {
+    let count = match messages(shortcut_id, client_id) {
+        Some(messages) => messages.len(),
+        None => shortcuts(client_id)
+            .iter()
+            .find(|shortcut| shortcut.id == shortcut_id)
+            .map_or(1, |shortcut| shortcut.message_count as usize),
+    };
+    for _ in 0..count.max(1) {
+        rate_limit::acquire(chat_id, client_id).await;
+    }
+    static SENDING_ID: AtomicI32 = AtomicI32::new(1);
+    let sending_id = SENDING_ID.fetch_add(1, Ordering::Relaxed);
+    let enums::Messages::Messages(messages) =
+        functions::send_quick_reply_shortcut_messages(chat_id, shortcut_id, sending_id, client_id)
+            .await?;
+    Ok(messages.messages.into_iter().flatten().collect())
+}
+
+/// Add a message to the shortcut with the name, creating the shortcut if it
+/// doesn't exist.
+pub async fn add_message(
+    shortcut_name: String,
+    reply_to_message_id: MessageId,
+    input_message_content: InputMessageContent,
+    client_id: i32,
+) -> Result<types::QuickReplyMessage, TdError> {
+    let enums::QuickReplyMessage::QuickReplyMessage(message) =
+        functions::add_quick_reply_shortcut_message(
+            shortcut_name,
+            reply_to_message_id,
+            input_message_content,
+            client_id,
+        )
+        .await?;
+    Ok(message)
+}
+
+/// Rename a shortcut.
+pub async fn rename(shortcut_id: i32, name: String, client_id: i32) -> Result<(), TdError> {
+    functions::set_quick_reply_shortcut_name(shortcut_id, name, client_id).await?;
+    Ok(())
+}
+
+/// Delete a shortcut with its messages.
+pub async fn delete_shortcut(shortcut_id: i32, client_id: i32) -> Result<(), TdError> {
+    functions::delete_quick_reply_shortcut(shortcut_id, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,323 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Delay the outgoing messages so that they stay within the limits of
+//! Telegram, instead of waiting for the flood errors of TdLib.
+//!
+//! A [`RateLimiter`] can be used directly, awaiting [`RateLimiter::acquire`]
+//! before each request, or it can be enabled globally with [`enable`] to
+//! delay the messages sent by the helpers of the library, such as those of
+//! the `ordered`, `outbox`, `forward` and `quick_replies` modules, each
+//! message being counted once.
+use crate::ids::ChatId;
+use crate::{enums, functions, runtime, types};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// The number of windows after which those left empty are discarded.
+const SWEEP_THRESHOLD: usize = 1024;
+
+/// At most `requests` requests in any `period`. A rate of zero requests
+/// disables the limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rate {
+    /// The maximum number of requests.
+    pub requests: usize,
+    /// The duration of the window in which the requests are counted.
+    pub period: Duration,
+}
+
+impl Rate {
+    /// Create a rate of at most `requests` requests in any `period`.
+    pub const fn new(requests: usize, period: Duration) -> Self {
+        Self { requests, period }
+    }
+}
+
+/// The rates enforced by a [`RateLimiter`] for each client. The default ones
+/// are the limits documented by Telegram for bots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The rate of all the messages sent by the client.
+    pub global: Rate,
+    /// The rate of the messages sent to each chat.
+    pub per_chat: Rate,
+    /// The rate of the messages sent to each group or channel, which are the
+    /// chats with a negative identifier, in addition to `per_chat`.
+    pub per_group: Rate,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            global: Rate::new(30, Duration::from_secs(1)),
+            per_chat: Rate::new(1, Duration::from_secs(1)),
+            per_group: Rate::new(20, Duration::from_secs(60)),
+        }
+    }
+}
+
+/// The instants of the requests sent in the last period of a rate.
+#[derive(Default)]
+struct Window(VecDeque<Instant>);
+
+impl Window {
+    /// Returns how long to wait before a request can be sent.
+    fn delay(&mut self, rate: &Rate, now: Instant) -> Duration {
+        while self
+            .0
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= rate.period)
+        {
+            self.0.pop_front();
+        }
+        if rate.requests == 0 || self.0.len() < rate.requests {
+            return Duration::ZERO;
+        }
+        let oldest = self.0[self.0.len() - rate.requests];
+        (oldest + rate.period).saturating_duration_since(now)
+    }
+}
+
+#[derive(Default)]
+struct Windows {
+    global: HashMap<i32, Window>,
+    chats: HashMap<(i32, ChatId), Window>,
+    groups: HashMap<(i32, ChatId), Window>,
+}
+
+impl Windows {
+    fn sweep(&mut self) {
+        if self.chats.len() > SWEEP_THRESHOLD {
+            self.chats.retain(|_, window| !window.0.is_empty());
+        }
+        if self.groups.len() > SWEEP_THRESHOLD {
+            self.groups.retain(|_, window| !window.0.is_empty());
+        }
+    }
+}
+
+/// Enforces [`Limits`] on the messages sent by each client.
+pub struct RateLimiter {
+    limits: Limits,
+    windows: Mutex<Windows>,
+}
+
+impl RateLimiter {
+    /// Create a limiter enforcing the limits.
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            windows: Mutex::default(),
+        }
+    }
+
+    /// Returns the limits enforced by the limiter.
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Wait until a message can be sent to the chat without exceeding the
+    /// limits, and count it as sent.
+    pub async fn acquire(&self, chat_id: ChatId, client_id: i32) {
+        #[allow(clippy::useless_conversion)]
+        let is_group = i64::from(chat_id) < 0;
+
+        loop {
+            let delay = {
+                let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+                windows.sweep();
+
+                let now = Instant::now();
+                let key = (client_id, chat_id);
+                let mut delay = windows
+                    .global
+                    .entry(client_id)
+                    .or_default()
+                    .delay(&self.limits.global, now);
+                delay = delay.max(
+                    windows
+                        .chats
+                        .entry(key)
+                        .or_default()
+                        .delay(&self.limits.per_chat, now),
+                );
+                if is_group {
+                    delay = delay.max(
+                        windows
+                            .groups
+                            .entry(key)
+                            .or_default()
+                            .delay(&self.limits.per_group, now),
+                    );
+                }
+
+                if delay.is_zero() {
+                    windows
+                        .global
+                        .entry(client_id)
+                        .or_default()
+                        .0
+                        .push_back(now);
+                    windows.chats.entry(key).or_default().0.push_back(now);
+                    if is_group {
+                        windows.groups.entry(key).or_default().0.push_back(now);
+                    }
+                    return;
+                }
+                delay
+            };
+            runtime::sleep(delay).await;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(Limits::default())
+    }
+}
+
+static GLOBAL: Lazy<RwLock<Option<Arc<RateLimiter>>>> = Lazy::new(RwLock::default);
+
+/// Delay the messages sent by the helpers of the library to enforce the
+/// limits.
+pub fn enable(limits: Limits) {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(RateLimiter::new(limits)));
+}
+
+/// Stop delaying the messages sent by the helpers of the library.
+pub fn disable() {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Wait for the globally enabled limiter, if any.
+pub(crate) async fn acquire(chat_id: ChatId, client_id: i32) {
+    let limiter = GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(limiter) = limiter {
+        limiter.acquire(chat_id, client_id).await;
+    }
+}
+
+/// Returns the future of [`functions::send_message`], without waiting for the
+/// limiter. The reply markup can only be sent by the bots, so it is ignored
+/// unless the `bots-only-api` feature is enabled.
+pub(crate) fn send_message_request(
+    chat_id: ChatId,
+    message_thread_id: i64,
+    reply_to: Option<enums::InputMessageReplyTo>,
+    options: Option<types::MessageSendOptions>,
+    reply_markup: Option<enums::ReplyMarkup>,
+    input_message_content: enums::InputMessageContent,
+    client_id: i32,
+) -> impl Future<Output = Result<enums::Message, types::Error>> {
+    #[cfg(feature = "bots-only-api")]
+    return functions::send_message(
+        chat_id,
+        message_thread_id,
+        reply_to,
+        options,
+        reply_markup,
+        input_message_content,
+        client_id,
+    );
+    #[cfg(not(feature = "bots-only-api"))]
+    {
+        let _ = reply_markup;
+        functions::send_message(
+            chat_id,
+            message_thread_id,
+            reply_to,
+            options,
+            input_message_content,
+            client_id,
+        )
+    }
+}
+
+/// Same as [`functions::send_message`], waiting for the globally enabled
+/// limiter first, see [`send_message_request`] for the reply markup.
+pub(crate) async fn send_message(
+    chat_id: ChatId,
+    message_thread_id: i64,
+    reply_to: Option<enums::InputMessageReplyTo>,
+    options: Option<types::MessageSendOptions>,
+    reply_markup: Option<enums::ReplyMarkup>,
+    input_message_content: enums::InputMessageContent,
+    client_id: i32,
+) -> Result<enums::Message, types::Error> {
+    acquire(chat_id, client_id).await;
+    send_message_request(
+        chat_id,
+        message_thread_id,
+        reply_to,
+        options,
+        reply_markup,
+        input_message_content,
+        client_id,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_window_delay() {
+        let rate = Rate::new(2, Duration::from_secs(1));
+        let start = Instant::now();
+        let mut window = Window::default();
+        assert_eq!(window.delay(&rate, start), Duration::ZERO);
+        window.0.extend([start, start + Duration::from_millis(400)]);
+
+        let now = start + Duration::from_millis(500);
+        assert_eq!(window.delay(&rate, now), Duration::from_millis(500));
+        // The oldest request leaves the window after its period
+        let now = start + Duration::from_secs(1);
+        assert_eq!(window.delay(&rate, now), Duration::ZERO);
+        assert_eq!(window.0.len(), 1);
+    }
+
+    #[test]
+    fn check_unlimited_rate() {
+        let rate = Rate::new(0, Duration::from_secs(1));
+        let now = Instant::now();
+        let mut window = Window(VecDeque::from(vec![now; 100]));
+        assert_eq!(window.delay(&rate, now), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn check_limits() {
+        let period = Duration::from_millis(200);
+        let limiter = RateLimiter::new(Limits {
+            global: Rate::new(3, period),
+            per_chat: Rate::new(1, period),
+            per_group: Rate::new(2, period * 2),
+        });
+        let start = Instant::now();
+        #[allow(clippy::useless_conversion)]
+        let (user, group) = (ChatId::from(1), ChatId::from(-1));
+
+        limiter.acquire(user, 1).await;
+        limiter.acquire(group, 1).await;
+        // Another client has its own limits
+        limiter.acquire(user, 2).await;
+        assert!(start.elapsed() < period / 2);
+
+        limiter.acquire(user, 1).await;
+        assert!(start.elapsed() >= period);
+        limiter.acquire(group, 1).await;
+        let start = Instant::now();
+        limiter.acquire(group, 1).await;
+        assert!(start.elapsed() >= period);
+    }
+}
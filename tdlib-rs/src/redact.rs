@@ -0,0 +1,207 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hide the sensitive fields of the requests and responses before they are
+//! logged by the library, such as phone numbers, tokens and passwords.
+//!
+//! The fields are selected by name with patterns, where a leading or a
+//! trailing `*` matches any prefix or suffix: `*password` matches both
+//! `password` and `new_password`. A pattern prefixed by a type and a dot only
+//! matches the fields of the objects of this `@type`: `checkAuthenticationCode.code`
+//! matches the code sent to log in, but not the code of the errors. The
+//! requests and responses are logged at the
+//! `trace` level, and the JSON of the updates and responses which can't be
+//! deserialized at the `warn` level; both are redacted.
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::sync::RwLock;
+
+/// The value replacing the redacted fields.
+pub const REDACTED: &str = "<redacted>";
+
+/// The patterns of the fields redacted by default.
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    "*phone_number",
+    "*token",
+    "*password",
+    "*hint",
+    "checkAuthenticationCode.code",
+    "checkAuthenticationEmailCode.code",
+    "checkEmailAddressVerificationCode.code",
+    "checkLoginEmailAddressCode.code",
+    "checkPhoneNumberCode.code",
+    "checkRecoveryEmailAddressCode.code",
+    "emailAddressAuthenticationCode.code",
+    "*recovery_code",
+    "*encryption_key",
+    "api_hash",
+    "*email_address",
+    "*secret",
+];
+
+static PATTERNS: Lazy<RwLock<Vec<String>>> =
+    Lazy::new(|| RwLock::new(DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect()));
+
+/// Replace the patterns of the redacted fields. An empty list disables the
+/// redaction.
+pub fn set_patterns<I, S>(patterns: I)
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    *PATTERNS.write().unwrap_or_else(|e| e.into_inner()) =
+        patterns.into_iter().map(Into::into).collect();
+}
+
+/// Add a pattern to those of the redacted fields.
+pub fn add_pattern(pattern: impl Into<String>) {
+    PATTERNS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(pattern.into());
+}
+
+/// Returns the patterns of the redacted fields.
+pub fn patterns() -> Vec<String> {
+    PATTERNS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Returns `true` if the field of an object of the type matches the
+/// pattern, scoped to a type or not.
+fn matches_field(pattern: &str, ty: Option<&str>, field: &str) -> bool {
+    match pattern.split_once('.') {
+        Some((pattern_ty, pattern)) => ty == Some(pattern_ty) && matches(pattern, field),
+        None => matches(pattern, field),
+    }
+}
+
+fn matches(pattern: &str, field: &str) -> bool {
+    match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+        (Some(suffix), _) if suffix.ends_with('*') => field.contains(&suffix[..suffix.len() - 1]),
+        (Some(suffix), _) => field.ends_with(suffix),
+        (None, Some(prefix)) => field.starts_with(prefix),
+        (None, None) => field == pattern,
+    }
+}
+
+fn redact_in(value: &mut Value, patterns: &[String]) {
+    match value {
+        Value::Object(fields) => {
+            let ty = fields
+                .get("@type")
+                .and_then(Value::as_str)
+                .map(str::to_owned);
+            for (field, value) in fields.iter_mut() {
+                if patterns
+                    .iter()
+                    .any(|pattern| matches_field(pattern, ty.as_deref(), field))
+                {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_in(value, patterns);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact_in(value, patterns);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns a copy of the value with the sensitive fields redacted.
+pub fn redact_value(value: &Value) -> Value {
+    let mut value = value.clone();
+    redact_in(
+        &mut value,
+        &PATTERNS.read().unwrap_or_else(|e| e.into_inner()),
+    );
+    value
+}
+
+/// Returns the JSON with the sensitive fields redacted. A string which is
+/// not valid JSON is entirely redacted.
+pub fn redact(json: &str) -> String {
+    match serde_json::from_str::<Value>(json) {
+        Ok(value) => redact_value(&value).to_string(),
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redact_with(value: Value, patterns: &[&str]) -> Value {
+        let mut value = value;
+        let patterns = patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        redact_in(&mut value, &patterns);
+        value
+    }
+
+    fn defaults(value: Value) -> Value {
+        redact_with(value, DEFAULT_PATTERNS)
+    }
+
+    #[test]
+    fn check_patterns() {
+        assert!(matches("*password", "new_password"));
+        assert!(matches("*password", "password"));
+        assert!(!matches("*password", "password_hint"));
+        assert!(matches("phone*", "phone_number"));
+        assert!(matches("*token*", "a_token_b"));
+        assert!(matches("code", "code"));
+        assert!(!matches("code", "country_code"));
+    }
+
+    #[test]
+    fn check_auth_codes() {
+        let request = json!({ "@type": "checkAuthenticationCode", "code": "12345" });
+        assert_eq!(defaults(request)["code"], REDACTED);
+        let email = json!({
+            "@type": "checkAuthenticationEmailCode",
+            "code": { "@type": "emailAddressAuthenticationCode", "code": "abc" }
+        });
+        assert_eq!(defaults(email)["code"], REDACTED);
+        let recovery = json!({ "@type": "checkPasswordRecoveryCode", "recovery_code": "1" });
+        assert_eq!(defaults(recovery)["recovery_code"], REDACTED);
+    }
+
+    #[test]
+    fn check_errors_are_kept() {
+        let error = json!({ "@type": "error", "code": 429, "message": "Too Many Requests" });
+        assert_eq!(defaults(error.clone()), error);
+    }
+
+    #[test]
+    fn check_nested_values() {
+        let response = json!({
+            "@type": "users",
+            "users": [{ "@type": "user", "id": 1, "phone_number": "+1234" }],
+            "settings": { "api_hash": "x", "database_encryption_key": "y" }
+        });
+        let redacted = defaults(response);
+        assert_eq!(redacted["users"][0]["phone_number"], REDACTED);
+        assert_eq!(redacted["users"][0]["id"], 1);
+        assert_eq!(redacted["settings"]["api_hash"], REDACTED);
+        assert_eq!(redacted["settings"]["database_encryption_key"], REDACTED);
+    }
+
+    #[test]
+    fn check_scoped_patterns() {
+        let value = json!({ "@type": "a", "x": 1, "b": { "@type": "b", "x": 2 } });
+        let redacted = redact_with(value, &["b.x"]);
+        assert_eq!(redacted["x"], 1);
+        assert_eq!(redacted["b"]["x"], REDACTED);
+        assert_eq!(redact_with(json!("not an object"), &["x"]), "not an object");
+        assert_eq!(redact("{invalid"), REDACTED);
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The registry of the requests waiting for their response, keyed by their
+//! `@extra`. It is split into shards, each with its own lock, so that the
+//! concurrent requests rarely contend for the same one. It is public only to
+//! be benchmarked.
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The number of shards, a power of two so that consecutive keys, as the
+/// `@extra` of consecutive requests, are spread over all of them.
+const SHARDS: usize = 64;
+
+pub struct Registry<T> {
+    shards: Box<[Mutex<HashMap<u32, T>>]>,
+}
+
+impl<T> Registry<T> {
+    pub fn new() -> Self {
+        Self {
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    fn shard(&self, key: u32) -> &Mutex<HashMap<u32, T>> {
+        &self.shards[key as usize & (SHARDS - 1)]
+    }
+
+    pub fn insert(&self, key: u32, value: T) {
+        self.shard(key)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, value);
+    }
+
+    /// Insert the value unless the key is already registered, in which case
+    /// the value is given back and the registered one is kept.
+    pub fn try_insert(&self, key: u32, value: T) -> Result<(), T> {
+        match self
+            .shard(key)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+        {
+            Entry::Occupied(_) => Err(value),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn remove(&self, key: u32) -> Option<T> {
+        self.shard(key)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&key)
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
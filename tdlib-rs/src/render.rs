@@ -0,0 +1,422 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Render the formatted texts with their entities, into the escape
+//! sequences of the terminals with [`ansi`] or into HTML with [`html`], so
+//! that the messages can be displayed without slicing their text at the
+//! UTF-16 offsets of the entities.
+//!
+//! The overlapping entities are closed and opened again where needed, so
+//! that the result is always well nested. The custom emoji are rendered as
+//! the emoji of their text, which TdLib sends as their placeholder.
+//!
+//! The texts are sent by anyone, so they are rendered safely: the control
+//! characters, but the new lines and the tabs, are replaced in the terminals
+//! so that they can't inject their own escape sequences, and only the links
+//! to `http`, `https`, `tg`, `mailto` and `tel` URLs are kept in HTML.
+//!
+//! ```rust
+//! use tdlib_rs::{markdown, render};
+//!
+//! let text = markdown::parse("*Hello* [world](https://telegram.org)").unwrap();
+//! assert_eq!(
+//!     render::html(&text),
+//!     "<b>Hello</b> <a href=\"https://telegram.org\">world</a>"
+//! );
+//! assert_eq!(
+//!     render::ansi(&text),
+//!     "\x1b[1mHello\x1b[0m \x1b]8;;https://telegram.org\x1b\\\x1b[4;34mworld\x1b[0m\x1b]8;;\x1b\\"
+//! );
+//! ```
+use crate::enums::TextEntityType;
+use crate::{types, utf16};
+use std::cmp::Reverse;
+use std::fmt::Write;
+
+/// The way the entities and the text are written.
+trait Style {
+    /// Write the start of the entity, whose text is given.
+    fn open(&mut self, out: &mut String, entity: &TextEntityType, text: &str);
+    /// Write the end of the innermost entity.
+    fn close(&mut self, out: &mut String, entity: &TextEntityType);
+    /// Write a character of the text.
+    fn text(&mut self, out: &mut String, c: char);
+}
+
+/// Write the text with its entities, opening them at their offset and
+/// closing them at their end, innermost first.
+fn walk<S: Style>(text: &types::FormattedText, style: &mut S) -> String {
+    let entities = &text.entities;
+    let end = |index: usize| {
+        entities[index]
+            .offset
+            .saturating_add(entities[index].length)
+    };
+    let entity_text = |index: usize| utf16::entity_text(text, &entities[index]).unwrap_or_default();
+
+    let mut order = (0..entities.len())
+        .filter(|&index| entities[index].length > 0)
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&index| (entities[index].offset, Reverse(entities[index].length)));
+
+    let mut out = String::new();
+    let mut open: Vec<usize> = Vec::new();
+    let mut next = 0;
+    let mut offset = 0;
+    let mut chars = text.text.chars();
+    loop {
+        // Close the entities ended, with the ones opened inside them, and
+        // open again the latter if they didn't end
+        if let Some(position) = open.iter().position(|&index| end(index) <= offset) {
+            let closed = open.split_off(position);
+            for &index in closed.iter().rev() {
+                style.close(&mut out, &entities[index].r#type);
+            }
+            for &index in &closed[1..] {
+                if end(index) > offset {
+                    style.open(&mut out, &entities[index].r#type, entity_text(index));
+                    open.push(index);
+                }
+            }
+        }
+        while let Some(&index) = order.get(next).filter(|&&i| entities[i].offset <= offset) {
+            style.open(&mut out, &entities[index].r#type, entity_text(index));
+            open.push(index);
+            next += 1;
+        }
+
+        let Some(c) = chars.next() else {
+            break;
+        };
+        style.text(&mut out, c);
+        offset += c.len_utf16() as i32;
+    }
+    for &index in open.iter().rev() {
+        style.close(&mut out, &entities[index].r#type);
+    }
+    out
+}
+
+/// Whether the character can't be written as is to a terminal: a control
+/// character of C0, C1 or DEL, but the new line and the tab.
+fn is_unsafe_control(c: char) -> bool {
+    c.is_control() && c != '\n' && c != '\t'
+}
+
+/// The style of the entities in the terminals.
+#[derive(Default)]
+struct Ansi {
+    /// The parameters of the select graphic rendition of the open entities.
+    sgr: Vec<&'static str>,
+    quotes: usize,
+}
+
+impl Ansi {
+    fn sgr(entity: &TextEntityType) -> &'static str {
+        match entity {
+            TextEntityType::Bold => "1",
+            TextEntityType::Italic => "3",
+            TextEntityType::Underline => "4",
+            TextEntityType::Strikethrough => "9",
+            TextEntityType::Spoiler => "7",
+            TextEntityType::Code | TextEntityType::Pre | TextEntityType::PreCode(_) => "36",
+            TextEntityType::Url
+            | TextEntityType::TextUrl(_)
+            | TextEntityType::EmailAddress
+            | TextEntityType::PhoneNumber => "4;34",
+            TextEntityType::Mention
+            | TextEntityType::MentionName(_)
+            | TextEntityType::Hashtag
+            | TextEntityType::Cashtag
+            | TextEntityType::BotCommand
+            | TextEntityType::MediaTimestamp(_) => "34",
+            TextEntityType::BlockQuote => "2",
+            _ => "",
+        }
+    }
+
+    fn apply(&self, out: &mut String) {
+        let sgr = self
+            .sgr
+            .iter()
+            .filter(|sgr| !sgr.is_empty())
+            .copied()
+            .collect::<Vec<_>>();
+        if !sgr.is_empty() {
+            let _ = write!(out, "\x1b[{}m", sgr.join(";"));
+        }
+    }
+}
+
+impl Style for Ansi {
+    fn open(&mut self, out: &mut String, entity: &TextEntityType, _: &str) {
+        match entity {
+            TextEntityType::TextUrl(url) => {
+                let url = url
+                    .url
+                    .chars()
+                    .filter(|&c| !is_unsafe_control(c))
+                    .collect::<String>();
+                let _ = write!(out, "\x1b]8;;{}\x1b\\", url);
+            }
+            TextEntityType::BlockQuote => {
+                self.quotes += 1;
+                out.push_str("│ ");
+            }
+            _ => {}
+        }
+        self.sgr.push(Ansi::sgr(entity));
+        if !Ansi::sgr(entity).is_empty() {
+            let _ = write!(out, "\x1b[{}m", Ansi::sgr(entity));
+        }
+    }
+
+    fn close(&mut self, out: &mut String, entity: &TextEntityType) {
+        self.sgr.pop();
+        if !Ansi::sgr(entity).is_empty() {
+            out.push_str("\x1b[0m");
+            self.apply(out);
+        }
+        match entity {
+            TextEntityType::TextUrl(_) => out.push_str("\x1b]8;;\x1b\\"),
+            TextEntityType::BlockQuote => self.quotes -= 1,
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, out: &mut String, c: char) {
+        out.push(if is_unsafe_control(c) {
+            char::REPLACEMENT_CHARACTER
+        } else {
+            c
+        });
+        if c == '\n' && self.quotes > 0 {
+            out.push_str("│ ");
+        }
+    }
+}
+
+/// The schemes of the URLs kept as links in HTML.
+const SAFE_SCHEMES: [&str; 5] = ["http", "https", "tg", "mailto", "tel"];
+
+/// The style of the entities in HTML.
+#[derive(Default)]
+struct Html {
+    /// Whether each link entity open was written as a link, its URL being
+    /// safe.
+    links: Vec<bool>,
+}
+
+impl Html {
+    fn escape(out: &mut String, text: &str) {
+        for c in text.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// Returns `true` if the URL has one of the [`SAFE_SCHEMES`].
+    fn is_safe(href: &str) -> bool {
+        href.split_once(':').is_some_and(|(scheme, _)| {
+            SAFE_SCHEMES
+                .iter()
+                .any(|safe| scheme.eq_ignore_ascii_case(safe))
+        })
+    }
+
+    /// Open a link to the URL, unless it is unsafe.
+    fn link(&mut self, out: &mut String, href: &str) {
+        let safe = Html::is_safe(href);
+        if safe {
+            out.push_str("<a href=\"");
+            Html::escape(out, href);
+            out.push_str("\">");
+        }
+        self.links.push(safe);
+    }
+}
+
+impl Style for Html {
+    fn open(&mut self, out: &mut String, entity: &TextEntityType, text: &str) {
+        match entity {
+            TextEntityType::Bold => out.push_str("<b>"),
+            TextEntityType::Italic => out.push_str("<i>"),
+            TextEntityType::Underline => out.push_str("<u>"),
+            TextEntityType::Strikethrough => out.push_str("<s>"),
+            TextEntityType::Spoiler => out.push_str("<tg-spoiler>"),
+            TextEntityType::Code => out.push_str("<code>"),
+            TextEntityType::Pre => out.push_str("<pre>"),
+            TextEntityType::PreCode(pre) => {
+                out.push_str("<pre><code class=\"language-");
+                Html::escape(out, &pre.language);
+                out.push_str("\">");
+            }
+            TextEntityType::BlockQuote => out.push_str("<blockquote>"),
+            TextEntityType::TextUrl(url) => self.link(out, &url.url),
+            TextEntityType::MentionName(mention) => {
+                self.link(out, &format!("tg://user?id={}", mention.user_id))
+            }
+            TextEntityType::Url if text.contains("://") => self.link(out, text),
+            TextEntityType::Url => self.link(out, &format!("http://{}", text)),
+            TextEntityType::EmailAddress => self.link(out, &format!("mailto:{}", text)),
+            TextEntityType::PhoneNumber => self.link(out, &format!("tel:{}", text)),
+            TextEntityType::CustomEmoji(emoji) => {
+                let _ = write!(out, "<tg-emoji emoji-id=\"{}\">", emoji.custom_emoji_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, out: &mut String, entity: &TextEntityType) {
+        match entity {
+            TextEntityType::Bold => out.push_str("</b>"),
+            TextEntityType::Italic => out.push_str("</i>"),
+            TextEntityType::Underline => out.push_str("</u>"),
+            TextEntityType::Strikethrough => out.push_str("</s>"),
+            TextEntityType::Spoiler => out.push_str("</tg-spoiler>"),
+            TextEntityType::Code => out.push_str("</code>"),
+            TextEntityType::Pre => out.push_str("</pre>"),
+            TextEntityType::PreCode(_) => out.push_str("</code></pre>"),
+            TextEntityType::BlockQuote => out.push_str("</blockquote>"),
+            TextEntityType::TextUrl(_)
+            | TextEntityType::MentionName(_)
+            | TextEntityType::Url
+            | TextEntityType::EmailAddress
+            | TextEntityType::PhoneNumber
+                if self.links.pop().unwrap_or_default() =>
+            {
+                out.push_str("</a>")
+            }
+            TextEntityType::CustomEmoji(_) => out.push_str("</tg-emoji>"),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, out: &mut String, c: char) {
+        let mut buffer = [0; 4];
+        Html::escape(out, c.encode_utf8(&mut buffer));
+    }
+}
+
+/// Render the text with the escape sequences of the terminals: the styles
+/// of the select graphic rendition, the hyperlinks of the text URLs, and a
+/// bar at the start of the lines of the block quotes.
+pub fn ansi(text: &types::FormattedText) -> String {
+    walk(text, &mut Ansi::default())
+}
+
+/// Render the text as HTML, with the tags of the HTML of the Bot API, like
+/// `<tg-spoiler>` and `<tg-emoji>`, so that it can be sent back to
+/// Telegram too. The new lines are kept as they are, and the links to URLs
+/// with another scheme than `http`, `https`, `tg`, `mailto` and `tel` are
+/// dropped, keeping their text.
+pub fn html(text: &types::FormattedText) -> String {
+    walk(text, &mut Html::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str, entities: &[(i32, i32, TextEntityType)]) -> types::FormattedText {
+        let entities = entities
+            .iter()
+            .map(|(offset, length, r#type)| {
+                td_struct!(types::TextEntity {
+                    offset: *offset,
+                    length: *length,
+                    r#type: r#type.clone()
+                })
+            })
+            .collect();
+        td_struct!(types::FormattedText {
+            text: text.into(),
+            entities
+        })
+    }
+
+    fn text_url(url: &str) -> TextEntityType {
+        TextEntityType::TextUrl(td_struct!(types::TextEntityTypeTextUrl { url: url.into() }))
+    }
+
+    #[test]
+    fn check_nesting() {
+        let nested = text(
+            "bold italic",
+            &[
+                (0, 11, TextEntityType::Bold),
+                (5, 6, TextEntityType::Italic),
+            ],
+        );
+        assert_eq!(html(&nested), "<b>bold <i>italic</i></b>");
+        assert_eq!(
+            ansi(&nested),
+            "\x1b[1mbold \x1b[3mitalic\x1b[0m\x1b[1m\x1b[0m"
+        );
+
+        let overlapping = text(
+            "abcd",
+            &[(0, 3, TextEntityType::Bold), (1, 3, TextEntityType::Italic)],
+        );
+        assert_eq!(html(&overlapping), "<b>a<i>bc</i></b><i>d</i>");
+    }
+
+    #[test]
+    fn check_html_escapes() {
+        let pre = TextEntityType::PreCode(td_struct!(types::TextEntityTypePreCode {
+            language: "\"rust\"".into()
+        }));
+        let escaped = text("<a> & \"b\"", &[(0, 11, pre)]);
+        assert_eq!(
+            html(&escaped),
+            "<pre><code class=\"language-&quot;rust&quot;\">&lt;a&gt; &amp; &quot;b&quot;</code></pre>"
+        );
+    }
+
+    #[test]
+    fn check_html_links() {
+        let links = text(
+            "a b c d",
+            &[
+                (0, 1, text_url("javascript:alert(1)")),
+                (2, 1, text_url(" javascript:alert(1)")),
+                (4, 1, text_url("HTTPS://telegram.org")),
+                (6, 1, text_url("data:text/html,x")),
+            ],
+        );
+        assert_eq!(html(&links), "a b <a href=\"HTTPS://telegram.org\">c</a> d");
+        let email = text("me@example.com", &[(0, 14, TextEntityType::EmailAddress)]);
+        assert_eq!(
+            html(&email),
+            "<a href=\"mailto:me@example.com\">me@example.com</a>"
+        );
+    }
+
+    #[test]
+    fn check_ansi_controls() {
+        let injected = text("hi\x1b[2Jthere\n\tok\u{9b}\x7f", &[]);
+        assert_eq!(ansi(&injected), "hi\u{fffd}[2Jthere\n\tok\u{fffd}\u{fffd}");
+        let url = text("x", &[(0, 1, text_url("https://a\x1b]0;title\x07.org"))]);
+        assert_eq!(
+            ansi(&url),
+            "\x1b]8;;https://a]0;title.org\x1b\\\x1b[4;34mx\x1b[0m\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn check_block_quotes() {
+        let quote = text("a\nb", &[(0, 3, TextEntityType::BlockQuote)]);
+        assert_eq!(ansi(&quote), "│ \x1b[2ma\n│ b\x1b[0m");
+        assert_eq!(html(&quote), "<blockquote>a\nb</blockquote>");
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Cache the responses of the getters of users, chats and groups for a
+//! while, so that rendering the same entities again doesn't cost a
+//! round-trip to TdLib.
+//!
+//! The cache is opt-in: the getters of a client are cached once it is
+//! enabled with [`enable`], and the getters sent from a future wrapped with
+//! [`with_ttl`] are cached for that long whatever the client. A cached
+//! response is dropped as soon as an update about its entity is received,
+//! so it is only ever older than the updates when the updates aren't
+//! received. The cached functions are:
+//!
+//! - `getUser` and `getUserFullInfo`,
+//! - `getChat`,
+//! - `getBasicGroup` and `getBasicGroupFullInfo`,
+//! - `getSupergroup` and `getSupergroupFullInfo`.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tdlib_rs::{functions, response_cache};
+//!
+//! # async fn render(client_id: i32) {
+//! # let user_id = Default::default();
+//! response_cache::enable(client_id, Duration::from_secs(60));
+//! // Cached for a minute
+//! let user = functions::get_user(user_id, client_id).await;
+//! // Always sent to TdLib, caching the response again
+//! let user = response_cache::refresh(functions::get_user(user_id, client_id)).await;
+//! # }
+//! ```
+use crate::enums::{AuthorizationState, Update};
+use crate::observer::Response;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The cached functions, with the parameter identifying their entity.
+const FUNCTIONS: &[(&str, &str)] = &[
+    ("getUser", "user_id"),
+    ("getUserFullInfo", "user_id"),
+    ("getChat", "chat_id"),
+    ("getBasicGroup", "basic_group_id"),
+    ("getBasicGroupFullInfo", "basic_group_id"),
+    ("getSupergroup", "supergroup_id"),
+    ("getSupergroupFullInfo", "supergroup_id"),
+];
+
+type Key = (&'static str, i64);
+
+struct Entry {
+    response: Response,
+    stored: Instant,
+}
+
+#[derive(Default)]
+struct Cache {
+    ttl: Option<Duration>,
+    entries: HashMap<Key, Entry>,
+    /// When the entities requested were last updated, so that a response
+    /// requested before is not cached.
+    updated: HashMap<Key, Instant>,
+    /// The number of requests of each entity waiting for their response, an
+    /// update being only remembered while its entity is requested.
+    pending: HashMap<Key, usize>,
+}
+
+static CACHES: Lazy<Mutex<HashMap<i32, Cache>>> = Lazy::new(Mutex::default);
+
+thread_local! {
+    static TTL: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Cache the responses of the getters of the client for the TTL.
+pub fn enable(client_id: i32, ttl: Duration) {
+    CACHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(client_id)
+        .or_default()
+        .ttl = Some(ttl);
+}
+
+/// Stop caching the responses of the getters of the client, dropping the
+/// cached ones.
+pub fn disable(client_id: i32) {
+    CACHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&client_id);
+}
+
+/// Drop the cached responses of the getters of the client.
+pub fn clear(client_id: i32) {
+    if let Some(cache) = CACHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(&client_id)
+    {
+        cache.entries.clear();
+    }
+}
+
+/// Run the future with the responses of the getters it sends cached for the
+/// TTL instead of the one of their client, even if the cache of the client
+/// isn't enabled.
+pub async fn with_ttl<F: Future>(ttl: Duration, future: F) -> F::Output {
+    let mut future = pin!(future);
+    poll_fn(|cx| {
+        let previous = TTL.with(|current| current.replace(Some(ttl)));
+        let output = future.as_mut().poll(cx);
+        TTL.with(|current| current.set(previous));
+        output
+    })
+    .await
+}
+
+/// Run the future with the getters it sends always sent to TdLib, caching
+/// their response again.
+pub async fn refresh<F: Future>(future: F) -> F::Output {
+    with_ttl(Duration::ZERO, future).await
+}
+
+/// Forget the cached responses of the entities updated, and the cache of the
+/// clients once closed.
+#[allow(clippy::useless_conversion)]
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    let key = match update {
+        Update::User(update) => ("getUser", i64::from(update.user.id)),
+        Update::UserStatus(update) => ("getUser", i64::from(update.user_id)),
+        Update::UserFullInfo(update) => ("getUserFullInfo", i64::from(update.user_id)),
+        Update::BasicGroup(update) => ("getBasicGroup", update.basic_group.id),
+        Update::BasicGroupFullInfo(update) => ("getBasicGroupFullInfo", update.basic_group_id),
+        Update::Supergroup(update) => ("getSupergroup", update.supergroup.id),
+        Update::SupergroupFullInfo(update) => ("getSupergroupFullInfo", update.supergroup_id),
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                disable(client_id);
+            }
+            return;
+        }
+        update => match update.chat_id() {
+            Some(chat_id) => ("getChat", i64::from(chat_id)),
+            None => return,
+        },
+    };
+
+    let mut caches = CACHES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cache) = caches.get_mut(&client_id) {
+        cache.entries.remove(&key);
+        if cache.pending.contains_key(&key) {
+            cache.updated.insert(key, Instant::now());
+        }
+    }
+}
+
+/// A request of a cached function not answered from the cache, whose
+/// response is cached once received.
+pub(crate) struct Slot {
+    client_id: i32,
+    key: Key,
+    requested: Instant,
+}
+
+impl Slot {
+    /// Cache the response, unless it's an error or its entity was updated
+    /// since the request.
+    pub(crate) fn store(self, response: &Response) {
+        if response.is_error() {
+            return;
+        }
+        let mut caches = CACHES.lock().unwrap_or_else(|e| e.into_inner());
+        let cache = caches.entry(self.client_id).or_default();
+        if cache
+            .updated
+            .get(&self.key)
+            .is_some_and(|updated| *updated >= self.requested)
+        {
+            return;
+        }
+        cache.entries.insert(
+            self.key,
+            Entry {
+                response: response.clone(),
+                stored: Instant::now(),
+            },
+        );
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut caches = CACHES.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(cache) = caches.get_mut(&self.client_id) else {
+            return;
+        };
+        if let Some(pending) = cache.pending.get_mut(&self.key) {
+            *pending -= 1;
+            if *pending == 0 {
+                cache.pending.remove(&self.key);
+                cache.updated.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// How a request of a cached function is answered.
+pub(crate) enum Lookup {
+    /// The response is cached.
+    Hit(Response),
+    /// The request must be sent, and its response cached.
+    Miss(Slot),
+}
+
+/// Returns how the request is answered, or `None` if its function isn't
+/// cached for the client, or from the current poll.
+pub(crate) fn lookup(client_id: i32, request: &Value) -> Option<Lookup> {
+    let function = request["@type"].as_str()?;
+    let &(function, param) = FUNCTIONS.iter().find(|(name, _)| *name == function)?;
+    let key = (function, request[param].as_i64()?);
+
+    let mut caches = CACHES.lock().unwrap_or_else(|e| e.into_inner());
+    let ttl = TTL
+        .with(Cell::get)
+        .or_else(|| caches.get(&client_id)?.ttl)?;
+    let cache = caches.entry(client_id).or_default();
+    if let Some(entry) = cache.entries.get(&key) {
+        if entry.stored.elapsed() < ttl {
+            return Some(Lookup::Hit(entry.response.clone()));
+        }
+    }
+    *cache.pending.entry(key).or_default() += 1;
+    Some(Lookup::Miss(Slot {
+        client_id,
+        key,
+        requested: Instant::now(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::UserStatus;
+    use crate::types;
+    use serde_json::json;
+
+    fn updated(client_id: i32) -> usize {
+        CACHES
+            .lock()
+            .unwrap()
+            .get(&client_id)
+            .unwrap()
+            .updated
+            .len()
+    }
+
+    #[test]
+    #[allow(clippy::useless_conversion)]
+    fn check_updates_of_pending_requests() {
+        let client_id = -3001;
+        let update = |user_id: i64| {
+            Update::UserStatus(types::UpdateUserStatus {
+                user_id: user_id.into(),
+                status: UserStatus::Empty,
+            })
+        };
+        enable(client_id, Duration::from_secs(60));
+
+        // An update of an entity not requested isn't remembered
+        observe(&update(1), client_id);
+        assert_eq!(updated(client_id), 0);
+
+        let request = json!({"@type": "getUser", "user_id": 1});
+        let Some(Lookup::Miss(first)) = lookup(client_id, &request) else {
+            panic!("the user isn't cached");
+        };
+        let Some(Lookup::Miss(second)) = lookup(client_id, &request) else {
+            panic!("the user isn't cached");
+        };
+        observe(&update(1), client_id);
+        observe(&update(2), client_id);
+        assert_eq!(updated(client_id), 1);
+
+        // The update is forgotten once no request of its entity is pending
+        drop(first);
+        assert_eq!(updated(client_id), 1);
+        second.store(&Response::timed_out());
+        assert_eq!(updated(client_id), 0);
+        disable(client_id);
+    }
+}
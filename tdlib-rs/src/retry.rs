@@ -0,0 +1,204 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Retry the requests failed because of a transient error, waiting longer
+//! after each attempt.
+//!
+//! The requests are not retried by default: a policy must be set with
+//! [`set_policy`]. Only the requests which TdLib guarantees were not
+//! executed are retried, see [`types::Error::is_retryable`], so that no
+//! request is applied twice: a request failed with any other error, even a
+//! transient one, or whose outcome is unknown, like one timed out, is
+//! returned to the caller.
+use crate::types;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// The messages of the errors returned by TdLib when a request could not
+/// complete for reasons unrelated to the request itself.
+const TRANSIENT_MESSAGES: &[&str] = &[
+    "Request aborted",
+    "Request timeout",
+    "Connection closed",
+    "Lost connection",
+    "Network is unreachable",
+];
+
+/// The code of the errors returned by the library instead of a response of
+/// TdLib, when the request was abandoned, timed out or answered with a
+/// response which couldn't be parsed: TdLib may have executed it or not.
+pub const UNKNOWN_OUTCOME: i32 = -1;
+
+impl types::Error {
+    /// Returns `true` if the request may succeed when sent again: flood
+    /// waits, internal server errors and network failures. The errors with
+    /// an unknown outcome are not transient.
+    pub fn is_transient(&self) -> bool {
+        !self.is_unknown_outcome()
+            && (self.code == 429
+                || self.code >= 500
+                || TRANSIENT_MESSAGES
+                    .iter()
+                    .any(|message| self.message.contains(message)))
+    }
+
+    /// Returns `true` if the error was returned by the library instead of a
+    /// response of TdLib, which may have executed the request or not.
+    pub fn is_unknown_outcome(&self) -> bool {
+        self.code == UNKNOWN_OUTCOME
+    }
+
+    /// Returns `true` if TdLib guarantees that the request was not executed,
+    /// so that it can be sent again without being applied twice: only the
+    /// flood waits, rejected before the request is run.
+    pub fn is_retryable(&self) -> bool {
+        self.code == 429
+    }
+
+    /// Returns `true` if the request fails the same way however many times
+    /// it is sent, for example because one of its arguments is invalid.
+    pub fn is_permanent(&self) -> bool {
+        !self.is_transient() && !self.is_unknown_outcome()
+    }
+}
+
+/// How the requests which TdLib guarantees were not executed are retried,
+/// the flood waits without an explicit delay. The other errors, transient
+/// or not, are never retried, since TdLib may have executed the request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of times a request is sent again.
+    pub max_retries: u32,
+    /// How long to wait before the first retry.
+    pub initial_backoff: Duration,
+    /// The longest wait between two attempts.
+    pub max_backoff: Duration,
+    /// The factor by which the wait grows after each retry.
+    pub multiplier: f64,
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub const NEVER: RetryPolicy = RetryPolicy {
+        max_retries: 0,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+        multiplier: 1.0,
+    };
+
+    /// Returns how long to wait before the `retry`-th retry, starting from 0.
+    /// The wait is `max_backoff` if it can't be computed, for instance with
+    /// a negative `multiplier`.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self.initial_backoff.as_secs_f64()
+            * self.multiplier.powi(retry.min(i32::MAX as u32) as i32);
+        Duration::try_from_secs_f64(backoff.min(self.max_backoff.as_secs_f64()))
+            .unwrap_or(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retry up to 5 times, waiting from half a second up to 30 seconds.
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+static POLICY: Lazy<RwLock<RetryPolicy>> = Lazy::new(|| RwLock::new(RetryPolicy::NEVER));
+
+/// Set how the requests which TdLib guarantees were not executed are
+/// retried. The flood waits with an explicit delay are always retried after
+/// it.
+pub fn set_policy(policy: RetryPolicy) {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Returns how the requests which TdLib guarantees were not executed are
+/// retried.
+pub fn policy() -> RetryPolicy {
+    *POLICY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: i32, message: &str) -> types::Error {
+        td_struct!(types::Error {
+            code,
+            message: message.into()
+        })
+    }
+
+    #[test]
+    fn check_transient_errors() {
+        assert!(error(429, "Too Many Requests: retry after 5").is_transient());
+        assert!(error(500, "Internal Server Error").is_transient());
+        assert!(error(400, "Request aborted").is_transient());
+        assert!(error(406, "Lost connection to the server").is_transient());
+        assert!(error(400, "CHAT_NOT_FOUND").is_permanent());
+        assert!(error(403, "USER_IS_BLOCKED").is_permanent());
+    }
+
+    #[test]
+    fn check_unknown_outcomes() {
+        let timed_out = error(UNKNOWN_OUTCOME, "Request timeout");
+        assert!(timed_out.is_unknown_outcome());
+        assert!(!timed_out.is_transient());
+        assert!(!timed_out.is_permanent());
+        assert!(!timed_out.is_retryable());
+    }
+
+    #[test]
+    fn check_retryable_errors() {
+        assert!(error(429, "Too Many Requests: retry after 5").is_retryable());
+        assert!(!error(500, "Internal Server Error").is_retryable());
+        assert!(!error(400, "Request aborted").is_retryable());
+        assert!(!error(400, "CHAT_NOT_FOUND").is_retryable());
+    }
+
+    #[test]
+    fn check_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(10), Duration::from_secs(30));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(30));
+        assert_eq!(RetryPolicy::NEVER.backoff(3), Duration::ZERO);
+    }
+
+    #[test]
+    fn check_invalid_backoff() {
+        let max_backoff = Duration::from_secs(30);
+        for multiplier in [-2.0, f64::NAN, f64::INFINITY, f64::MAX] {
+            let policy = RetryPolicy {
+                multiplier,
+                ..RetryPolicy::default()
+            };
+            assert!(
+                policy.backoff(1) <= max_backoff,
+                "multiplier {}",
+                multiplier
+            );
+            assert!(policy.backoff(u32::MAX) <= max_backoff);
+        }
+        let policy = RetryPolicy {
+            max_backoff: Duration::MAX,
+            multiplier: f64::INFINITY,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::MAX);
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The async runtime facilities used by the library, provided by the
+//! runtime selected with the `runtime-*` features, or by tokio if none is
+//! selected, as before these features. The channels used to wait for the
+//! responses work with any runtime, so only the timer is abstracted.
+use std::time::Duration;
+
+/// Wait until the duration has elapsed.
+#[cfg(any(
+    feature = "runtime-tokio",
+    not(any(feature = "runtime-async-std", feature = "runtime-smol"))
+))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Wait until the duration has elapsed.
+#[cfg(all(feature = "runtime-async-std", not(feature = "runtime-tokio")))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_std::task::sleep(duration).await;
+}
+
+/// Wait until the duration has elapsed.
+#[cfg(all(
+    feature = "runtime-smol",
+    not(any(feature = "runtime-tokio", feature = "runtime-async-std"))
+))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_io::Timer::after(duration).await;
+}
+
+#[cfg(feature = "blocking")]
+pub(crate) use blocking::block_on;
+
+#[cfg(feature = "blocking")]
+mod blocking {
+    use once_cell::sync::Lazy;
+    use std::future::Future;
+    use tokio::runtime::{Builder, Runtime};
+
+    static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+        Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("failed to build the runtime of the blocking functions")
+    });
+
+    /// Run the future to completion on the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within an async context.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        RUNTIME.block_on(future)
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The topics of the Saved Messages, tracked from the updates sent for
+//! them, and helpers to load them, pin them and get their messages.
+//!
+//! TdLib sends the topics only as updates, after [`load_topics`].
+use crate::enums::{self, AuthorizationState, Update};
+use crate::ids::MessageId;
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct Topics {
+    topics: HashMap<i64, types::SavedMessagesTopic>,
+    topic_count: i32,
+}
+
+static TOPICS: Lazy<RwLock<HashMap<i32, Topics>>> = Lazy::new(RwLock::default);
+
+/// Returns the loaded topics of the client, in the order in which they are
+/// shown.
+pub fn topics(client_id: i32) -> Vec<types::SavedMessagesTopic> {
+    let mut topics: Vec<_> = TOPICS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .map(|topics| topics.topics.values().cloned().collect())
+        .unwrap_or_default();
+    topics.sort_by_key(|topic| Reverse(topic.order));
+    topics
+}
+
+/// Returns the approximate number of topics of the client, including those
+/// not loaded yet.
+pub fn topic_count(client_id: i32) -> i32 {
+    TOPICS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .map(|topics| topics.topic_count)
+        .unwrap_or_default()
+}
+
+/// Keep the topics of the client up to date, forgetting them once closed.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::SavedMessagesTopic(update) => {
+            TOPICS
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(client_id)
+                .or_default()
+                .topics
+                .insert(update.topic.id, update.topic.clone());
+        }
+        Update::SavedMessagesTopicCount(update) => {
+            TOPICS
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(client_id)
+                .or_default()
+                .topic_count = update.topic_count;
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                TOPICS
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&client_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Load more topics, tracked by [`topics`]. Returns `false` if all the
+/// topics were already loaded.
+pub async fn load_topics(limit: i32, client_id: i32) -> Result<bool, TdError> {
+    match functions::load_saved_messages_topics(limit, client_id).await {
+        Ok(()) => Ok(true),
+        Err(e) if e.code == 404 => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Load all the topics, tracked by [`topics`].
+pub async fn load_all_topics(client_id: i32) -> Result<(), TdError> {
+    while load_topics(100, client_id).await? {}
+    Ok(())
+}
+
+/// Returns the messages of a topic, from the newest to the oldest, starting
+/// from `from_message_id` or from the last message if 0.
+pub async fn history(
+    saved_messages_topic_id: i64,
+    from_message_id: MessageId,
+    limit: i32,
+    client_id: i32,
+) -> Result<Vec<types::Message>, TdError> {
+    let enums::Messages::Messages(messages) = functions::get_saved_messages_topic_history(
+        saved_messages_topic_id,
+        from_message_id,
+        0,
+        limit,
+        client_id,
+    )
+    .await?;
+    Ok(messages.messages.into_iter().flatten().collect())
+}
+
+/// Pin or unpin a topic.
+pub async fn set_pinned(
+    saved_messages_topic_id: i64,
+    is_pinned: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::toggle_saved_messages_topic_is_pinned(saved_messages_topic_id, is_pinned, client_id)
+        .await?;
+    Ok(())
+}
+
+/// Replace the pinned topics, in order.
+pub async fn set_pinned_topics(
+    saved_messages_topic_ids: Vec<i64>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::set_pinned_saved_messages_topics(saved_messages_topic_ids, client_id).await?;
+    Ok(())
+}
+
+/// Delete all the messages of a topic.
+pub async fn delete_history(saved_messages_topic_id: i64, client_id: i32) -> Result<(), TdError> {
+    functions::delete_saved_messages_topic_history(saved_messages_topic_id, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The TL schema the library was generated from, as data, for the tools
+//! built on top of it like REPLs and bridges.
+//!
+//! ```rust
+//! use tdlib_rs::schema;
+//!
+//! let send_message = schema::function("sendMessage").unwrap();
+//! assert_eq!(send_message.ty, "Message");
+//! for field in send_message.fields {
+//!     println!("{}: {} - {}", field.name, field.ty, field.description);
+//! }
+//! ```
+use crate::generated::schema::{FUNCTIONS, TYPES};
+
+/// A field of a type, or a parameter of a function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+    /// The name of the field, as in the JSON sent to TdLib.
+    pub name: &'static str,
+    /// The TL type of the field, like `int53` or `vector<message>`.
+    pub ty: &'static str,
+    /// The documentation of the field.
+    pub description: &'static str,
+    /// Whether the field may be null, or omitted.
+    pub optional: bool,
+}
+
+/// A type or a function of the schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Definition {
+    /// The name of the type or the function, as the `@type` of its JSON.
+    pub name: &'static str,
+    /// The type the type belongs to, or the type returned by the function.
+    pub ty: &'static str,
+    /// The documentation of the definition.
+    pub description: &'static str,
+    /// The fields of the type, or the parameters of the function.
+    pub fields: &'static [Field],
+}
+
+impl Definition {
+    /// Returns the field with the name, if any.
+    pub fn field(&self, name: &str) -> Option<&'static Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// Returns the types of the schema, in the order of the schema.
+pub fn types() -> &'static [Definition] {
+    TYPES
+}
+
+/// Returns the functions of the schema, in the order of the schema.
+pub fn functions() -> &'static [Definition] {
+    FUNCTIONS
+}
+
+/// Returns the type with the name, like `message`.
+pub fn find_type(name: &str) -> Option<&'static Definition> {
+    TYPES.iter().find(|definition| definition.name == name)
+}
+
+/// Returns the function with the name, like `sendMessage`.
+pub fn function(name: &str) -> Option<&'static Definition> {
+    FUNCTIONS.iter().find(|definition| definition.name == name)
+}
+
+/// Returns the types belonging to the type, like the kinds of `Update`.
+pub fn variants(ty: &str) -> impl Iterator<Item = &'static Definition> + '_ {
+    TYPES.iter().filter(move |definition| definition.ty == ty)
+}
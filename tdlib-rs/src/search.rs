@@ -0,0 +1,111 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Search the chats, the contacts and the messages, separately or all at
+//! once with [`search`] for the search bars.
+use crate::batch;
+use crate::enums;
+use crate::ids::{ChatId, UserId};
+use crate::{functions, types, TdError};
+
+/// Returns the public chat with the username, if any.
+pub async fn search_public_chat(username: String, client_id: i32) -> Result<types::Chat, TdError> {
+    let enums::Chat::Chat(chat) = functions::search_public_chat(username, client_id).await?;
+    Ok(chat)
+}
+
+/// Returns the public chats whose username or title starts with the query.
+pub async fn search_public_chats(query: String, client_id: i32) -> Result<Vec<ChatId>, TdError> {
+    let enums::Chats::Chats(chats) = functions::search_public_chats(query, client_id).await?;
+    Ok(chats.chat_ids)
+}
+
+/// Returns at most `limit` chats of the user matching the query, searched
+/// only among the chats known by TdLib.
+pub async fn search_chats(
+    query: String,
+    limit: i32,
+    client_id: i32,
+) -> Result<Vec<ChatId>, TdError> {
+    let enums::Chats::Chats(chats) = functions::search_chats(query, limit, client_id).await?;
+    Ok(chats.chat_ids)
+}
+
+/// Returns at most `limit` chats of the user matching the query, searched by
+/// the server.
+pub async fn search_chats_on_server(
+    query: String,
+    limit: i32,
+    client_id: i32,
+) -> Result<Vec<ChatId>, TdError> {
+    let enums::Chats::Chats(chats) =
+        functions::search_chats_on_server(query, limit, client_id).await?;
+    Ok(chats.chat_ids)
+}
+
+/// Returns at most `limit` contacts of the user matching the query.
+pub async fn search_contacts(
+    query: String,
+    limit: i32,
+    client_id: i32,
+) -> Result<Vec<UserId>, TdError> {
+    let enums::Users::Users(users) = functions::search_contacts(query, limit, client_id).await?;
+    Ok(users.user_ids)
+}
+
+/// The results of [`search`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchResults {
+    /// The chats of the user.
+    pub chats: Vec<ChatId>,
+    /// The public chats which are not chats of the user.
+    pub public_chats: Vec<ChatId>,
+    /// The contacts of the user.
+    pub contacts: Vec<UserId>,
+    /// The messages of all the chats, most recent first.
+    pub messages: Vec<types::Message>,
+    /// The offset of the next messages, empty if there are no more.
+    pub next_offset: String,
+}
+
+/// Search the chats, the public chats, the contacts and the messages at
+/// once, at most `limit` of each.
+pub async fn search(query: String, limit: i32, client_id: i32) -> Result<SearchResults, TdError> {
+    let (chats, public_chats, contacts, messages) = batch::join4(
+        functions::search_chats(query.clone(), limit, client_id),
+        functions::search_public_chats(query.clone(), client_id),
+        functions::search_contacts(query.clone(), limit, client_id),
+        functions::search_messages(
+            None,
+            false,
+            query,
+            String::new(),
+            limit,
+            None,
+            0,
+            0,
+            client_id,
+        ),
+    )
+    .await;
+    let enums::Chats::Chats(chats) = chats?;
+    let enums::Chats::Chats(public_chats) = public_chats?;
+    let enums::Users::Users(contacts) = contacts?;
+    let enums::FoundMessages::FoundMessages(messages) = messages?;
+
+    let mut public_chats = public_chats.chat_ids;
+    public_chats.retain(|chat_id| !chats.chat_ids.contains(chat_id));
+    public_chats.truncate(limit.max(0) as usize);
+    Ok(SearchResults {
+        chats: chats.chat_ids,
+        public_chats,
+        contacts: contacts.user_ids,
+        messages: messages.messages,
+        next_offset: messages.next_offset,
+    })
+}
@@ -0,0 +1,151 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The secret chats, identified by the identifier of their chat instead of
+//! the separate identifier of the secret chat, with their state tracked
+//! from `updateSecretChat`.
+use crate::enums::{self, AuthorizationState, InputMessageContent, SecretChatState, Update};
+use crate::ids::{ChatId, UserId};
+use crate::{functions, rate_limit, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::RwLock;
+use tokio::sync::Notify;
+
+static SECRET_CHATS: Lazy<RwLock<HashMap<(i32, i32), types::SecretChat>>> =
+    Lazy::new(RwLock::default);
+static CHANGED: Notify = Notify::const_new();
+
+/// Keep the secret chats of the client up to date, forgetting them once
+/// closed.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    match update {
+        Update::SecretChat(update) => {
+            SECRET_CHATS
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(
+                    (client_id, update.secret_chat.id),
+                    update.secret_chat.clone(),
+                );
+            CHANGED.notify_waiters();
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                SECRET_CHATS
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .retain(|(id, _), _| *id != client_id);
+                CHANGED.notify_waiters();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Start a secret chat with the user, returning its chat. The secret chat is
+/// pending until the user comes online.
+pub async fn create(user_id: UserId, client_id: i32) -> Result<types::Chat, TdError> {
+    let enums::Chat::Chat(chat) = functions::create_new_secret_chat(user_id, client_id).await?;
+    Ok(chat)
+}
+
+/// Returns the identifier of the secret chat of the chat, failing if the chat
+/// is not a secret chat.
+pub async fn secret_chat_id(chat_id: ChatId, client_id: i32) -> Result<i32, TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    chat.r#type
+        .secret_chat_id()
+        .ok_or_else(|| TdError::invalid(400, "Chat is not a secret chat"))
+}
+
+/// Returns the secret chat of the chat.
+pub async fn secret_chat(chat_id: ChatId, client_id: i32) -> Result<types::SecretChat, TdError> {
+    tracked_or_get(secret_chat_id(chat_id, client_id).await?, client_id).await
+}
+
+/// Returns the tracked secret chat, or requests it if not tracked yet.
+async fn tracked_or_get(secret_chat_id: i32, client_id: i32) -> Result<types::SecretChat, TdError> {
+    let tracked = SECRET_CHATS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(client_id, secret_chat_id))
+        .cloned();
+    match tracked {
+        Some(secret_chat) => Ok(secret_chat),
+        None => {
+            let enums::SecretChat::SecretChat(secret_chat) =
+                functions::get_secret_chat(secret_chat_id, client_id).await?;
+            Ok(secret_chat)
+        }
+    }
+}
+
+/// Returns the state of the secret chat of the chat.
+pub async fn state(chat_id: ChatId, client_id: i32) -> Result<SecretChatState, TdError> {
+    Ok(secret_chat(chat_id, client_id).await?.state)
+}
+
+/// Wait until the secret chat of the chat is no longer pending. Returns
+/// `true` if it's ready, or `false` if it was closed. The updates must be
+/// received meanwhile.
+pub async fn wait_ready(chat_id: ChatId, client_id: i32) -> Result<bool, TdError> {
+    let secret_chat_id = secret_chat_id(chat_id, client_id).await?;
+    loop {
+        let mut changed = pin!(CHANGED.notified());
+        changed.as_mut().enable();
+        match tracked_or_get(secret_chat_id, client_id).await?.state {
+            SecretChatState::Pending => changed.await,
+            SecretChatState::Ready => return Ok(true),
+            SecretChatState::Closed => return Ok(false),
+            SecretChatState::Unsupported => {
+                return Err(TdError::invalid(500, "Unsupported secret chat state"))
+            }
+        }
+    }
+}
+
+/// Set the time after which the messages sent to the chat are deleted, in
+/// seconds, or 0 to keep them.
+pub async fn set_ttl(
+    chat_id: ChatId,
+    message_auto_delete_time: i32,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::set_chat_message_auto_delete_time(chat_id, message_auto_delete_time, client_id)
+        .await?;
+    Ok(())
+}
+
+/// Send a message to the chat, which is delivered once the secret chat is
+/// ready.
+pub async fn send(
+    chat_id: ChatId,
+    input_message_content: InputMessageContent,
+    client_id: i32,
+) -> Result<types::Message, TdError> {
+    let enums::Message::Message(message) = rate_limit::send_message(
+        chat_id,
+        0,
+        None,
+        None,
+        None,
+        input_message_content,
+        client_id,
+    )
+    .await?;
+    Ok(message)
+}
+
+/// Close the secret chat of the chat; no more messages can be sent to it.
+pub async fn close(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    let secret_chat_id = secret_chat_id(chat_id, client_id).await?;
+    functions::close_secret_chat(secret_chat_id, client_id).await?;
+    Ok(())
+}
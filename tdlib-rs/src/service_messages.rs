@@ -0,0 +1,478 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Describe the service messages, like the members added to a group or the
+//! ended calls, with a sentence to display in place of their content.
+//!
+//! The sentences are made from templates, one for each kind of service
+//! message, named after the type of its content in TdLib, like
+//! `messageChatAddMembers`. A few kinds have a template for a special case:
+//!
+//! - `messageChatJoin`: the sender added themselves to the group.
+//! - `messageChatLeave`: the sender removed themselves from the group.
+//! - `messageCallMissed` and `messageCallDeclined`: the call wasn't
+//!   answered.
+//! - `messageChatDisableMessageAutoDeleteTime`: the auto-delete timer was
+//!   set to 0.
+//! - `messageChatDisableTheme`: the theme was set to none.
+//!
+//! The templates can use the placeholders:
+//!
+//! - `{actor}`: the sender of the message.
+//! - `{user}`: the user removed from the group.
+//! - `{users}`: the users added to the group or invited to the video chat.
+//! - `{title}`: the title of the group, of the topic, or of the theme.
+//! - `{duration}`: the duration of the call, of the video chat, or of the
+//!   auto-delete timer.
+//! - `{call}`: `call` or `video call`.
+//! - `{text}`: the text of a custom service action.
+//!
+//! The names of the users and the titles of the chats are the ones sent by
+//! TdLib, see the [`names`] module, falling back to their
+//! identifier if unknown.
+//!
+//! ```rust
+//! use tdlib_rs::enums::{MessageContent, MessageSender};
+//! use tdlib_rs::service_messages::Humanizer;
+//! use tdlib_rs::types;
+//!
+//! let sender = MessageSender::User(types::MessageSenderUser {
+//!     user_id: 42.into(),
+//!     ..Default::default()
+//! });
+//! let content = MessageContent::MessageChatChangeTitle(types::MessageChatChangeTitle {
+//!     title: "Rustaceans".into(),
+//!     ..Default::default()
+//! });
+//!
+//! let humanizer = Humanizer::new();
+//! assert_eq!(
+//!     humanizer.render_content(&content, &sender, 1).as_deref(),
+//!     Some("User 42 changed the group name to \"Rustaceans\"")
+//! );
+//!
+//! let humanizer = humanizer.template("messageChatChangeTitle", "{actor} → {title}");
+//! assert_eq!(
+//!     humanizer.render_content(&content, &sender, 1).as_deref(),
+//!     Some("User 42 → Rustaceans")
+//! );
+//! ```
+use crate::enums::{CallDiscardReason, MessageContent, MessageSender};
+use crate::ids::{ChatId, UserId};
+use crate::{names, types};
+use std::collections::HashMap;
+
+/// The templates in English, used by default.
+const TEMPLATES: &[(&str, &str)] = &[
+    ("messageChatAddMembers", "{actor} added {users}"),
+    ("messageChatJoin", "{actor} joined the group"),
+    (
+        "messageChatJoinByLink",
+        "{actor} joined the group via invite link",
+    ),
+    (
+        "messageChatJoinByRequest",
+        "{actor} was accepted into the group",
+    ),
+    ("messageChatDeleteMember", "{actor} removed {user}"),
+    ("messageChatLeave", "{actor} left the group"),
+    (
+        "messageChatChangeTitle",
+        "{actor} changed the group name to \"{title}\"",
+    ),
+    ("messageChatChangePhoto", "{actor} changed the group photo"),
+    ("messageChatDeletePhoto", "{actor} removed the group photo"),
+    (
+        "messageBasicGroupChatCreate",
+        "{actor} created the group \"{title}\"",
+    ),
+    (
+        "messageSupergroupChatCreate",
+        "{actor} created the group \"{title}\"",
+    ),
+    (
+        "messageChatUpgradeTo",
+        "The group was upgraded to a supergroup",
+    ),
+    (
+        "messageChatUpgradeFrom",
+        "The group \"{title}\" was upgraded to a supergroup",
+    ),
+    ("messagePinMessage", "{actor} pinned a message"),
+    ("messageScreenshotTaken", "{actor} took a screenshot"),
+    ("messageContactRegistered", "{actor} joined Telegram"),
+    ("messageCall", "{actor} made a {call} ({duration})"),
+    ("messageCallMissed", "Missed {call} from {actor}"),
+    ("messageCallDeclined", "Declined {call} from {actor}"),
+    (
+        "messageVideoChatScheduled",
+        "{actor} scheduled a video chat",
+    ),
+    ("messageVideoChatStarted", "{actor} started a video chat"),
+    ("messageVideoChatEnded", "The video chat ended ({duration})"),
+    (
+        "messageInviteVideoChatParticipants",
+        "{actor} invited {users} to the video chat",
+    ),
+    (
+        "messageChatSetMessageAutoDeleteTime",
+        "{actor} set messages to auto-delete after {duration}",
+    ),
+    (
+        "messageChatDisableMessageAutoDeleteTime",
+        "{actor} disabled the auto-delete timer",
+    ),
+    (
+        "messageChatSetTheme",
+        "{actor} changed the chat theme to {title}",
+    ),
+    ("messageChatDisableTheme", "{actor} disabled the chat theme"),
+    (
+        "messageForumTopicCreated",
+        "{actor} created the topic \"{title}\"",
+    ),
+    ("messageCustomServiceAction", "{text}"),
+];
+
+/// Renders the service messages with templates, see the
+/// [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct Humanizer {
+    templates: HashMap<String, String>,
+}
+
+impl Default for Humanizer {
+    fn default() -> Self {
+        Self {
+            templates: TEMPLATES
+                .iter()
+                .map(|&(kind, template)| (kind.to_owned(), template.to_owned()))
+                .collect(),
+        }
+    }
+}
+
+impl Humanizer {
+    /// Returns a humanizer with the templates in English.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the template of a kind of service message, like
+    /// `messagePinMessage`.
+    pub fn template(mut self, kind: impl Into<String>, template: impl Into<String>) -> Self {
+        self.templates.insert(kind.into(), template.into());
+        self
+    }
+
+    /// Returns the sentence describing the message, or `None` if it isn't a
+    /// service message or its kind has no template.
+    pub fn render(&self, message: &types::Message, client_id: i32) -> Option<String> {
+        self.render_content(&message.content, &message.sender_id, client_id)
+    }
+
+    /// Returns the sentence describing the content of a message sent by
+    /// `sender`, or `None` if it isn't a service message or its kind has no
+    /// template.
+    pub fn render_content(
+        &self,
+        content: &MessageContent,
+        sender: &MessageSender,
+        client_id: i32,
+    ) -> Option<String> {
+        let (kind, values) = describe(content, sender, client_id)?;
+        let template = self.templates.get(kind)?;
+        Some(fill(template, &values))
+    }
+}
+
+/// Returns the kind of the service message with the values of its
+/// placeholders.
+fn describe(
+    content: &MessageContent,
+    sender: &MessageSender,
+    client_id: i32,
+) -> Option<(&'static str, Vec<(&'static str, String)>)> {
+    let actor = names::sender_name(sender, client_id)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| match sender {
+            MessageSender::Chat(sender) => chat_fallback(sender.chat_id),
+            MessageSender::User(sender) => user_fallback(sender.user_id),
+            MessageSender::Unsupported => "Someone".to_owned(),
+        });
+    let sender_user = match sender {
+        MessageSender::User(sender) => Some(sender.user_id),
+        _ => None,
+    };
+    let user = |user_id: UserId| user_name(user_id, client_id);
+    let users = |user_ids: &[UserId]| join(user_ids.iter().map(|&user_id| user(user_id)).collect());
+    let mut values = vec![("actor", actor)];
+
+    let kind = match content {
+        MessageContent::MessageChatAddMembers(added) => {
+            if added.member_user_ids.len() == 1 && Some(added.member_user_ids[0]) == sender_user {
+                "messageChatJoin"
+            } else {
+                values.push(("users", users(&added.member_user_ids)));
+                "messageChatAddMembers"
+            }
+        }
+        MessageContent::MessageChatJoinByLink => "messageChatJoinByLink",
+        MessageContent::MessageChatJoinByRequest => "messageChatJoinByRequest",
+        MessageContent::MessageChatDeleteMember(deleted) => {
+            if Some(deleted.user_id) == sender_user {
+                "messageChatLeave"
+            } else {
+                values.push(("user", user(deleted.user_id)));
+                "messageChatDeleteMember"
+            }
+        }
+        MessageContent::MessageChatChangeTitle(changed) => {
+            values.push(("title", changed.title.clone()));
+            "messageChatChangeTitle"
+        }
+        MessageContent::MessageChatChangePhoto(_) => "messageChatChangePhoto",
+        MessageContent::MessageChatDeletePhoto => "messageChatDeletePhoto",
+        MessageContent::MessageBasicGroupChatCreate(created) => {
+            values.push(("title", created.title.clone()));
+            values.push(("users", users(&created.member_user_ids)));
+            "messageBasicGroupChatCreate"
+        }
+        MessageContent::MessageSupergroupChatCreate(created) => {
+            values.push(("title", created.title.clone()));
+            "messageSupergroupChatCreate"
+        }
+        MessageContent::MessageChatUpgradeTo(_) => "messageChatUpgradeTo",
+        MessageContent::MessageChatUpgradeFrom(upgraded) => {
+            values.push(("title", upgraded.title.clone()));
+            "messageChatUpgradeFrom"
+        }
+        MessageContent::MessagePinMessage(_) => "messagePinMessage",
+        MessageContent::MessageScreenshotTaken => "messageScreenshotTaken",
+        MessageContent::MessageContactRegistered => "messageContactRegistered",
+        MessageContent::MessageCall(call) => {
+            let name = if call.is_video { "video call" } else { "call" };
+            values.push(("call", name.to_owned()));
+            values.push(("duration", duration(call.duration)));
+            match call.discard_reason {
+                CallDiscardReason::Missed => "messageCallMissed",
+                CallDiscardReason::Declined => "messageCallDeclined",
+                _ => "messageCall",
+            }
+        }
+        MessageContent::MessageVideoChatScheduled(_) => "messageVideoChatScheduled",
+        MessageContent::MessageVideoChatStarted(_) => "messageVideoChatStarted",
+        MessageContent::MessageVideoChatEnded(ended) => {
+            values.push(("duration", duration(ended.duration)));
+            "messageVideoChatEnded"
+        }
+        MessageContent::MessageInviteVideoChatParticipants(invited) => {
+            values.push(("users", users(&invited.user_ids)));
+            "messageInviteVideoChatParticipants"
+        }
+        MessageContent::MessageChatSetMessageAutoDeleteTime(set) => {
+            // The timer is changed by a user on behalf of the chat
+            if set.from_user_id != UserId::default() {
+                values[0].1 = user(set.from_user_id);
+            }
+            if set.message_auto_delete_time == 0 {
+                "messageChatDisableMessageAutoDeleteTime"
+            } else {
+                values.push(("duration", duration(set.message_auto_delete_time)));
+                "messageChatSetMessageAutoDeleteTime"
+            }
+        }
+        MessageContent::MessageChatSetTheme(set) => {
+            if set.theme_name.is_empty() {
+                "messageChatDisableTheme"
+            } else {
+                values.push(("title", set.theme_name.clone()));
+                "messageChatSetTheme"
+            }
+        }
+        MessageContent::MessageForumTopicCreated(created) => {
+            values.push(("title", created.name.clone()));
+            "messageForumTopicCreated"
+        }
+        MessageContent::MessageCustomServiceAction(action) => {
+            values.push(("text", action.text.clone()));
+            "messageCustomServiceAction"
+        }
+        _ => return None,
+    };
+    Some((kind, values))
+}
+
+fn user_name(user_id: UserId, client_id: i32) -> String {
+    names::user_name(user_id, client_id)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| user_fallback(user_id))
+}
+
+fn user_fallback(user_id: UserId) -> String {
+    format!("User {}", user_id)
+}
+
+fn chat_fallback(chat_id: ChatId) -> String {
+    format!("Chat {}", chat_id)
+}
+
+/// Returns the names joined as a list in English.
+fn join(mut names: Vec<String>) -> String {
+    match names.len() {
+        0 => String::new(),
+        1 => names.remove(0),
+        _ => {
+            let last = names.pop().unwrap_or_default();
+            format!("{} and {}", names.join(", "), last)
+        }
+    }
+}
+
+/// Returns the seconds as days, hours, minutes and seconds, like
+/// `1 h 5 min`.
+fn duration(seconds: i32) -> String {
+    let seconds = seconds.max(0);
+    let parts = [
+        (
+            seconds / 86400,
+            if seconds / 86400 == 1 {
+                " day"
+            } else {
+                " days"
+            },
+        ),
+        (seconds / 3600 % 24, " h"),
+        (seconds / 60 % 60, " min"),
+        (seconds % 60, " s"),
+    ];
+    let parts = parts
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, unit)| format!("{}{}", count, unit))
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        "0 s".to_owned()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Returns the template with its placeholders replaced by their values; the
+/// unknown ones are kept as they are.
+fn fill(template: &str, values: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            let value = values.iter().find(|(key, _)| *key == name)?;
+            Some((end, &value.1))
+        });
+        match value {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_fill() {
+        let values = [("actor", "Alice".to_owned()), ("title", "Rust".to_owned())];
+        assert_eq!(
+            fill("{actor} renamed it {title}", &values),
+            "Alice renamed it Rust"
+        );
+        assert_eq!(fill("{actor}{actor}", &values), "AliceAlice");
+        assert_eq!(fill("{unknown} {actor}", &values), "{unknown} Alice");
+        assert_eq!(fill("{actor} {title", &values), "Alice {title");
+        assert_eq!(fill("{{actor}}", &values), "{Alice}");
+        assert_eq!(fill("no placeholders", &values), "no placeholders");
+    }
+
+    #[test]
+    fn check_join() {
+        let names = |names: &[&str]| names.iter().map(|&name| name.to_owned()).collect();
+        assert_eq!(join(names(&[])), "");
+        assert_eq!(join(names(&["Alice"])), "Alice");
+        assert_eq!(join(names(&["Alice", "Bob"])), "Alice and Bob");
+        assert_eq!(
+            join(names(&["Alice", "Bob", "Carol", "Dave"])),
+            "Alice, Bob, Carol and Dave"
+        );
+    }
+
+    #[test]
+    fn check_duration() {
+        assert_eq!(duration(0), "0 s");
+        assert_eq!(duration(-5), "0 s");
+        assert_eq!(duration(59), "59 s");
+        assert_eq!(duration(3900), "1 h 5 min");
+        assert_eq!(duration(86400), "1 day");
+        assert_eq!(duration(2 * 86400 + 1), "2 days 1 s");
+    }
+
+    #[test]
+    #[allow(clippy::useless_conversion)]
+    fn check_joins_and_leaves() {
+        let client_id = -4001;
+        let sender = MessageSender::User(td_struct!(types::MessageSenderUser {
+            user_id: UserId::from(42)
+        }));
+        let kind = |content| describe(&content, &sender, client_id).map(|(kind, _)| kind);
+        let added = |user_ids: Vec<i64>| {
+            MessageContent::MessageChatAddMembers(td_struct!(types::MessageChatAddMembers {
+                member_user_ids: user_ids.into_iter().map(UserId::from).collect()
+            }))
+        };
+        let deleted = |user_id: i64| {
+            MessageContent::MessageChatDeleteMember(td_struct!(types::MessageChatDeleteMember {
+                user_id: UserId::from(user_id)
+            }))
+        };
+
+        assert_eq!(kind(added(vec![42])), Some("messageChatJoin"));
+        assert_eq!(kind(added(vec![7])), Some("messageChatAddMembers"));
+        assert_eq!(kind(added(vec![42, 7])), Some("messageChatAddMembers"));
+        assert_eq!(kind(deleted(42)), Some("messageChatLeave"));
+        assert_eq!(kind(deleted(7)), Some("messageChatDeleteMember"));
+
+        let humanizer = Humanizer::new();
+        let render = |content| humanizer.render_content(&content, &sender, client_id);
+        assert_eq!(
+            render(added(vec![42])).as_deref(),
+            Some("User 42 joined the group")
+        );
+        assert_eq!(
+            render(added(vec![7, 8])).as_deref(),
+            Some("User 42 added User 7 and User 8")
+        );
+        assert_eq!(
+            render(deleted(42)).as_deref(),
+            Some("User 42 left the group")
+        );
+        assert_eq!(
+            render(deleted(7)).as_deref(),
+            Some("User 42 removed User 7")
+        );
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The sessions of the account on the other devices, and helpers to
+//! terminate them and to change what they can accept.
+use crate::enums;
+use crate::{functions, types, TdError};
+
+/// The active sessions of the account.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sessions {
+    /// The session of the client, if TdLib returned it.
+    pub current: Option<types::Session>,
+    /// The other sessions, most recently active first.
+    pub others: Vec<types::Session>,
+    /// The number of days of inactivity after which the sessions are
+    /// terminated.
+    pub inactive_session_ttl_days: i32,
+}
+
+impl Sessions {
+    /// Returns the other sessions which logged in recently and must be
+    /// confirmed or terminated.
+    pub fn unconfirmed(&self) -> impl Iterator<Item = &types::Session> {
+        self.others.iter().filter(|session| session.is_unconfirmed)
+    }
+
+    /// Returns the session, if active.
+    pub fn get(&self, session_id: i64) -> Option<&types::Session> {
+        self.current
+            .iter()
+            .chain(&self.others)
+            .find(|session| session.id == session_id)
+    }
+}
+
+/// Returns the active sessions of the account.
+pub async fn sessions(client_id: i32) -> Result<Sessions, TdError> {
+    let enums::Sessions::Sessions(sessions) = functions::get_active_sessions(client_id).await?;
+    let (mut current, mut others) = (None, Vec::new());
+    for session in sessions.sessions {
+        if session.is_current {
+            current = Some(session);
+        } else {
+            others.push(session);
+        }
+    }
+    others.sort_by_key(|session| std::cmp::Reverse(session.last_active_date));
+    Ok(Sessions {
+        current,
+        others,
+        inactive_session_ttl_days: sessions.inactive_session_ttl_days,
+    })
+}
+
+/// Terminate the session, logging out its device.
+pub async fn terminate(session_id: i64, client_id: i32) -> Result<(), TdError> {
+    functions::terminate_session(session_id, client_id).await?;
+    Ok(())
+}
+
+/// Terminate every session but the one of the client.
+pub async fn terminate_all_others(client_id: i32) -> Result<(), TdError> {
+    functions::terminate_all_other_sessions(client_id).await?;
+    Ok(())
+}
+
+/// Confirm an unconfirmed session, acknowledging the login.
+pub async fn confirm(session_id: i64, client_id: i32) -> Result<(), TdError> {
+    functions::confirm_session(session_id, client_id).await?;
+    Ok(())
+}
+
+/// Change whether the session can accept the incoming calls.
+pub async fn set_can_accept_calls(
+    session_id: i64,
+    can_accept_calls: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::toggle_session_can_accept_calls(session_id, can_accept_calls, client_id).await?;
+    Ok(())
+}
+
+/// Change whether the session can accept the incoming secret chats.
+pub async fn set_can_accept_secret_chats(
+    session_id: i64,
+    can_accept_secret_chats: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::toggle_session_can_accept_secret_chats(
+        session_id,
+        can_accept_secret_chats,
+        client_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Change the number of days of inactivity after which the sessions are
+/// terminated.
+pub async fn set_inactive_session_ttl(
+    inactive_session_ttl_days: i32,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::set_inactive_session_ttl(inactive_session_ttl_days, client_id).await?;
+    Ok(())
+}
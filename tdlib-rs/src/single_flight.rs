@@ -0,0 +1,229 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Coalesce the identical requests sent concurrently, so that the tasks
+//! rendering the same user or chat at once share a single round-trip to
+//! TdLib.
+//!
+//! While a request of a coalesced function is waiting for its response,
+//! the same request sent with the same client, with the same parameters,
+//! waits for that response instead of being sent again. Nothing is cached:
+//! the requests sent once the response is received are sent to TdLib.
+//!
+//! No function is coalesced by default, as only the functions without side
+//! effects may be.
+//!
+//! ```rust
+//! use tdlib_rs::single_flight::{self, FunctionClass};
+//!
+//! single_flight::enable(FunctionClass::Getters);
+//! // Every chat history must be loaded by its own request
+//! single_flight::disable(FunctionClass::Function("getChatHistory".into()));
+//! ```
+use crate::observer::Response;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use tokio::sync::oneshot;
+
+/// The functions whose requests are coalesced.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FunctionClass {
+    /// The getters without side effects listed in [`GETTERS`], like
+    /// `getUser`. The getters answered by a bot or a server for a given
+    /// request, like `getCallbackQueryAnswer`, are not among them.
+    Getters,
+    /// The function with the name, like `searchPublicChat`.
+    Function(String),
+}
+
+/// The functions coalesced by [`FunctionClass::Getters`], only returning
+/// the state of TdLib or of the server.
+pub const GETTERS: &[&str] = &[
+    "getBasicGroup",
+    "getBasicGroupFullInfo",
+    "getChat",
+    "getChatAdministrators",
+    "getChatFolder",
+    "getChatHistory",
+    "getChatMember",
+    "getChatPinnedMessage",
+    "getChats",
+    "getContacts",
+    "getCustomEmojiStickers",
+    "getFile",
+    "getMe",
+    "getMessage",
+    "getMessageLocally",
+    "getMessages",
+    "getOption",
+    "getRemoteFile",
+    "getRepliedMessage",
+    "getScopeNotificationSettings",
+    "getSecretChat",
+    "getStickerSet",
+    "getSupergroup",
+    "getSupergroupFullInfo",
+    "getSupergroupMembers",
+    "getUser",
+    "getUserFullInfo",
+    "getUserProfilePhotos",
+];
+
+#[derive(Default)]
+struct Settings {
+    getters: bool,
+    functions: HashMap<String, bool>,
+}
+
+impl Settings {
+    fn is_coalesced(&self, function: &str) -> bool {
+        self.functions
+            .get(function)
+            .copied()
+            .unwrap_or(self.getters && GETTERS.contains(&function))
+    }
+}
+
+static SETTINGS: Lazy<RwLock<Settings>> = Lazy::new(RwLock::default);
+
+type Key = (i32, String);
+
+static IN_FLIGHT: Lazy<Mutex<HashMap<Key, Vec<oneshot::Sender<Response>>>>> =
+    Lazy::new(Mutex::default);
+
+fn set(class: FunctionClass, coalesced: bool) {
+    let mut settings = SETTINGS.write().unwrap_or_else(|e| e.into_inner());
+    match class {
+        FunctionClass::Getters => settings.getters = coalesced,
+        FunctionClass::Function(function) => {
+            settings.functions.insert(function, coalesced);
+        }
+    }
+}
+
+/// Coalesce the requests of the functions. A function enabled or disabled
+/// by its name keeps its setting whatever the setting of the getters.
+pub fn enable(class: FunctionClass) {
+    set(class, true);
+}
+
+/// Stop coalescing the requests of the functions.
+pub fn disable(class: FunctionClass) {
+    set(class, false);
+}
+
+/// Stop coalescing the requests of every function, forgetting the settings
+/// of each function.
+pub fn disable_all() {
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = Settings::default();
+}
+
+/// Returns `true` if the requests of the function are coalesced.
+pub fn is_enabled(function: &str) -> bool {
+    SETTINGS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_coalesced(function)
+}
+
+/// The request sent to TdLib on behalf of the identical ones, answering them
+/// once finished; if dropped before, they are sent again.
+pub(crate) struct Leader {
+    key: Option<Key>,
+}
+
+impl Leader {
+    /// Answer the requests waiting for the response.
+    pub(crate) fn finish(mut self, response: &Response) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let followers = IN_FLIGHT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&key)
+            .unwrap_or_default();
+        for follower in followers {
+            let _ = follower.send(response.clone());
+        }
+    }
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            IN_FLIGHT
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&key);
+        }
+    }
+}
+
+/// How a request of a coalesced function is answered.
+pub(crate) enum Flight {
+    /// The request must be sent, for itself and the identical ones.
+    Leader(Leader),
+    /// The request waits for the response of the identical one.
+    Follower(oneshot::Receiver<Response>),
+}
+
+/// Returns how the request is answered, or `None` if its function isn't
+/// coalesced.
+pub(crate) fn join(client_id: i32, request: &Value) -> Option<Flight> {
+    if !is_enabled(request["@type"].as_str()?) {
+        return None;
+    }
+    let key = (client_id, request.to_string());
+    let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    match in_flight.get_mut(&key) {
+        Some(followers) => {
+            let (sender, receiver) = oneshot::channel();
+            followers.push(sender);
+            Some(Flight::Follower(receiver))
+        }
+        None => {
+            in_flight.insert(key.clone(), Vec::new());
+            Some(Flight::Leader(Leader { key: Some(key) }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_getters() {
+        let settings = Settings {
+            getters: true,
+            ..Default::default()
+        };
+        assert!(settings.is_coalesced("getUser"));
+        assert!(!settings.is_coalesced("getCallbackQueryAnswer"));
+        assert!(!settings.is_coalesced("getInlineQueryResults"));
+        assert!(!settings.is_coalesced("getPaymentForm"));
+        assert!(!settings.is_coalesced("getLoginUrl"));
+        assert!(!settings.is_coalesced("sendMessage"));
+    }
+
+    #[test]
+    fn check_functions() {
+        let mut settings = Settings {
+            getters: true,
+            ..Default::default()
+        };
+        settings.functions.insert("getUser".into(), false);
+        settings.functions.insert("getLoginUrl".into(), true);
+        assert!(!settings.is_coalesced("getUser"));
+        assert!(settings.is_coalesced("getLoginUrl"));
+        assert!(settings.is_coalesced("getChat"));
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Report the requests TdLib takes longer than a threshold to answer, to
+//! find which functions are stalling an application.
+//!
+//! The time is measured from the moment a request is sent to TdLib to the
+//! moment its response is received, for each attempt when the request is
+//! retried. With the `metrics` feature the duration of every request is also
+//! recorded in the `tdlib_request_duration_seconds` histogram, labelled with
+//! the `function` of the request.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use tdlib_rs::slow_requests;
+//!
+//! slow_requests::set_threshold(Duration::from_secs(5));
+//! slow_requests::set_hook(|request| {
+//!     eprintln!("{} took {:?}", request.function, request.elapsed);
+//! });
+//! ```
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+type SlowRequestHook = Arc<dyn Fn(&SlowRequest) + Send + Sync>;
+
+static THRESHOLD: RwLock<Option<Duration>> = RwLock::new(None);
+
+static HOOK: Lazy<RwLock<Option<SlowRequestHook>>> = Lazy::new(RwLock::default);
+
+/// A request answered by TdLib after the threshold.
+#[derive(Clone, Copy, Debug)]
+pub struct SlowRequest<'a> {
+    /// The function of the request, like `getChatHistory`
+    pub function: &'a str,
+    /// The client the request was sent with
+    pub client_id: i32,
+    /// How long TdLib took to answer the request
+    pub elapsed: Duration,
+    /// The threshold the request exceeded
+    pub threshold: Duration,
+}
+
+/// Report the requests of every client answered after the threshold, unless
+/// the client has its own in its
+/// [`ClientOptions`](crate::client_options::ClientOptions).
+pub fn set_threshold(threshold: Duration) {
+    *THRESHOLD.write().unwrap_or_else(|e| e.into_inner()) = Some(threshold);
+}
+
+/// Stop reporting the requests of the clients without a threshold of their
+/// own.
+pub fn disable() {
+    *THRESHOLD.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the threshold set with [`set_threshold`], if any.
+pub fn threshold() -> Option<Duration> {
+    *THRESHOLD.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the callback invoked with every slow request, replacing the previous
+/// one. Without a callback the slow requests are logged as warnings.
+pub fn set_hook<F>(hook: F)
+where
+    F: Fn(&SlowRequest) + Send + Sync + 'static,
+{
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Remove the callback set with [`set_hook`].
+pub fn remove_hook() {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Record the duration of a request answered by TdLib, reporting it if
+/// longer than the threshold of its client, or the shared one.
+pub(crate) fn record(
+    function: &str,
+    client_id: i32,
+    elapsed: Duration,
+    client_threshold: Option<Duration>,
+) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("tdlib_request_duration_seconds", "function" => function.to_owned())
+        .record(elapsed.as_secs_f64());
+
+    let Some(threshold) = client_threshold.or_else(threshold) else {
+        return;
+    };
+    if elapsed < threshold {
+        return;
+    }
+
+    let request = SlowRequest {
+        function,
+        client_id,
+        elapsed,
+        threshold,
+    };
+    // Called once the lock is released, so that it can set another hook
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match hook {
+        Some(hook) => hook(&request),
+        None => log::warn!(
+            "The request {} of the client {} took {:?}, more than {:?}",
+            request.function,
+            request.client_id,
+            request.elapsed,
+            request.threshold
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn check_hook_removing_itself() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        set_hook(|_| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            remove_hook();
+        });
+
+        let elapsed = Duration::from_secs(2);
+        let threshold = Some(Duration::from_secs(1));
+        record("getMe", 0, elapsed, threshold);
+        record("getMe", 0, elapsed, threshold);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    }
+}
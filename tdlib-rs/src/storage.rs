@@ -0,0 +1,211 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The storage used by TdLib, by chat and by type of file, as reports for
+//! the screens of the storage usage.
+use crate::enums::{self, FileType};
+use crate::ids::ChatId;
+use crate::{functions, types, TdError};
+use std::fmt;
+
+/// Returns the size in bytes in a human-readable form, such as `1.5 MB`,
+/// in multiples of 1024.
+pub fn format_size(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["KB", "MB", "GB", "TB", "PB"];
+    if bytes.abs() < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// The size and the number of some files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// The total size of the files, in bytes.
+    pub size: i64,
+    /// The number of files.
+    pub count: i32,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in {} files", format_size(self.size), self.count)
+    }
+}
+
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.size += other.size;
+        self.count += other.count;
+    }
+}
+
+/// The storage used by the files of a chat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatUsage {
+    /// The chat, `None` for the files not belonging to any chat, such as
+    /// the profile photos.
+    pub chat_id: Option<ChatId>,
+    /// The storage used by the files of the chat.
+    pub usage: Usage,
+    /// The storage used by each type of file of the chat, largest first.
+    pub by_file_type: Vec<(FileType, Usage)>,
+}
+
+fn by_file_type(by_file_type: Vec<types::StorageStatisticsByFileType>) -> Vec<(FileType, Usage)> {
+    let mut by_file_type: Vec<_> = by_file_type
+        .into_iter()
+        .map(|statistics| {
+            let usage = Usage {
+                size: statistics.size,
+                count: statistics.count,
+            };
+            (statistics.file_type, usage)
+        })
+        .collect();
+    by_file_type.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.size));
+    by_file_type
+}
+
+/// The storage used by the files, returned by [`report`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageReport {
+    /// The storage used by all the files.
+    pub total: Usage,
+    chats: Vec<ChatUsage>,
+}
+
+impl StorageReport {
+    /// Returns the storage used by each chat, largest first.
+    pub fn by_chat(&self) -> &[ChatUsage] {
+        &self.chats
+    }
+
+    /// Returns the storage used by the chat, if it has files in the report.
+    pub fn chat(&self, chat_id: ChatId) -> Option<&ChatUsage> {
+        self.chats.iter().find(|chat| chat.chat_id == Some(chat_id))
+    }
+
+    /// Returns the storage used by each type of file over all the chats,
+    /// largest first.
+    pub fn by_file_type(&self) -> Vec<(FileType, Usage)> {
+        let mut by_file_type: Vec<(FileType, Usage)> = Vec::new();
+        for (file_type, usage) in self.chats.iter().flat_map(|chat| &chat.by_file_type) {
+            match by_file_type.iter_mut().find(|(t, _)| t == file_type) {
+                Some((_, total)) => *total += *usage,
+                None => by_file_type.push((file_type.clone(), *usage)),
+            }
+        }
+        by_file_type.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.size));
+        by_file_type
+    }
+}
+
+impl From<types::StorageStatistics> for StorageReport {
+    fn from(statistics: types::StorageStatistics) -> Self {
+        let mut chats: Vec<_> = statistics
+            .by_chat
+            .into_iter()
+            .map(|chat| ChatUsage {
+                chat_id: Some(chat.chat_id).filter(|&chat_id| chat_id != ChatId::default()),
+                usage: Usage {
+                    size: chat.size,
+                    count: chat.count,
+                },
+                by_file_type: by_file_type(chat.by_file_type),
+            })
+            .collect();
+        chats.sort_by_key(|chat| std::cmp::Reverse(chat.usage.size));
+        Self {
+            total: Usage {
+                size: statistics.size,
+                count: statistics.count,
+            },
+            chats,
+        }
+    }
+}
+
+/// Returns the storage used by the files, detailed for the `chat_limit`
+/// chats using the most, the others counted together; this can take a
+/// while, as every file is checked.
+pub async fn report(chat_limit: i32, client_id: i32) -> Result<StorageReport, TdError> {
+    let enums::StorageStatistics::StorageStatistics(statistics) =
+        functions::get_storage_statistics(chat_limit, client_id).await?;
+    Ok(statistics.into())
+}
+
+/// The storage used by TdLib, returned quickly by [`fast_report`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FastReport {
+    /// The storage used by the files.
+    pub files: Usage,
+    /// The size of the database, in bytes.
+    pub database_size: i64,
+    /// The size of the database of the language packs, in bytes.
+    pub language_pack_database_size: i64,
+    /// The size of the internal log of TdLib, in bytes.
+    pub log_size: i64,
+}
+
+impl FastReport {
+    /// Returns the size of everything stored by TdLib, in bytes.
+    pub fn total_size(&self) -> i64 {
+        self.files.size + self.database_size + self.language_pack_database_size + self.log_size
+    }
+}
+
+impl fmt::Display for FastReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (files: {}, database: {}, log: {})",
+            format_size(self.total_size()),
+            self.files,
+            format_size(self.database_size + self.language_pack_database_size),
+            format_size(self.log_size)
+        )
+    }
+}
+
+/// Returns the storage used by TdLib, from the sizes cached by TdLib.
+pub async fn fast_report(client_id: i32) -> Result<FastReport, TdError> {
+    let enums::StorageStatisticsFast::StorageStatisticsFast(statistics) =
+        functions::get_storage_statistics_fast(client_id).await?;
+    Ok(FastReport {
+        files: Usage {
+            size: statistics.files_size,
+            count: statistics.file_count,
+        },
+        database_size: statistics.database_size,
+        language_pack_database_size: statistics.language_pack_database_size,
+        log_size: statistics.log_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KB");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+        assert_eq!(format_size(-2048), "-2.0 KB");
+        assert_eq!(format_size(i64::MAX), "8192.0 PB");
+    }
+}
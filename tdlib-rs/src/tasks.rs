@@ -0,0 +1,161 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tie the background tasks of a client to its lifetime.
+//!
+//! The library doesn't depend on a runtime to spawn tasks, so the futures
+//! running for a client, like [`ViewBatcher::run`](crate::views::ViewBatcher::run),
+//! are wrapped with [`bind`] before being spawned: they end once the client
+//! is closed, and [`shutdown`] closes the client and waits for them. The
+//! receive loop of the `updates` module is shared by every client, and is
+//! not bound to any.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tdlib_rs::tasks;
+//! use tdlib_rs::views::ViewBatcher;
+//!
+//! # async fn run(client_id: i32) {
+//! let views = ViewBatcher::new(Duration::from_millis(500), client_id);
+//! tokio::spawn(tasks::bind(client_id, async move { views.run().await }));
+//! // ...
+//! let ended = tasks::shutdown(client_id, Duration::from_secs(5)).await;
+//! # }
+//! ```
+use crate::enums::{AuthorizationState, Update};
+use crate::{functions, runtime};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct ClientTasks {
+    running: usize,
+    closed: bool,
+}
+
+static TASKS: Lazy<Mutex<HashMap<i32, ClientTasks>>> = Lazy::new(Mutex::default);
+
+static CHANGED: Notify = Notify::const_new();
+
+fn with_tasks<T>(client_id: i32, f: impl FnOnce(&mut ClientTasks) -> T) -> T {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    f(tasks.entry(client_id).or_default())
+}
+
+fn close(client_id: i32) {
+    with_tasks(client_id, |tasks| tasks.closed = true);
+    CHANGED.notify_waiters();
+}
+
+/// End the tasks of the clients once closed.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    if let Update::AuthorizationState(update) = update {
+        if matches!(update.authorization_state, AuthorizationState::Closed) {
+            close(client_id);
+        }
+    }
+}
+
+/// Whether the client was closed, ending its tasks.
+pub fn is_closed(client_id: i32) -> bool {
+    with_tasks(client_id, |tasks| tasks.closed)
+}
+
+/// Returns the number of tasks bound to the client still running.
+pub fn running(client_id: i32) -> usize {
+    with_tasks(client_id, |tasks| tasks.running)
+}
+
+struct Running(i32);
+
+impl Running {
+    fn new(client_id: i32) -> Self {
+        with_tasks(client_id, |tasks| tasks.running += 1);
+        Self(client_id)
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        with_tasks(self.0, |tasks| tasks.running -= 1);
+        CHANGED.notify_waiters();
+    }
+}
+
+/// Bind the future to the client: it is counted as running from now until
+/// it ends or is dropped, and it is dropped at its next poll once the client
+/// is closed, returning `None`.
+pub fn bind<F: Future>(client_id: i32, future: F) -> impl Future<Output = Option<F::Output>> {
+    let running = Running::new(client_id);
+    async move {
+        let _running = running;
+        let mut future = pin!(future);
+        loop {
+            let mut changed = pin!(CHANGED.notified());
+            changed.as_mut().enable();
+            if is_closed(client_id) {
+                return None;
+            }
+            let output = poll_fn(|cx| {
+                if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                    return Poll::Ready(Some(output));
+                }
+                changed.as_mut().poll(cx).map(|_| None)
+            })
+            .await;
+            if output.is_some() {
+                return output;
+            }
+        }
+    }
+}
+
+/// Close the client, ending the tasks bound to it, and wait at most for the
+/// timeout until they are dropped; returns whether they all were.
+pub async fn shutdown(client_id: i32, timeout: Duration) -> bool {
+    close(client_id);
+    let deadline = Instant::now() + timeout;
+    let mut sleep = pin!(runtime::sleep(timeout));
+    let mut closing = pin!(functions::close(client_id));
+    let mut closed = false;
+    loop {
+        let mut changed = pin!(CHANGED.notified());
+        changed.as_mut().enable();
+        if closed && running(client_id) == 0 {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        let timed_out = poll_fn(|cx| {
+            if !closed {
+                if let Poll::Ready(result) = closing.as_mut().poll(cx) {
+                    if let Err(e) = result {
+                        log::warn!("The client {} failed to be closed: {}", client_id, e);
+                    }
+                    closed = true;
+                    return Poll::Ready(false);
+                }
+            }
+            if changed.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(false);
+            }
+            sleep.as_mut().poll(cx).map(|_| true)
+        })
+        .await;
+        if timed_out {
+            return closed && running(client_id) == 0;
+        }
+    }
+}
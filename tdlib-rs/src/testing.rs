@@ -0,0 +1,905 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A fake TdLib in memory, [`FakeTd`], to test the code using the library
+//! without a network nor a Telegram account.
+//!
+//! Unlike the [`MockClientApi`](crate::client_api::MockClientApi), which
+//! only answers the requests with the responses set for them, a fake client
+//! behaves like a small part of TdLib: it goes through the authorization
+//! states, sends the chats of the main chat list with `loadChats`, and sends
+//! the text messages, with their updates. The requests sent with its
+//! `client_id` by the functions of the library are answered by it instead of
+//! TdLib, and its updates are passed to the modules tracking the state of
+//! the clients and to the subscribers of the [`updates`] module, so the
+//! helpers of the library can be tested as they run with TdLib.
+//!
+//! ```rust
+//! use tdlib_rs::enums::{self, ChatList, InputMessageContent};
+//! use tdlib_rs::testing::FakeTd;
+//! use tdlib_rs::{chat_lists, functions, types};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let td = FakeTd::new().authentication_code("22222");
+//! let client_id = td.client_id();
+//!
+//! td.authorize();
+//! let chat_id = td.add_chat("Alice");
+//! let chats = chat_lists::load_all_chats(ChatList::Main, client_id).await.unwrap();
+//! assert_eq!(chats, [chat_id]);
+//!
+//! let content = InputMessageContent::InputMessageText(types::InputMessageText {
+//!     text: types::FormattedText {
+//!         text: "Hello".into(),
+//!         ..Default::default()
+//!     },
+//!     ..Default::default()
+//! });
+//! # #[cfg(not(feature = "bots-only-api"))]
+//! # {
+//! td.flood_wait("sendMessage", 1);
+//! let enums::Message::Message(message) =
+//!     functions::send_message(chat_id, 0, None, None, content, client_id)
+//!         .await
+//!         .unwrap();
+//! assert!(message.sending_state.is_some());
+//! assert_eq!(td.calls_to("sendMessage").len(), 2);
+//! assert_eq!(td.messages(chat_id).len(), 1);
+//! # }
+//! # }
+//! ```
+use crate::client_api::ClientApi;
+use crate::enums::{
+    AuthenticationCodeType, AuthorizationState, ChatAvailableReactions, ChatList, ChatType,
+    InputMessageContent, MessageContent, MessageSender, MessageSendingState, Update, UserStatus,
+    UserType,
+};
+use crate::ids::{ChatId, MessageId, UserId};
+use crate::json::RawJson;
+use crate::observer::Response;
+use crate::{types, updates};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::future::{self, Future};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The identifier of the next fake client, negative so that it is never the
+/// one of a client of TdLib.
+static NEXT_CLIENT_ID: AtomicI32 = AtomicI32::new(-1);
+
+static FAKES: Lazy<RwLock<HashMap<i32, Arc<Mutex<State>>>>> = Lazy::new(RwLock::default);
+
+/// The identifier of the user of the fake clients, the users of their chats
+/// being the next ones.
+const MY_USER_ID: i64 = 1_000_000;
+
+fn now() -> i32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i32
+}
+
+fn error(code: i32, message: &str) -> types::Error {
+    td_struct!(types::Error {
+        code,
+        message: message.into()
+    })
+}
+
+fn ok() -> Result<Value, types::Error> {
+    Ok(json!({ "@type": "ok" }))
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, types::Error> {
+    serde_json::to_value(value).map_err(|e| error(500, &e.to_string()))
+}
+
+/// Returns the parameter of the request.
+fn param<T: DeserializeOwned>(request: &Value, name: &str) -> Result<T, types::Error> {
+    serde_json::from_value(request[name].clone())
+        .map_err(|_| error(400, &format!("Failed to parse the parameter {}", name)))
+}
+
+#[allow(clippy::useless_conversion)]
+fn user(id: i64, first_name: &str, last_name: &str) -> types::User {
+    td_struct!(types::User {
+        id: UserId::from(id),
+        first_name: first_name.into(),
+        last_name: last_name.into(),
+        usernames: None,
+        phone_number: String::new(),
+        status: UserStatus::Empty,
+        profile_photo: None,
+        accent_color_id: 0,
+        background_custom_emoji_id: 0,
+        profile_accent_color_id: -1,
+        profile_background_custom_emoji_id: 0,
+        emoji_status: None,
+        is_contact: true,
+        is_mutual_contact: true,
+        is_close_friend: false,
+        is_verified: false,
+        is_premium: false,
+        is_support: false,
+        restriction_reason: String::new(),
+        is_scam: false,
+        is_fake: false,
+        has_active_stories: false,
+        has_unread_active_stories: false,
+        restricts_new_chats: false,
+        have_access: true,
+        r#type: UserType::Regular,
+        language_code: String::new(),
+        added_to_attachment_menu: false,
+    })
+}
+
+#[allow(clippy::useless_conversion)]
+fn chat(user_id: UserId, title: &str) -> types::Chat {
+    td_struct!(types::Chat {
+        id: ChatId::from(i64::from(user_id)),
+        r#type: ChatType::Private(td_struct!(types::ChatTypePrivate { user_id })),
+        title: title.into(),
+        photo: None,
+        accent_color_id: 0,
+        background_custom_emoji_id: 0,
+        profile_accent_color_id: -1,
+        profile_background_custom_emoji_id: 0,
+        permissions: Default::default(),
+        last_message: None,
+        positions: Vec::new(),
+        chat_lists: Vec::new(),
+        message_sender_id: None,
+        block_list: None,
+        has_protected_content: false,
+        is_translatable: false,
+        is_marked_as_unread: false,
+        view_as_topics: false,
+        has_scheduled_messages: false,
+        can_be_deleted_only_for_self: true,
+        can_be_deleted_for_all_users: true,
+        can_be_reported: false,
+        default_disable_notification: false,
+        unread_count: 0,
+        last_read_inbox_message_id: Default::default(),
+        last_read_outbox_message_id: Default::default(),
+        unread_mention_count: 0,
+        unread_reaction_count: 0,
+        notification_settings: Default::default(),
+        available_reactions: ChatAvailableReactions::All(Default::default()),
+        message_auto_delete_time: 0,
+        emoji_status: None,
+        background: None,
+        theme_name: String::new(),
+        action_bar: None,
+        business_bot_manage_bar: None,
+        video_chat: Default::default(),
+        pending_join_requests: None,
+        reply_markup_message_id: Default::default(),
+        draft_message: None,
+        client_data: String::new(),
+    })
+}
+
+#[allow(clippy::useless_conversion)]
+fn message(
+    id: i64,
+    chat_id: ChatId,
+    sender: UserId,
+    is_outgoing: bool,
+    content: MessageContent,
+) -> types::Message {
+    td_struct!(types::Message {
+        id: MessageId::from(id),
+        sender_id: MessageSender::User(td_struct!(types::MessageSenderUser { user_id: sender })),
+        chat_id,
+        sending_state: None,
+        scheduling_state: None,
+        is_outgoing,
+        is_pinned: false,
+        is_from_offline: false,
+        can_be_edited: is_outgoing,
+        can_be_forwarded: true,
+        can_be_replied_in_another_chat: true,
+        can_be_saved: true,
+        can_be_deleted_only_for_self: true,
+        can_be_deleted_for_all_users: true,
+        can_get_added_reactions: false,
+        can_get_statistics: false,
+        can_get_message_thread: false,
+        can_get_read_date: false,
+        can_get_viewers: false,
+        can_get_media_timestamp_links: false,
+        can_report_reactions: false,
+        has_timestamped_media: true,
+        is_channel_post: false,
+        is_topic_message: false,
+        contains_unread_mention: false,
+        date: now(),
+        edit_date: 0,
+        forward_info: None,
+        import_info: None,
+        interaction_info: None,
+        unread_reactions: Vec::new(),
+        reply_to: None,
+        message_thread_id: Default::default(),
+        saved_messages_topic_id: Default::default(),
+        self_destruct_type: None,
+        self_destruct_in: 0.0,
+        auto_delete_in: 0.0,
+        via_bot_user_id: Default::default(),
+        sender_business_bot_user_id: Default::default(),
+        sender_boost_count: 0,
+        author_signature: String::new(),
+        media_album_id: Default::default(),
+        restriction_reason: String::new(),
+        content,
+        reply_markup: None,
+    })
+}
+
+/// A chat of a fake client.
+struct Chat {
+    chat: types::Chat,
+    user: types::User,
+    /// Whether the chat was sent with `updateNewChat`
+    sent: bool,
+    /// The messages of the chat, the oldest first
+    messages: Vec<types::Message>,
+}
+
+/// The state of a fake client.
+struct State {
+    authorization_state: AuthorizationState,
+    code: String,
+    password: Option<String>,
+    me: types::User,
+    chats: Vec<Chat>,
+    next_order: i64,
+    next_message_id: i64,
+    overrides: HashMap<String, VecDeque<Result<Value, types::Error>>>,
+    calls: Vec<Value>,
+    /// The updates sent, until taken by [`FakeTd::updates`]
+    updates: Vec<Update>,
+    /// The updates sent and not yet passed to the library
+    pending: Vec<Update>,
+}
+
+impl State {
+    fn emit(&mut self, update: Update) {
+        self.updates.push(update.clone());
+        self.pending.push(update);
+    }
+
+    fn set_authorization_state(&mut self, authorization_state: AuthorizationState) {
+        self.authorization_state = authorization_state.clone();
+        self.emit(Update::AuthorizationState(td_struct!(
+            types::UpdateAuthorizationState {
+                authorization_state
+            }
+        )));
+    }
+
+    fn authorize(&mut self) {
+        self.emit(Update::User(td_struct!(types::UpdateUser {
+            user: self.me.clone()
+        })));
+        self.set_authorization_state(AuthorizationState::Ready);
+    }
+
+    /// Fail unless the authorization state is the one expected by the
+    /// function, like TdLib.
+    fn expect(&self, function: &str, expected: bool) -> Result<(), types::Error> {
+        if expected {
+            Ok(())
+        } else {
+            Err(error(400, &format!("Call to {} unexpected", function)))
+        }
+    }
+
+    fn chat(&self, chat_id: ChatId) -> Result<&Chat, types::Error> {
+        self.chats
+            .iter()
+            .find(|chat| chat.chat.id == chat_id)
+            .ok_or_else(|| error(400, "Chat not found"))
+    }
+
+    fn chat_mut(&mut self, chat_id: ChatId) -> Result<&mut Chat, types::Error> {
+        self.chats
+            .iter_mut()
+            .find(|chat| chat.chat.id == chat_id)
+            .ok_or_else(|| error(400, "Chat not found"))
+    }
+
+    /// Send the chat with its user if not sent yet.
+    fn send_chat(&mut self, index: usize) {
+        let chat = &mut self.chats[index];
+        if chat.sent {
+            return;
+        }
+        chat.sent = true;
+        let user = Update::User(td_struct!(types::UpdateUser {
+            user: chat.user.clone()
+        }));
+        let new_chat = Update::NewChat(td_struct!(types::UpdateNewChat {
+            chat: chat.chat.clone()
+        }));
+        self.emit(user);
+        self.emit(new_chat);
+    }
+
+    /// Send at most `limit` chats not sent yet, by order; returns how many.
+    fn load_chats(&mut self, limit: i32) -> usize {
+        let mut unsent = (0..self.chats.len())
+            .filter(|&index| !self.chats[index].sent)
+            .collect::<Vec<_>>();
+        unsent.sort_by_key(|&index| std::cmp::Reverse(self.chats[index].chat.positions[0].order));
+        unsent.truncate(limit.max(0) as usize);
+        for &index in &unsent {
+            self.send_chat(index);
+        }
+        unsent.len()
+    }
+
+    /// Set the last message of the chat, moving it to the top of the main
+    /// chat list.
+    fn set_last_message(&mut self, chat_id: ChatId, message: &types::Message) {
+        self.next_order += 1;
+        let order = self.next_order;
+        let Ok(chat) = self.chat_mut(chat_id) else {
+            return;
+        };
+        chat.chat.positions[0].order = order;
+        chat.chat.last_message = Some(message.clone());
+        if !chat.sent {
+            return;
+        }
+        let update = Update::ChatLastMessage(td_struct!(types::UpdateChatLastMessage {
+            chat_id,
+            last_message: Some(message.clone()),
+            positions: chat.chat.positions.clone()
+        }));
+        self.emit(update);
+    }
+
+    fn new_message_id(&mut self) -> i64 {
+        self.next_message_id += 1;
+        self.next_message_id << 20
+    }
+
+    fn answer(&mut self, request: &Value) -> Result<Value, types::Error> {
+        self.calls.push(request.clone());
+        let function = request["@type"].as_str().unwrap_or_default().to_owned();
+        if let Some(response) = self
+            .overrides
+            .get_mut(&function)
+            .and_then(VecDeque::pop_front)
+        {
+            return response;
+        }
+        if matches!(self.authorization_state, AuthorizationState::Closed) {
+            return Err(error(500, "Request aborted"));
+        }
+
+        match function.as_str() {
+            "getAuthorizationState" => to_value(&self.authorization_state),
+            "setOption" | "setLogVerbosityLevel" => ok(),
+            "setTdlibParameters" => {
+                let expected = matches!(
+                    self.authorization_state,
+                    AuthorizationState::WaitTdlibParameters
+                );
+                self.expect(&function, expected)?;
+                self.set_authorization_state(AuthorizationState::WaitPhoneNumber);
+                ok()
+            }
+            "setAuthenticationPhoneNumber" => {
+                let expected = matches!(
+                    self.authorization_state,
+                    AuthorizationState::WaitPhoneNumber | AuthorizationState::WaitCode(_)
+                );
+                self.expect(&function, expected)?;
+                let phone_number: String = param(request, "phone_number")?;
+                self.me.phone_number = phone_number.clone();
+                let code_type = td_struct!(types::AuthenticationCodeTypeSms {
+                    length: self.code.len() as i32
+                });
+                let code_info = td_struct!(types::AuthenticationCodeInfo {
+                    phone_number,
+                    r#type: AuthenticationCodeType::Sms(code_type),
+                    next_type: None,
+                    timeout: 0
+                });
+                self.set_authorization_state(AuthorizationState::WaitCode(td_struct!(
+                    types::AuthorizationStateWaitCode { code_info }
+                )));
+                ok()
+            }
+            "checkAuthenticationCode" => {
+                let expected = matches!(self.authorization_state, AuthorizationState::WaitCode(_));
+                self.expect(&function, expected)?;
+                let code: String = param(request, "code")?;
+                if code != self.code {
+                    return Err(error(400, "PHONE_CODE_INVALID"));
+                }
+                if self.password.is_some() {
+                    let state = td_struct!(types::AuthorizationStateWaitPassword {
+                        password_hint: String::new(),
+                        has_recovery_email_address: false,
+                        has_passport_data: false,
+                        recovery_email_address_pattern: String::new()
+                    });
+                    self.set_authorization_state(AuthorizationState::WaitPassword(state));
+                } else {
+                    self.authorize();
+                }
+                ok()
+            }
+            "checkAuthenticationPassword" => {
+                let expected = matches!(
+                    self.authorization_state,
+                    AuthorizationState::WaitPassword(_)
+                );
+                self.expect(&function, expected)?;
+                let password: String = param(request, "password")?;
+                if self.password.as_ref() != Some(&password) {
+                    return Err(error(400, "PASSWORD_HASH_INVALID"));
+                }
+                self.authorize();
+                ok()
+            }
+            "logOut" => {
+                self.set_authorization_state(AuthorizationState::LoggingOut);
+                self.set_authorization_state(AuthorizationState::Closed);
+                ok()
+            }
+            "close" => {
+                self.set_authorization_state(AuthorizationState::Closing);
+                self.set_authorization_state(AuthorizationState::Closed);
+                ok()
+            }
+            _ if !matches!(self.authorization_state, AuthorizationState::Ready) => {
+                Err(error(401, "Unauthorized"))
+            }
+            "getMe" => to_value(&self.me),
+            "getUser" => {
+                let user_id: UserId = param(request, "user_id")?;
+                if user_id == self.me.id {
+                    return to_value(&self.me);
+                }
+                self.chats
+                    .iter()
+                    .find(|chat| chat.user.id == user_id)
+                    .map_or_else(
+                        || Err(error(404, "User not found")),
+                        |chat| to_value(&chat.user),
+                    )
+            }
+            "loadChats" | "getChats" => {
+                let chat_list: Option<ChatList> = param(request, "chat_list")?;
+                let limit: i32 = param(request, "limit")?;
+                let is_main = matches!(chat_list, None | Some(ChatList::Main));
+                let loaded = if is_main { self.load_chats(limit) } else { 0 };
+                if function == "loadChats" {
+                    return if loaded == 0 {
+                        Err(error(404, "Not Found"))
+                    } else {
+                        ok()
+                    };
+                }
+                let mut chats = self
+                    .chats
+                    .iter()
+                    .filter(|chat| is_main && chat.sent)
+                    .collect::<Vec<_>>();
+                chats.sort_by_key(|chat| std::cmp::Reverse(chat.chat.positions[0].order));
+                chats.truncate(limit.max(0) as usize);
+                to_value(td_struct!(types::Chats {
+                    total_count: chats.len() as i32,
+                    chat_ids: chats.iter().map(|chat| chat.chat.id).collect()
+                }))
+            }
+            "getChat" => {
+                let chat_id: ChatId = param(request, "chat_id")?;
+                let index = self
+                    .chats
+                    .iter()
+                    .position(|chat| chat.chat.id == chat_id)
+                    .ok_or_else(|| error(400, "Chat not found"))?;
+                self.send_chat(index);
+                to_value(&self.chats[index].chat)
+            }
+            "getChatHistory" => {
+                let chat_id: ChatId = param(request, "chat_id")?;
+                let from_message_id: MessageId = param(request, "from_message_id")?;
+                let limit: i32 = param(request, "limit")?;
+                let messages = self
+                    .chat(chat_id)?
+                    .messages
+                    .iter()
+                    .rev()
+                    .filter(|message| {
+                        from_message_id == MessageId::default() || message.id < from_message_id
+                    })
+                    .take(limit.max(0) as usize)
+                    .cloned()
+                    .map(Some)
+                    .collect::<Vec<_>>();
+                to_value(td_struct!(types::Messages {
+                    total_count: messages.len() as i32,
+                    messages
+                }))
+            }
+            "getMessage" => {
+                let chat_id: ChatId = param(request, "chat_id")?;
+                let message_id: MessageId = param(request, "message_id")?;
+                self.chat(chat_id)?
+                    .messages
+                    .iter()
+                    .find(|message| message.id == message_id)
+                    .map_or_else(|| Err(error(404, "Not Found")), to_value)
+            }
+            "sendMessage" => {
+                let chat_id: ChatId = param(request, "chat_id")?;
+                let content: InputMessageContent = param(request, "input_message_content")?;
+                let InputMessageContent::InputMessageText(content) = content else {
+                    return Err(error(400, "FakeTd only sends text messages"));
+                };
+                if !self.chat(chat_id)?.sent {
+                    return Err(error(400, "Chat not found"));
+                }
+                let content = MessageContent::MessageText(td_struct!(types::MessageText {
+                    text: content.text,
+                    web_page: None,
+                    link_preview_options: content.link_preview_options
+                }));
+                let id = self.new_message_id();
+                let sent = message(id, chat_id, self.me.id, true, content);
+                let mut pending = message(id + 1, chat_id, self.me.id, true, sent.content.clone());
+                pending.can_be_edited = false;
+                pending.sending_state = Some(MessageSendingState::Pending(Default::default()));
+
+                self.emit(Update::NewMessage(td_struct!(types::UpdateNewMessage {
+                    message: pending.clone()
+                })));
+                self.set_last_message(chat_id, &pending);
+                self.chat_mut(chat_id)?.messages.push(sent.clone());
+                self.emit(Update::MessageSendSucceeded(td_struct!(
+                    types::UpdateMessageSendSucceeded {
+                        message: sent.clone(),
+                        old_message_id: pending.id
+                    }
+                )));
+                self.set_last_message(chat_id, &sent);
+                to_value(&pending)
+            }
+            _ => Err(error(
+                400,
+                &format!("The function {} isn't faked by FakeTd", function),
+            )),
+        }
+    }
+}
+
+/// Pass the updates of the fake client to the library, as if received from
+/// TdLib.
+fn deliver(client_id: i32, pending: Vec<Update>) {
+    for update in pending {
+        crate::observe(&update, client_id);
+        updates::fan_out(update, client_id);
+    }
+}
+
+/// Answer the request with the fake client, if it is the one of a fake
+/// client; returns whether it was answered.
+pub(crate) fn send(client_id: i32, request: &Value) -> bool {
+    let state = FAKES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .cloned();
+    let Some(state) = state else {
+        return false;
+    };
+    let (response, pending) = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        let response = state.answer(request);
+        (response, std::mem::take(&mut state.pending))
+    };
+
+    // The updates of a request precede its response, like with TdLib, so
+    // the state tracked by the library is up to date once it is received
+    deliver(client_id, pending);
+
+    let mut response = response.unwrap_or_else(|e| to_value(e).unwrap_or_default());
+    response["@client_id"] = client_id.into();
+    if let Some(extra) = request["@extra"].as_u64() {
+        response["@extra"] = extra.into();
+        let ty = response["@type"].as_str().unwrap_or_default().to_owned();
+        crate::OBSERVER.notify(
+            extra as u32,
+            client_id,
+            Response::new(ty, response.to_string()),
+        );
+    }
+    true
+}
+
+/// A fake TdLib client, answering the requests sent with its `client_id`,
+/// see the [module documentation](self). It is removed when dropped.
+///
+/// The fake client starts waiting for the parameters of TdLib, then for a
+/// phone number and for the authentication code, `12345` by default, and
+/// for the password if one is set.
+///
+/// ```rust
+/// use tdlib_rs::enums::AuthorizationState;
+/// use tdlib_rs::testing::FakeTd;
+/// use tdlib_rs::functions;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let td = FakeTd::new().password("secret");
+/// let client_id = td.client_id();
+/// # let name = || String::from("test");
+///
+/// functions::set_tdlib_parameters(
+///     false, name(), name(), name(), true, true, true, false, 0, name(), name(), name(),
+///     name(), name(), client_id,
+/// )
+/// .await
+/// .unwrap();
+/// functions::set_authentication_phone_number("+1234567890".into(), None, client_id)
+///     .await
+///     .unwrap();
+/// let error = functions::check_authentication_code("00000".into(), client_id)
+///     .await
+///     .unwrap_err();
+/// assert_eq!(error.message, "PHONE_CODE_INVALID");
+///
+/// functions::check_authentication_code("12345".into(), client_id).await.unwrap();
+/// functions::check_authentication_password("secret".into(), client_id).await.unwrap();
+/// assert!(matches!(td.authorization_state(), AuthorizationState::Ready));
+/// # }
+/// ```
+pub struct FakeTd {
+    client_id: i32,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for FakeTd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeTd {
+    /// Start a fake client, waiting for the parameters of TdLib.
+    pub fn new() -> Self {
+        let client_id = NEXT_CLIENT_ID.fetch_sub(1, Ordering::Relaxed);
+        let state = State {
+            authorization_state: AuthorizationState::WaitTdlibParameters,
+            code: "12345".into(),
+            password: None,
+            me: user(MY_USER_ID, "Test", "User"),
+            chats: Vec::new(),
+            next_order: 0,
+            next_message_id: 0,
+            overrides: HashMap::new(),
+            calls: Vec::new(),
+            updates: Vec::new(),
+            pending: Vec::new(),
+        };
+        let td = Self {
+            client_id,
+            state: Arc::new(Mutex::new(state)),
+        };
+        FAKES
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(client_id, Arc::clone(&td.state));
+        td.run(|state| state.set_authorization_state(AuthorizationState::WaitTdlibParameters));
+        td
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Change the state, then pass the updates sent to the library.
+    fn run<T>(&self, f: impl FnOnce(&mut State) -> T) -> T {
+        let (result, pending) = {
+            let mut state = self.lock();
+            let result = f(&mut state);
+            (result, std::mem::take(&mut state.pending))
+        };
+        deliver(self.client_id, pending);
+        result
+    }
+
+    /// Returns the identifier of the fake client, to pass to the functions
+    /// of the library.
+    pub fn client_id(&self) -> i32 {
+        self.client_id
+    }
+
+    /// Accept the code instead of `12345`.
+    pub fn authentication_code(self, code: &str) -> Self {
+        self.lock().code = code.into();
+        self
+    }
+
+    /// Ask for the password once the code is checked.
+    pub fn password(self, password: &str) -> Self {
+        self.lock().password = Some(password.into());
+        self
+    }
+
+    /// Log in the user now, without the parameters, the phone number and
+    /// the code, to test the code running once the client is ready.
+    pub fn authorize(&self) {
+        self.run(State::authorize);
+    }
+
+    /// Returns the authorization state of the fake client.
+    pub fn authorization_state(&self) -> AuthorizationState {
+        self.lock().authorization_state.clone()
+    }
+
+    /// Returns the user logged in with the fake client.
+    pub fn me(&self) -> types::User {
+        self.lock().me.clone()
+    }
+
+    /// Add a private chat with a new user named after its title, at the top
+    /// of the main chat list. It is sent once loaded with `loadChats` or
+    /// requested with `getChat`, as a chat of the server.
+    pub fn add_chat(&self, title: &str) -> ChatId {
+        let mut state = self.lock();
+        state.next_order += 1;
+        let user = user(MY_USER_ID + 1 + state.chats.len() as i64, title, "");
+        let mut chat = chat(user.id, title);
+        chat.positions.push(td_struct!(types::ChatPosition {
+            list: ChatList::Main,
+            order: state.next_order,
+            is_pinned: false,
+            source: None
+        }));
+        let chat_id = chat.id;
+        state.chats.push(Chat {
+            chat,
+            user,
+            sent: false,
+            messages: Vec::new(),
+        });
+        chat_id
+    }
+
+    /// Receive a text message from the user of the chat.
+    ///
+    /// # Panics
+    ///
+    /// If the chat wasn't added with [`add_chat`](Self::add_chat).
+    pub fn receive_message(&self, chat_id: ChatId, text: &str) -> types::Message {
+        self.run(|state| {
+            let content = MessageContent::MessageText(td_struct!(types::MessageText {
+                text: td_struct!(types::FormattedText {
+                    text: text.into(),
+                    entities: Vec::new()
+                }),
+                web_page: None,
+                link_preview_options: None
+            }));
+            let id = state.new_message_id();
+            let chat = state
+                .chat_mut(chat_id)
+                .expect("the chat of the fake client");
+            let message = message(id, chat_id, chat.user.id, false, content);
+            chat.messages.push(message.clone());
+            chat.chat.unread_count += 1;
+            if chat.sent {
+                let update = Update::NewMessage(td_struct!(types::UpdateNewMessage {
+                    message: message.clone()
+                }));
+                state.emit(update);
+            }
+            state.set_last_message(chat_id, &message);
+            message
+        })
+    }
+
+    /// Returns the messages of the chat, the oldest first.
+    pub fn messages(&self, chat_id: ChatId) -> Vec<types::Message> {
+        self.lock()
+            .chat(chat_id)
+            .map(|chat| chat.messages.clone())
+            .unwrap_or_default()
+    }
+
+    /// Send the update, as if sent by TdLib.
+    pub fn emit(&self, update: Update) {
+        self.run(|state| state.emit(update));
+    }
+
+    /// Answer the next request of the function with the response, once,
+    /// instead of the fake client.
+    ///
+    /// # Panics
+    ///
+    /// If the response can't be serialized into JSON.
+    pub fn respond(&self, function: &str, response: impl Serialize) -> &Self {
+        let response = serde_json::to_value(response).expect("serialize the faked response");
+        self.override_next(function, Ok(response))
+    }
+
+    /// Fail the next request of the function with the error, once.
+    pub fn fail(&self, function: &str, code: i32, message: &str) -> &Self {
+        self.override_next(function, Err(error(code, message)))
+    }
+
+    /// Fail the next request of the function with a flood wait of the
+    /// seconds, once, like Telegram when too many requests are sent.
+    pub fn flood_wait(&self, function: &str, seconds: u32) -> &Self {
+        let message = format!("Too Many Requests: retry after {}", seconds);
+        self.fail(function, 429, &message)
+    }
+
+    fn override_next(&self, function: &str, response: Result<Value, types::Error>) -> &Self {
+        self.lock()
+            .overrides
+            .entry(function.into())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Returns the requests sent to the fake client, in order.
+    pub fn calls(&self) -> Vec<Value> {
+        self.lock().calls.clone()
+    }
+
+    /// Returns the requests of the function sent to the fake client, in
+    /// order.
+    pub fn calls_to(&self, function: &str) -> Vec<Value> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|request| request["@type"] == function)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the updates sent by the fake client since the last call, in
+    /// order.
+    pub fn updates(&self) -> Vec<Update> {
+        std::mem::take(&mut self.lock().updates)
+    }
+}
+
+impl Drop for FakeTd {
+    fn drop(&mut self) {
+        FAKES
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.client_id);
+    }
+}
+
+impl ClientApi for FakeTd {
+    fn send(&self, request: Value) -> impl Future<Output = Result<RawJson, types::Error>> + Send {
+        let response = self.run(|state| state.answer(&request));
+        future::ready(response.map(|response| RawJson::new(response.to_string())))
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Translate the messages and the texts with the translation service of
+//! Telegram. The language of the source is detected by Telegram, so only
+//! the language to translate to is given, as an IETF language tag such as
+//! `"en"`; languages which TdLib doesn't support are rejected by it.
+use crate::{enums, functions, types, TdError};
+
+/// Translate the text or the caption of the message.
+pub async fn translate(
+    message: &types::Message,
+    to_language_code: impl Into<String>,
+    client_id: i32,
+) -> Result<types::FormattedText, TdError> {
+    let enums::FormattedText::FormattedText(text) = functions::translate_message_text(
+        message.chat_id,
+        message.id,
+        to_language_code.into(),
+        client_id,
+    )
+    .await?;
+    Ok(text)
+}
+
+/// Translate the text, keeping its entities.
+pub async fn translate_text(
+    text: types::FormattedText,
+    to_language_code: impl Into<String>,
+    client_id: i32,
+) -> Result<types::FormattedText, TdError> {
+    let enums::FormattedText::FormattedText(text) =
+        functions::translate_text(text, to_language_code.into(), client_id).await?;
+    Ok(text)
+}
@@ -0,0 +1,189 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The unread mentions and reactions of the chats, tracked from the updates
+//! sent for them, and helpers to jump to the next one, like the `@` and the
+//! heart buttons of the Telegram applications.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::unread;
+//!
+//! # async fn jump(client_id: i32) -> Result<(), tdlib_rs::TdError> {
+//! # let chat_id = Default::default();
+//! if unread::counts(chat_id, client_id).mentions > 0 {
+//!     if let Some(message) = unread::next_mention(chat_id, client_id).await? {
+//!         // Scroll to the message, then mark it as read
+//!         unread::mark_read(chat_id, message.id, client_id).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::enums::{self, AuthorizationState, SearchMessagesFilter, Update};
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, types, TdError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// The number of messages searched at once for the next unread one.
+const SEARCH_LIMIT: i32 = 100;
+
+/// The unread mentions and reactions of a chat.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnreadCounts {
+    /// The number of unread messages mentioning the user
+    pub mentions: i32,
+    /// The number of messages of the user with unread reactions
+    pub reactions: i32,
+}
+
+static COUNTS: Lazy<RwLock<HashMap<i32, HashMap<ChatId, UnreadCounts>>>> =
+    Lazy::new(RwLock::default);
+
+/// Keep the unread mentions and reactions of the chats of the client up to
+/// date.
+pub(crate) fn observe(update: &Update, client_id: i32) {
+    let mut counts = COUNTS.write().unwrap_or_else(|e| e.into_inner());
+    let mut set = |chat_id: ChatId, mentions: Option<i32>, reactions: Option<i32>| {
+        let chat = counts
+            .entry(client_id)
+            .or_default()
+            .entry(chat_id)
+            .or_default();
+        chat.mentions = mentions.unwrap_or(chat.mentions);
+        chat.reactions = reactions.unwrap_or(chat.reactions);
+    };
+    match update {
+        Update::NewChat(update) => set(
+            update.chat.id,
+            Some(update.chat.unread_mention_count),
+            Some(update.chat.unread_reaction_count),
+        ),
+        Update::ChatUnreadMentionCount(update) => {
+            set(update.chat_id, Some(update.unread_mention_count), None)
+        }
+        Update::MessageMentionRead(update) => {
+            set(update.chat_id, Some(update.unread_mention_count), None)
+        }
+        Update::ChatUnreadReactionCount(update) => {
+            set(update.chat_id, None, Some(update.unread_reaction_count))
+        }
+        Update::MessageUnreadReactions(update) => {
+            set(update.chat_id, None, Some(update.unread_reaction_count))
+        }
+        Update::AuthorizationState(update) => {
+            if matches!(update.authorization_state, AuthorizationState::Closed) {
+                counts.remove(&client_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the unread mentions and reactions of the chat, none if TdLib
+/// didn't send the chat.
+pub fn counts(chat_id: ChatId, client_id: i32) -> UnreadCounts {
+    COUNTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .and_then(|chats| chats.get(&chat_id))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Returns the chats of the client with unread mentions or reactions.
+pub fn chats(client_id: i32) -> Vec<(ChatId, UnreadCounts)> {
+    COUNTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&client_id)
+        .map(|chats| {
+            chats
+                .iter()
+                .filter(|(_, counts)| **counts != UnreadCounts::default())
+                .map(|(&chat_id, &counts)| (chat_id, counts))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the oldest message of the chat found with the filter.
+async fn oldest(
+    chat_id: ChatId,
+    filter: SearchMessagesFilter,
+    client_id: i32,
+) -> Result<Option<types::Message>, TdError> {
+    let mut from_message_id = MessageId::default();
+    let mut oldest = None;
+    loop {
+        let enums::FoundChatMessages::FoundChatMessages(found) = functions::search_chat_messages(
+            chat_id,
+            String::new(),
+            None,
+            from_message_id,
+            0,
+            SEARCH_LIMIT,
+            Some(filter.clone()),
+            0,
+            0,
+            client_id,
+        )
+        .await?;
+        let next_from_message_id = found.next_from_message_id;
+        if let Some(message) = found.messages.into_iter().last() {
+            oldest = Some(message);
+        }
+        if next_from_message_id == MessageId::default() || next_from_message_id == from_message_id {
+            return Ok(oldest);
+        }
+        from_message_id = next_from_message_id;
+    }
+}
+
+/// Returns the oldest unread message of the chat mentioning the user, the
+/// next one to show, or `None` if all of them were read.
+pub async fn next_mention(
+    chat_id: ChatId,
+    client_id: i32,
+) -> Result<Option<types::Message>, TdError> {
+    oldest(chat_id, SearchMessagesFilter::UnreadMention, client_id).await
+}
+
+/// Returns the oldest message of the user in the chat with unread
+/// reactions, the next one to show, or `None` if all of them were read.
+pub async fn next_reaction(
+    chat_id: ChatId,
+    client_id: i32,
+) -> Result<Option<types::Message>, TdError> {
+    oldest(chat_id, SearchMessagesFilter::UnreadReaction, client_id).await
+}
+
+/// Mark the message as read, along with its mention and its reactions, once
+/// it is shown.
+pub async fn mark_read(
+    chat_id: ChatId,
+    message_id: MessageId,
+    client_id: i32,
+) -> Result<(), TdError> {
+    functions::view_messages(chat_id, vec![message_id], None, true, client_id).await?;
+    Ok(())
+}
+
+/// Mark every mention of the user in the chat as read.
+pub async fn read_all_mentions(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    functions::read_all_chat_mentions(chat_id, client_id).await?;
+    Ok(())
+}
+
+/// Mark every reaction to the messages of the user in the chat as read.
+pub async fn read_all_reactions(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    functions::read_all_chat_reactions(chat_id, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,630 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A receive loop running on a dedicated thread, which passes the updates to
+//! any number of subscribers, buffering them in a bounded queue for each
+//! subscriber until it consumes them.
+//!
+//! The loop also routes the responses to the functions waiting for them, so
+//! `receive` must not be called elsewhere once the loop has been started.
+//!
+//! The updates of every client are merged, each tagged with its `client_id`,
+//! so that the accounts of a multi-account application can be handled in a
+//! single loop: each account can have its own filter, and can be paused to
+//! keep its updates aside until it is resumed.
+//!
+//! ```rust,no_run
+//! use tdlib_rs::enums::Update;
+//! use tdlib_rs::updates::{self, Backpressure, UpdateFilter};
+//!
+//! # async fn run(work: i32, personal: i32) {
+//! let receiver = updates::subscribe(1000, Backpressure::DropOldest);
+//! receiver.set_client_filter(
+//!     personal,
+//!     UpdateFilter::new().when(|update| matches!(update, Update::NewMessage(_))),
+//! );
+//! receiver.pause(work);
+//! loop {
+//!     let (update, client_id) = receiver.recv().await;
+//!     println!("{client_id}: {update:?}");
+//! }
+//! # }
+//! ```
+use crate::enums::Update;
+use crate::ids::ChatId;
+use crate::runtime;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Once, RwLock};
+use std::task::Poll;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// What the receive loop does with an update when the queue is full.
+#[derive(Clone, Copy, Debug)]
+pub enum Backpressure {
+    /// Block the receive loop until the application consumes an update.
+    /// Note that the responses are not routed while the loop is blocked.
+    Block,
+    /// Drop the oldest update of the queue to make room for the new one.
+    DropOldest,
+    /// Replace the queued update superseded by the new one, according to the
+    /// function, and block the receive loop if there is none. The function is
+    /// called with the new update and each queued one, oldest first.
+    ///
+    /// [`supersedes`] is suitable for most applications.
+    Coalesce(fn(&Update, &Update) -> bool),
+}
+
+/// Returns `true` if the state carried by the `old` update is entirely
+/// replaced by the `new` one, for the updates reporting the current value of
+/// a counter or of a status: the status of a user, the read position, the
+/// online members and the unread counters of a chat, a file, an option and
+/// the connection state.
+pub fn supersedes(new: &Update, old: &Update) -> bool {
+    match (new, old) {
+        (Update::UserStatus(new), Update::UserStatus(old)) => new.user_id == old.user_id,
+        (Update::ChatReadInbox(new), Update::ChatReadInbox(old)) => new.chat_id == old.chat_id,
+        (Update::ChatReadOutbox(new), Update::ChatReadOutbox(old)) => new.chat_id == old.chat_id,
+        (Update::ChatOnlineMemberCount(new), Update::ChatOnlineMemberCount(old)) => {
+            new.chat_id == old.chat_id
+        }
+        (Update::ChatUnreadMentionCount(new), Update::ChatUnreadMentionCount(old)) => {
+            new.chat_id == old.chat_id
+        }
+        (Update::ChatUnreadReactionCount(new), Update::ChatUnreadReactionCount(old)) => {
+            new.chat_id == old.chat_id
+        }
+        (Update::File(new), Update::File(old)) => new.file.id == old.file.id,
+        (Update::Option(new), Update::Option(old)) => new.name == old.name,
+        (Update::ConnectionState(_), Update::ConnectionState(_)) => true,
+        _ => false,
+    }
+}
+
+struct Queue {
+    updates: VecDeque<(Update, i32)>,
+    dropped: u64,
+    /// The updates of the paused clients, kept apart from the queue so that
+    /// they neither count in its capacity nor block the receive loop
+    paused: HashMap<i32, VecDeque<Update>>,
+}
+
+/// Which updates a subscriber receives, all of them by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UpdateFilter {
+    client_id: Option<i32>,
+    chat_id: Option<ChatId>,
+    predicate: Option<fn(&Update) -> bool>,
+}
+
+impl UpdateFilter {
+    /// Start a filter accepting every update.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the updates of the client.
+    pub fn client(mut self, client_id: i32) -> Self {
+        self.client_id = Some(client_id);
+        self
+    }
+
+    /// Only the updates about the chat, according to [`Update::chat_id`].
+    pub fn chat(mut self, chat_id: ChatId) -> Self {
+        self.chat_id = Some(chat_id);
+        self
+    }
+
+    /// Only the updates for which the function returns `true`, for instance
+    /// `|update| matches!(update, Update::NewMessage(_))` to filter by kind.
+    pub fn when(mut self, predicate: fn(&Update) -> bool) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Returns `true` if the update of the client passes the filter.
+    pub fn accepts(&self, update: &Update, client_id: i32) -> bool {
+        self.client_id.is_none_or(|id| id == client_id)
+            && self
+                .chat_id
+                .is_none_or(|chat_id| update.chat_id() == Some(chat_id))
+            && self.predicate.is_none_or(|predicate| predicate(update))
+    }
+}
+
+struct Shared {
+    capacity: usize,
+    backpressure: Backpressure,
+    filter: UpdateFilter,
+    client_filters: RwLock<HashMap<i32, UpdateFilter>>,
+    queue: Mutex<Queue>,
+    not_full: Condvar,
+    not_empty: Condvar,
+    notify: Notify,
+    closed: AtomicBool,
+}
+
+impl Shared {
+    fn new(capacity: usize, backpressure: Backpressure, filter: UpdateFilter) -> Self {
+        Self {
+            capacity,
+            backpressure,
+            filter,
+            client_filters: RwLock::default(),
+            queue: Mutex::new(Queue {
+                updates: VecDeque::with_capacity(capacity),
+                dropped: 0,
+                paused: HashMap::new(),
+            }),
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn accepts(&self, update: &Update, client_id: i32) -> bool {
+        self.filter.accepts(update, client_id)
+            && self
+                .client_filters
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&client_id)
+                .is_none_or(|filter| filter.accepts(update, client_id))
+    }
+
+    fn push(&self, update: Update, client_id: i32) {
+        let mut queue = self.lock();
+
+        if let Some(paused) = queue.paused.get_mut(&client_id) {
+            if let Backpressure::Coalesce(supersedes) = self.backpressure {
+                if let Some(queued) = paused.iter_mut().find(|queued| supersedes(&update, queued)) {
+                    *queued = update;
+                    queue.dropped += 1;
+                    return;
+                }
+            }
+            paused.push_back(update);
+            return;
+        }
+
+        if let Backpressure::Coalesce(supersedes) = self.backpressure {
+            if let Some(queued) = queue
+                .updates
+                .iter_mut()
+                .find(|(queued, id)| *id == client_id && supersedes(&update, queued))
+            {
+                queued.0 = update;
+                queue.dropped += 1;
+                return;
+            }
+        }
+
+        if queue.updates.len() >= self.capacity {
+            match self.backpressure {
+                Backpressure::DropOldest => {
+                    queue.updates.pop_front();
+                    queue.dropped += 1;
+                }
+                Backpressure::Block | Backpressure::Coalesce(_) => {
+                    while queue.updates.len() >= self.capacity
+                        && !self.closed.load(Ordering::Acquire)
+                    {
+                        queue = self.not_full.wait(queue).unwrap_or_else(|e| e.into_inner());
+                    }
+                }
+            }
+        }
+
+        queue.updates.push_back((update, client_id));
+        drop(queue);
+        self.not_empty.notify_one();
+        self.notify.notify_one();
+    }
+
+    fn pop(&self, queue: &mut Queue) -> Option<(Update, i32)> {
+        let update = queue.updates.pop_front();
+        if update.is_some() {
+            self.not_full.notify_one();
+        }
+        update
+    }
+}
+
+/// A subscription to the updates created by [`subscribe`], which ends when
+/// it is dropped.
+pub struct UpdateReceiver {
+    shared: Arc<Shared>,
+}
+
+impl UpdateReceiver {
+    /// Wait for the next update, returning it with the associated `client_id`.
+    pub async fn recv(&self) -> (Update, i32) {
+        loop {
+            if let Some(update) = self.try_recv() {
+                return update;
+            }
+            self.shared.notify.notified().await;
+        }
+    }
+
+    /// Block the current thread until the next update is received, returning
+    /// it with the associated `client_id`.
+    pub fn blocking_recv(&self) -> (Update, i32) {
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(update) = self.shared.pop(&mut queue) {
+                return update;
+            }
+            queue = self
+                .shared
+                .not_empty
+                .wait(queue)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Wait for the next update, then for the ones received at most
+    /// `max_wait` after it, returning at most `max_items` of them, so that
+    /// a burst of updates can be handled at once, for instance with a
+    /// single redraw.
+    pub async fn recv_batch(&self, max_items: usize, max_wait: Duration) -> Vec<(Update, i32)> {
+        let max_items = max_items.max(1);
+        let mut updates = vec![self.recv().await];
+        let mut sleep = pin!(runtime::sleep(max_wait));
+        while updates.len() < max_items {
+            if let Some(update) = self.try_recv() {
+                updates.push(update);
+                continue;
+            }
+            let mut notified = pin!(self.shared.notify.notified());
+            let timed_out = poll_fn(|cx| {
+                if notified.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(false);
+                }
+                sleep.as_mut().poll(cx).map(|_| true)
+            })
+            .await;
+            if timed_out {
+                updates.extend(self.try_recv());
+                break;
+            }
+        }
+        updates
+    }
+
+    /// Block the current thread like [`Self::recv_batch`], until a batch of
+    /// updates is received.
+    pub fn blocking_recv_batch(&self, max_items: usize, max_wait: Duration) -> Vec<(Update, i32)> {
+        let max_items = max_items.max(1);
+        let mut updates = vec![self.blocking_recv()];
+        let deadline = Instant::now() + max_wait;
+        let mut queue = self.shared.lock();
+        while updates.len() < max_items {
+            if let Some(update) = self.shared.pop(&mut queue) {
+                updates.push(update);
+                continue;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            queue = self
+                .shared
+                .not_empty
+                .wait_timeout(queue, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        updates
+    }
+
+    /// Returns the next update if one is queued, without waiting.
+    pub fn try_recv(&self) -> Option<(Update, i32)> {
+        let mut queue = self.shared.lock();
+        self.shared.pop(&mut queue)
+    }
+
+    /// Keep the updates of the client aside, without receiving them, until
+    /// it is resumed, including the ones already queued.
+    ///
+    /// They are kept apart from the queue: they don't count in its capacity
+    /// and never block the receive loop, whatever the backpressure policy,
+    /// so the memory they use grows until the client is resumed. With
+    /// [`Backpressure::Coalesce`], the updates kept aside are still replaced
+    /// by the ones superseding them.
+    pub fn pause(&self, client_id: i32) {
+        let mut queue = self.shared.lock();
+        if queue.paused.contains_key(&client_id) {
+            return;
+        }
+        let (paused, updates) = std::mem::take(&mut queue.updates)
+            .into_iter()
+            .partition::<VecDeque<_>, _>(|&(_, id)| id == client_id);
+        let freed = !paused.is_empty();
+        queue.updates = updates;
+        queue.paused.insert(
+            client_id,
+            paused.into_iter().map(|(update, _)| update).collect(),
+        );
+        drop(queue);
+        if freed {
+            self.shared.not_full.notify_all();
+        }
+    }
+
+    /// Receive again the updates of the client, starting with the ones kept
+    /// aside while it was paused, queued after the ones already queued even
+    /// if they exceed the capacity of the queue.
+    pub fn resume(&self, client_id: i32) {
+        let mut queue = self.shared.lock();
+        let Some(paused) = queue.paused.remove(&client_id) else {
+            return;
+        };
+        queue
+            .updates
+            .extend(paused.into_iter().map(|update| (update, client_id)));
+        drop(queue);
+        self.shared.not_empty.notify_all();
+        self.shared.notify.notify_one();
+    }
+
+    /// Returns `true` if the updates of the client are paused.
+    pub fn is_paused(&self, client_id: i32) -> bool {
+        self.shared.lock().paused.contains_key(&client_id)
+    }
+
+    /// Only receive the updates of the client passing the filter, in
+    /// addition to the filter of the subscription, replacing the previous
+    /// filter of the client.
+    pub fn set_client_filter(&self, client_id: i32, filter: UpdateFilter) {
+        self.shared
+            .client_filters
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(client_id, filter);
+    }
+
+    /// Remove the filter of the client set with [`Self::set_client_filter`].
+    pub fn remove_client_filter(&self, client_id: i32) {
+        self.shared
+            .client_filters
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&client_id);
+    }
+
+    /// Returns the number of queued updates, including the ones kept aside
+    /// for the paused clients.
+    pub fn len(&self) -> usize {
+        let queue = self.shared.lock();
+        queue.updates.len() + queue.paused.values().map(VecDeque::len).sum::<usize>()
+    }
+
+    /// Returns `true` if no update is queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of updates dropped to make room for newer ones, or
+    /// replaced by the updates superseding them, since the loop was started.
+    pub fn dropped(&self) -> u64 {
+        self.shared.lock().dropped
+    }
+}
+
+impl Drop for UpdateReceiver {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        self.shared.not_full.notify_all();
+    }
+}
+
+static SUBSCRIBERS: Lazy<Mutex<Vec<Arc<Shared>>>> = Lazy::new(Mutex::default);
+static RECEIVE_LOOP: Once = Once::new();
+
+/// Returns `true` if the receive loop has been started.
+pub(crate) fn is_running() -> bool {
+    RECEIVE_LOOP.is_completed()
+}
+
+/// Pass the update to every subscriber, forgetting those dropped.
+pub(crate) fn fan_out(update: Update, client_id: i32) {
+    let subscribers: Vec<_> = {
+        let mut subscribers = SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain(|shared| !shared.closed.load(Ordering::Acquire));
+        subscribers
+            .iter()
+            .filter(|shared| shared.accepts(&update, client_id))
+            .cloned()
+            .collect()
+    };
+    if let Some((last, others)) = subscribers.split_last() {
+        for shared in others {
+            shared.push(update.clone(), client_id);
+        }
+        last.push(update, client_id);
+    }
+}
+
+/// Subscribe to the updates, buffering at most `capacity` of them and
+/// applying the `backpressure` policy when the queue is full.
+///
+/// The first subscription starts the receive loop on a dedicated thread,
+/// which runs until the end of the program: it is the only one receiving from
+/// TdLib, routing the responses as soon as they are received and passing each
+/// update to every subscriber, so any number of consumers can subscribe.
+/// Note that a subscriber with the [`Backpressure::Block`] policy blocks the
+/// loop, and so all the other subscribers, while its queue is full.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero or if the thread can't be spawned.
+pub fn subscribe(capacity: usize, backpressure: Backpressure) -> UpdateReceiver {
+    subscribe_filtered(capacity, backpressure, UpdateFilter::default())
+}
+
+/// Subscribe like [`subscribe`] to the updates passing the filter only, so
+/// that each component of an application can receive the updates it needs.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero or if the thread can't be spawned.
+pub fn subscribe_filtered(
+    capacity: usize,
+    backpressure: Backpressure,
+    filter: UpdateFilter,
+) -> UpdateReceiver {
+    assert!(
+        capacity > 0,
+        "the capacity of the update queue must be positive"
+    );
+
+    let shared = Arc::new(Shared::new(capacity, backpressure, filter));
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::clone(&shared));
+
+    RECEIVE_LOOP.call_once(|| {
+        thread::Builder::new()
+            .name("tdlib-rs-receiver".into())
+            .spawn(|| loop {
+                if let Some((update, _, client_id)) =
+                    crate::receive_timeout(crate::receive_timeout_secs())
+                {
+                    fan_out(update, client_id);
+                }
+            })
+            .expect("failed to spawn the receive loop");
+    });
+
+    UpdateReceiver { shared }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::OptionValue;
+    use crate::types;
+
+    fn receiver(capacity: usize, backpressure: Backpressure) -> UpdateReceiver {
+        UpdateReceiver {
+            shared: Arc::new(Shared::new(capacity, backpressure, UpdateFilter::default())),
+        }
+    }
+
+    fn option(name: &str, value: i64) -> Update {
+        Update::Option(td_struct!(types::UpdateOption {
+            name: name.into(),
+            value: OptionValue::Integer(td_struct!(types::OptionValueInteger { value }))
+        }))
+    }
+
+    fn value(update: &Update) -> i64 {
+        match update {
+            Update::Option(update) => match &update.value {
+                OptionValue::Integer(value) => value.value,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn drain(receiver: &UpdateReceiver) -> Vec<(i64, i32)> {
+        std::iter::from_fn(|| receiver.try_recv())
+            .map(|(update, client_id)| (value(&update), client_id))
+            .collect()
+    }
+
+    #[test]
+    fn drop_oldest_drops_the_oldest_update() {
+        let receiver = receiver(2, Backpressure::DropOldest);
+        for i in 0..3 {
+            receiver.shared.push(option("a", i), 1);
+        }
+        assert_eq!(drain(&receiver), [(1, 1), (2, 1)]);
+        assert_eq!(receiver.dropped(), 1);
+    }
+
+    #[test]
+    fn coalesce_replaces_the_superseded_update() {
+        let receiver = receiver(2, Backpressure::Coalesce(supersedes));
+        receiver.shared.push(option("a", 0), 1);
+        receiver.shared.push(option("b", 1), 1);
+        receiver.shared.push(option("a", 2), 1);
+        assert_eq!(drain(&receiver), [(2, 1), (1, 1)]);
+        assert_eq!(receiver.dropped(), 1);
+    }
+
+    #[test]
+    fn block_waits_for_room() {
+        let receiver = receiver(1, Backpressure::Block);
+        receiver.shared.push(option("a", 0), 1);
+        let shared = Arc::clone(&receiver.shared);
+        let pusher = thread::spawn(move || shared.push(option("a", 1), 1));
+        thread::sleep(Duration::from_millis(50));
+        assert!(!pusher.is_finished());
+        assert_eq!(drain(&receiver), [(0, 1)]);
+        pusher.join().unwrap();
+        assert_eq!(drain(&receiver), [(1, 1)]);
+    }
+
+    #[test]
+    fn paused_client_never_blocks_the_loop() {
+        let receiver = receiver(1, Backpressure::Block);
+        receiver.shared.push(option("a", 0), 1);
+        receiver.pause(1);
+        for i in 1..5 {
+            receiver.shared.push(option("a", i), 1);
+        }
+        receiver.shared.push(option("b", 10), 2);
+        assert_eq!(receiver.len(), 6);
+        assert_eq!(drain(&receiver), [(10, 2)]);
+
+        receiver.resume(1);
+        assert!(!receiver.is_paused(1));
+        let expected = (0..5).map(|i| (i, 1)).collect::<Vec<_>>();
+        assert_eq!(drain(&receiver), expected);
+    }
+
+    #[test]
+    fn paused_client_never_evicts_the_others() {
+        let receiver = receiver(2, Backpressure::DropOldest);
+        receiver.pause(1);
+        receiver.shared.push(option("b", 10), 2);
+        for i in 0..5 {
+            receiver.shared.push(option("a", i), 1);
+        }
+        receiver.shared.push(option("b", 11), 2);
+        assert_eq!(receiver.dropped(), 0);
+        assert_eq!(drain(&receiver), [(10, 2), (11, 2)]);
+    }
+
+    #[test]
+    fn paused_client_updates_are_coalesced() {
+        let receiver = receiver(1, Backpressure::Coalesce(supersedes));
+        receiver.pause(1);
+        for i in 0..3 {
+            receiver.shared.push(option("a", i), 1);
+        }
+        receiver.shared.push(option("b", 10), 1);
+        receiver.resume(1);
+        assert_eq!(drain(&receiver), [(2, 1), (10, 1)]);
+        assert_eq!(receiver.dropped(), 2);
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The usernames of the user and of the supergroups and channels: the
+//! editable one, and the collectible ones which can only be activated,
+//! disabled and reordered.
+//!
+//! The editable usernames are checked with [`is_valid`] before any request,
+//! failing with the `USERNAME_INVALID` error of Telegram.
+use crate::enums::{self, CheckChatUsernameResult};
+use crate::ids::ChatId;
+use crate::{functions, TdError};
+
+/// The minimum length of an editable username.
+pub const MIN_LENGTH: usize = 5;
+
+/// The maximum length of an editable username.
+pub const MAX_LENGTH: usize = 32;
+
+/// Returns `true` if the username can be set as an editable username: from
+/// [`MIN_LENGTH`] to [`MAX_LENGTH`] Latin letters, digits and underscores,
+/// starting with a letter, neither ending with an underscore nor containing
+/// two in a row.
+pub fn is_valid(username: &str) -> bool {
+    (MIN_LENGTH..=MAX_LENGTH).contains(&username.len())
+        && username.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !username.ends_with('_')
+        && !username.contains("__")
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Fails without a request unless the username is empty or valid.
+fn check(username: &str) -> Result<(), TdError> {
+    if username.is_empty() || is_valid(username) {
+        Ok(())
+    } else {
+        Err(TdError::invalid(400, "USERNAME_INVALID"))
+    }
+}
+
+/// Returns the identifier of the supergroup or channel of the chat.
+async fn supergroup_id(chat_id: ChatId, client_id: i32) -> Result<i64, TdError> {
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await?;
+    chat.r#type
+        .supergroup_id()
+        .ok_or_else(|| TdError::invalid(400, "Chat is not a supergroup or channel"))
+}
+
+/// Change the editable username of the user, or remove it if empty.
+pub async fn set(username: String, client_id: i32) -> Result<(), TdError> {
+    check(&username)?;
+    functions::set_username(username, client_id).await?;
+    Ok(())
+}
+
+/// Activate or disable a collectible username of the user.
+pub async fn set_active(username: String, is_active: bool, client_id: i32) -> Result<(), TdError> {
+    functions::toggle_username_is_active(username, is_active, client_id).await?;
+    Ok(())
+}
+
+/// Change the order of the active usernames of the user, which must all be
+/// given.
+pub async fn reorder(usernames: Vec<String>, client_id: i32) -> Result<(), TdError> {
+    functions::reorder_active_usernames(usernames, client_id).await?;
+    Ok(())
+}
+
+/// Returns whether the username can be set for the chat: a supergroup, a
+/// channel, the private chat with the user, or 0 for a chat being created.
+/// The invalid usernames are reported without a request.
+pub async fn check_chat_username(
+    chat_id: ChatId,
+    username: String,
+    client_id: i32,
+) -> Result<CheckChatUsernameResult, TdError> {
+    if !is_valid(&username) {
+        return Ok(CheckChatUsernameResult::UsernameInvalid);
+    }
+    Ok(functions::check_chat_username(chat_id, username, client_id).await?)
+}
+
+/// Change the editable username of the supergroup or channel, or remove it
+/// if empty.
+pub async fn set_chat(chat_id: ChatId, username: String, client_id: i32) -> Result<(), TdError> {
+    check(&username)?;
+    let supergroup_id = supergroup_id(chat_id, client_id).await?;
+    functions::set_supergroup_username(supergroup_id, username, client_id).await?;
+    Ok(())
+}
+
+/// Activate or disable a collectible username of the supergroup or channel.
+pub async fn set_chat_active(
+    chat_id: ChatId,
+    username: String,
+    is_active: bool,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let supergroup_id = supergroup_id(chat_id, client_id).await?;
+    functions::toggle_supergroup_username_is_active(supergroup_id, username, is_active, client_id)
+        .await?;
+    Ok(())
+}
+
+/// Change the order of the active usernames of the supergroup or channel,
+/// which must all be given.
+pub async fn reorder_chat(
+    chat_id: ChatId,
+    usernames: Vec<String>,
+    client_id: i32,
+) -> Result<(), TdError> {
+    let supergroup_id = supergroup_id(chat_id, client_id).await?;
+    functions::reorder_supergroup_active_usernames(supergroup_id, usernames, client_id).await?;
+    Ok(())
+}
+
+/// Disable every username of the supergroup or channel, making it private.
+pub async fn disable_all_chat(chat_id: ChatId, client_id: i32) -> Result<(), TdError> {
+    let supergroup_id = supergroup_id(chat_id, client_id).await?;
+    functions::disable_all_supergroup_usernames(supergroup_id, client_id).await?;
+    Ok(())
+}
@@ -0,0 +1,308 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Convert the offsets of the entities, in UTF-16 code units, into the byte
+//! offsets of the strings of Rust, and edit the formatted texts keeping
+//! their entities on the same characters.
+//!
+//! The offsets which don't fall on a character, like the middle of an emoji
+//! made of two UTF-16 code units, or past the end of the text, are rejected
+//! instead of panicking when slicing.
+//!
+//! ```rust
+//! use tdlib_rs::{markdown, utf16};
+//!
+//! let mut text = markdown::parse("😀 *bold*").unwrap();
+//! assert_eq!(utf16::entity_text(&text, &text.entities[0]), Some("bold"));
+//! assert_eq!(utf16::to_byte_offset(&text.text, 3), Some(5));
+//!
+//! utf16::insert(&mut text, 0, "Hi ").unwrap();
+//! assert_eq!(text.text, "Hi 😀 bold");
+//! assert_eq!(utf16::entity_text(&text, &text.entities[0]), Some("bold"));
+//! ```
+use crate::types;
+use std::fmt;
+use std::ops::Range;
+
+/// The error returned when an offset doesn't fall on a character of the
+/// text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidOffset {
+    /// The offset, in UTF-16 code units.
+    pub offset: i32,
+}
+
+impl fmt::Display for InvalidOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The UTF-16 offset {} doesn't fall on a character",
+            self.offset
+        )
+    }
+}
+
+impl std::error::Error for InvalidOffset {}
+
+/// Returns the length of the text in UTF-16 code units.
+pub fn len(text: &str) -> i32 {
+    text.encode_utf16().count() as i32
+}
+
+/// Returns the byte offset of the UTF-16 offset in the text, if it falls on
+/// a character or at the end of the text.
+pub fn to_byte_offset(text: &str, offset: i32) -> Option<usize> {
+    let mut utf16 = 0;
+    for (byte, c) in text.char_indices() {
+        if utf16 == offset {
+            return Some(byte);
+        }
+        if utf16 > offset {
+            return None;
+        }
+        utf16 += c.len_utf16() as i32;
+    }
+    (utf16 == offset).then_some(text.len())
+}
+
+/// Returns the UTF-16 offset of the byte offset in the text, if it falls on
+/// a character or at the end of the text.
+pub fn from_byte_offset(text: &str, byte: usize) -> Option<i32> {
+    let prefix = text.get(..byte)?;
+    Some(len(prefix))
+}
+
+/// Returns the byte range of the UTF-16 range in the text, if its bounds
+/// fall on characters.
+pub fn range(text: &str, offset: i32, length: i32) -> Option<Range<usize>> {
+    let start = to_byte_offset(text, offset)?;
+    let end = offset.checked_add(length)?;
+    let end = start + to_byte_offset(&text[start..], end - offset)?;
+    Some(start..end)
+}
+
+/// Returns the part of the text in the UTF-16 range, if its bounds fall on
+/// characters.
+pub fn slice(text: &str, offset: i32, length: i32) -> Option<&str> {
+    range(text, offset, length).map(|range| &text[range])
+}
+
+/// Returns the part of the text covered by the entity, if its bounds fall
+/// on characters.
+pub fn entity_text<'a>(
+    text: &'a types::FormattedText,
+    entity: &types::TextEntity,
+) -> Option<&'a str> {
+    slice(&text.text, entity.offset, entity.length)
+}
+
+/// Move the entities as if the UTF-16 range was replaced with `inserted`
+/// code units: the entities after the range are shifted, the ones
+/// containing it grow or shrink, and the ones inside it are removed. The
+/// entities starting or ending at an inserted text don't cover it. The
+/// offsets saturate instead of overflowing.
+pub fn adjust(entities: &mut Vec<types::TextEntity>, offset: i32, length: i32, inserted: i32) {
+    let removed_end = offset.saturating_add(length);
+    let shift = inserted.saturating_sub(length);
+    entities.retain_mut(|entity| {
+        let start = entity.offset;
+        let end = entity.offset.saturating_add(entity.length);
+        if end <= offset {
+            return true;
+        }
+        if start >= removed_end {
+            entity.offset = entity.offset.saturating_add(shift);
+            return true;
+        }
+        let new_start = if start <= offset {
+            start
+        } else {
+            offset.saturating_add(inserted)
+        };
+        let new_end = if end >= removed_end {
+            end.saturating_add(shift)
+        } else {
+            offset
+        };
+        entity.offset = new_start;
+        entity.length = new_end.saturating_sub(new_start);
+        entity.length > 0
+    });
+}
+
+/// Replace the UTF-16 range of the text with the string, moving the
+/// entities as [`adjust`] does.
+pub fn replace(
+    text: &mut types::FormattedText,
+    offset: i32,
+    length: i32,
+    with: &str,
+) -> Result<(), InvalidOffset> {
+    let bytes = range(&text.text, offset, length).ok_or(InvalidOffset {
+        offset: if to_byte_offset(&text.text, offset).is_some() {
+            offset.saturating_add(length)
+        } else {
+            offset
+        },
+    })?;
+    text.text.replace_range(bytes, with);
+    adjust(&mut text.entities, offset, length, len(with));
+    Ok(())
+}
+
+/// Insert the string at the UTF-16 offset of the text, moving the entities
+/// after it.
+pub fn insert(
+    text: &mut types::FormattedText,
+    offset: i32,
+    with: &str,
+) -> Result<(), InvalidOffset> {
+    replace(text, offset, 0, with)
+}
+
+/// Remove the UTF-16 range of the text, moving the entities after it.
+pub fn remove(
+    text: &mut types::FormattedText,
+    offset: i32,
+    length: i32,
+) -> Result<(), InvalidOffset> {
+    replace(text, offset, length, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::TextEntityType;
+    use proptest::prelude::*;
+
+    fn text(text: &str, entities: &[(i32, i32)]) -> types::FormattedText {
+        let entities = entities
+            .iter()
+            .map(|&(offset, length)| {
+                td_struct!(types::TextEntity {
+                    offset,
+                    length,
+                    r#type: TextEntityType::Bold
+                })
+            })
+            .collect();
+        td_struct!(types::FormattedText {
+            text: text.into(),
+            entities
+        })
+    }
+
+    /// Returns the UTF-16 offsets falling on the characters of the text.
+    fn boundaries(text: &str) -> Vec<i32> {
+        text.char_indices()
+            .map(|(byte, _)| byte)
+            .chain([text.len()])
+            .map(|byte| len(&text[..byte]))
+            .collect()
+    }
+
+    #[test]
+    fn check_offsets() {
+        let text = "a😀é";
+        assert_eq!(len(text), 4);
+        assert_eq!(to_byte_offset(text, 1), Some(1));
+        assert_eq!(to_byte_offset(text, 2), None);
+        assert_eq!(to_byte_offset(text, 3), Some(5));
+        assert_eq!(to_byte_offset(text, 4), Some(7));
+        assert_eq!(to_byte_offset(text, 5), None);
+        assert_eq!(from_byte_offset(text, 2), None);
+        assert_eq!(slice(text, 1, 2), Some("😀"));
+        assert_eq!(slice(text, 1, 1), None);
+    }
+
+    #[test]
+    fn check_replace() {
+        let mut formatted = text("hello world", &[(0, 5), (6, 5), (3, 5)]);
+        replace(&mut formatted, 4, 3, "😀").unwrap();
+        assert_eq!(formatted.text, "hell😀orld");
+        let entities = formatted
+            .entities
+            .iter()
+            .map(|entity| (entity.offset, entity.length))
+            .collect::<Vec<_>>();
+        assert_eq!(entities, [(0, 4), (6, 4), (3, 4)]);
+        assert_eq!(
+            remove(&mut formatted, 5, 1),
+            Err(InvalidOffset { offset: 5 })
+        );
+    }
+
+    #[test]
+    fn check_adjust_overflow() {
+        let mut entities = text("", &[(i32::MAX - 1, 10), (0, i32::MAX)]).entities;
+        adjust(&mut entities, i32::MAX - 2, i32::MAX, i32::MAX);
+        adjust(&mut entities, 1, 0, i32::MAX);
+        let entities = entities
+            .iter()
+            .map(|entity| (entity.offset, entity.length))
+            .collect::<Vec<_>>();
+        assert_eq!(entities, [(0, i32::MAX)]);
+    }
+
+    proptest! {
+        #[test]
+        fn offsets_round_trip(text in "\\PC*") {
+            for (byte, _) in text.char_indices() {
+                let offset = from_byte_offset(&text, byte).unwrap();
+                prop_assert_eq!(to_byte_offset(&text, offset), Some(byte));
+            }
+            prop_assert_eq!(to_byte_offset(&text, len(&text)), Some(text.len()));
+        }
+
+        #[test]
+        fn slices_are_the_utf16_units(text in "\\PC*", a in 0usize..64, b in 0usize..64) {
+            let utf16 = text.encode_utf16().collect::<Vec<_>>();
+            let (start, end) = (a.min(b).min(utf16.len()), a.max(b).min(utf16.len()));
+            let expected = String::from_utf16(&utf16[start..end]).ok();
+            let slice = slice(&text, start as i32, (end - start) as i32);
+            // The slices cutting an emoji in two are rejected
+            prop_assert_eq!(slice.map(str::to_owned), expected.filter(|_| {
+                to_byte_offset(&text, start as i32).is_some()
+                    && to_byte_offset(&text, end as i32).is_some()
+            }));
+        }
+
+        #[test]
+        fn entities_keep_their_text(
+            original in "\\PC{0,24}",
+            inserted in "\\PC{0,8}",
+            picks in proptest::collection::vec((0usize..32, 0usize..32), 0..6),
+            edit in (0usize..32, 0usize..32),
+        ) {
+            let bounds = boundaries(&original);
+            let pick = |i: usize| bounds[i % bounds.len()];
+            let entities = picks
+                .iter()
+                .map(|&(a, b)| (pick(a).min(pick(b)), (pick(a) - pick(b)).abs()))
+                .filter(|&(_, length)| length > 0)
+                .collect::<Vec<_>>();
+            let mut formatted = text(&original, &entities);
+            let (offset, end) = (pick(edit.0).min(pick(edit.1)), pick(edit.0).max(pick(edit.1)));
+            replace(&mut formatted, offset, end - offset, &inserted).unwrap();
+
+            prop_assert_eq!(len(&formatted.text), len(&original) - (end - offset) + len(&inserted));
+            for entity in &formatted.entities {
+                prop_assert!(entity.length > 0);
+                prop_assert!(entity_text(&formatted, entity).is_some());
+            }
+            // The entities outside of the edit cover the same text
+            for &(start, length) in &entities {
+                if start + length <= offset || start >= end {
+                    let before = slice(&original, start, length).unwrap();
+                    let moved = if start >= end { start - (end - offset) + len(&inserted) } else { start };
+                    prop_assert_eq!(slice(&formatted.text, moved, length), Some(before));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Mark the messages shown to the user as viewed, in batches.
+//!
+//! A [`ViewBatcher`] collects the messages reported visible with
+//! [`ViewBatcher::view`] and sends them to TdLib with one `viewMessages` per
+//! chat once no more messages of the chat were reported for a while, while
+//! [`ViewBatcher::run`] is awaited, for instance in a task of its own.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use tdlib_rs::tasks;
+//! use tdlib_rs::views::ViewBatcher;
+//!
+//! # async fn scroll(client_id: i32) {
+//! let views = Arc::new(ViewBatcher::new(Duration::from_millis(500), client_id));
+//! tokio::spawn(tasks::bind(client_id, {
+//!     let views = views.clone();
+//!     async move { views.run().await }
+//! }));
+//! // When the UI shows messages:
+//! # let (chat_id, message_id) = (Default::default(), Default::default());
+//! views.view(chat_id, message_id);
+//! # }
+//! ```
+use crate::enums::MessageSource;
+use crate::ids::{ChatId, MessageId};
+use crate::{functions, runtime, TdError};
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// The messages of a chat not sent yet.
+struct Pending {
+    message_ids: Vec<MessageId>,
+    first_viewed: Instant,
+    last_viewed: Instant,
+}
+
+impl Pending {
+    fn deadline(&self, delay: Duration, max_delay: Duration) -> Instant {
+        (self.last_viewed + delay).min(self.first_viewed + max_delay)
+    }
+}
+
+/// Collects the messages viewed by the user and marks them as viewed in
+/// batches per chat.
+pub struct ViewBatcher {
+    client_id: i32,
+    delay: Duration,
+    max_delay: Duration,
+    source: Option<MessageSource>,
+    force_read: bool,
+    pending: Mutex<HashMap<ChatId, Pending>>,
+    viewed: Notify,
+}
+
+impl ViewBatcher {
+    /// Create a batcher sending the messages of a chat once none was
+    /// reported for `delay`, and at the latest five times `delay` after the
+    /// first one.
+    pub fn new(delay: Duration, client_id: i32) -> Self {
+        Self {
+            client_id,
+            delay,
+            max_delay: delay * 5,
+            source: None,
+            force_read: false,
+            pending: Mutex::default(),
+            viewed: Notify::new(),
+        }
+    }
+
+    /// Send the messages of a chat at the latest `max_delay` after the first
+    /// one, even while more are reported.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Tell TdLib where the messages are shown, which is guessed from the
+    /// opened chats otherwise.
+    pub fn source(mut self, source: MessageSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Mark the messages as read even if the chats are closed.
+    pub fn force_read(mut self) -> Self {
+        self.force_read = true;
+        self
+    }
+
+    /// Report a message shown to the user.
+    pub fn view(&self, chat_id: ChatId, message_id: MessageId) {
+        let now = Instant::now();
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let views = pending.entry(chat_id).or_insert_with(|| Pending {
+            message_ids: Vec::new(),
+            first_viewed: now,
+            last_viewed: now,
+        });
+        views.message_ids.push(message_id);
+        views.last_viewed = now;
+        drop(pending);
+        self.viewed.notify_one();
+    }
+
+    /// Send the messages of the chats which are due, or of every chat if
+    /// `all`, returning when the next ones are due.
+    async fn send(&self, all: bool) -> Result<Option<Instant>, TdError> {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        let mut next = None::<Instant>;
+        {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.retain(|chat_id, views| {
+                let deadline = views.deadline(self.delay, self.max_delay);
+                if all || deadline <= now {
+                    due.push((*chat_id, std::mem::take(&mut views.message_ids)));
+                    false
+                } else {
+                    next = Some(next.map_or(deadline, |next| next.min(deadline)));
+                    true
+                }
+            });
+        }
+
+        let mut result = Ok(());
+        for (chat_id, mut message_ids) in due {
+            message_ids.sort_unstable();
+            message_ids.dedup();
+            if let Err(error) = functions::view_messages(
+                chat_id,
+                message_ids,
+                self.source.clone(),
+                self.force_read,
+                self.client_id,
+            )
+            .await
+            {
+                result = Err(error.into());
+            }
+        }
+        result.map(|()| next)
+    }
+
+    /// Send every message reported so far, without waiting for the delay.
+    pub async fn flush(&self) -> Result<(), TdError> {
+        self.send(true).await?;
+        Ok(())
+    }
+
+    /// Send the messages of the chats as they are due, forever. The messages
+    /// which TdLib failed to mark as viewed are dropped with a warning, as
+    /// the user will view them again.
+    pub async fn run(&self) {
+        loop {
+            let next = match self.send(false).await {
+                Ok(next) => next,
+                Err(error) => {
+                    log::warn!("Failed to mark messages as viewed: {error:?}");
+                    continue;
+                }
+            };
+            let mut viewed = pin!(self.viewed.notified());
+            match next {
+                Some(deadline) => {
+                    let mut sleep = pin!(runtime::sleep(
+                        deadline.saturating_duration_since(Instant::now())
+                    ));
+                    poll_fn(|cx| {
+                        if viewed.as_mut().poll(cx).is_ready() {
+                            return Poll::Ready(());
+                        }
+                        sleep.as_mut().poll(cx)
+                    })
+                    .await;
+                }
+                None => viewed.await,
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2024 - developers of the `tgt` and `tdlib-rs` projects.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Open the Web Apps (Mini Apps) of the bots and the login buttons of the
+//! websites, making the sequence of requests each of them needs.
+use crate::enums::{self, InputMessageReplyTo, LoginUrlInfo};
+use crate::ids::{ChatId, MessageId, UserId};
+use crate::{functions, types, TdError};
+use std::future::Future;
+
+/// A Web App opened by [`open`], which must be closed by [`WebApp::close`]
+/// when its web view is closed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebApp {
+    /// The identifier of the launch of the Web App.
+    pub launch_id: i64,
+    /// The URL to open in a web view.
+    pub url: String,
+    client_id: i32,
+}
+
+impl WebApp {
+    /// Tell TdLib that the web view of the Web App was closed.
+    pub async fn close(self) -> Result<(), TdError> {
+        functions::close_web_app(self.launch_id, self.client_id).await?;
+        Ok(())
+    }
+}
+
+/// Open a Web App from the attachment menu or from a `web_app` button of the
+/// menu of the bot, in the chat where its messages will be sent.
+#[allow(clippy::too_many_arguments)]
+pub async fn open(
+    chat_id: ChatId,
+    bot_user_id: UserId,
+    url: String,
+    theme: Option<types::ThemeParameters>,
+    application_name: String,
+    message_thread_id: i64,
+    reply_to: Option<InputMessageReplyTo>,
+    client_id: i32,
+) -> Result<WebApp, TdError> {
+    let enums::WebAppInfo::WebAppInfo(info) = functions::open_web_app(
+        chat_id,
+        bot_user_id,
+        url,
+        theme,
+        application_name,
+        message_thread_id,
+        reply_to,
+        client_id,
+    )
+    .await?;
+    Ok(WebApp {
+        launch_id: info.launch_id,
+        url: info.url,
+        client_id,
+    })
+}
+
+/// Returns the URL to open in a web view for a Web App opened from a
+/// `web_app` keyboard button, an inline button or the inline mode.
+pub async fn url(
+    bot_user_id: UserId,
+    url: String,
+    theme: Option<types::ThemeParameters>,
+    application_name: String,
+    client_id: i32,
+) -> Result<String, TdError> {
+    let enums::HttpUrl::HttpUrl(url) =
+        functions::get_web_app_url(bot_user_id, url, theme, application_name, client_id).await?;
+    Ok(url.url)
+}
+
+/// Answer a query received from a Web App, sending a message on behalf of
+/// the user. Returns the identifier of the sent inline message, if known.
+#[cfg(feature = "bots-only-api")]
+pub async fn answer_query(
+    web_app_query_id: String,
+    result: enums::InputInlineQueryResult,
+    client_id: i32,
+) -> Result<Option<String>, TdError> {
+    let enums::SentWebAppMessage::SentWebAppMessage(message) =
+        functions::answer_web_app_query(web_app_query_id, result, client_id).await?;
+    Ok(Some(message.inline_message_id).filter(|id| !id.is_empty()))
+}
+
+/// The answer of the user to the confirmation of a login button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoginConfirmation {
+    /// Log in the website, allowing its bot to send messages to the user if
+    /// `allow_write_access`.
+    Authorize { allow_write_access: bool },
+    /// Open the URL without logging in.
+    Decline,
+}
+
+/// Returns the URL to open for a login button, logging the user in the
+/// website. If TdLib asks for it, `confirm` is called to show the
+/// confirmation to the user.
+///
+/// As recommended by TdLib, the button is opened as an ordinary URL button
+/// if the user can't be logged in.
+pub async fn login_url<F, Fut>(
+    chat_id: ChatId,
+    message_id: MessageId,
+    button_id: i64,
+    confirm: F,
+    client_id: i32,
+) -> Result<String, TdError>
+where
+    F: FnOnce(types::LoginUrlInfoRequestConfirmation) -> Fut,
+    Fut: Future<Output = LoginConfirmation>,
+{
+    let confirmation =
+        match functions::get_login_url_info(chat_id, message_id, button_id, client_id).await? {
+            LoginUrlInfo::Open(info) => return Ok(info.url),
+            LoginUrlInfo::RequestConfirmation(confirmation) => confirmation,
+            LoginUrlInfo::Unsupported => {
+                return Err(TdError::invalid(500, "Unsupported login URL info"))
+            }
+        };
+
+    let url = confirmation.url.clone();
+    match confirm(confirmation).await {
+        LoginConfirmation::Authorize { allow_write_access } => {
+            match functions::get_login_url(
+                chat_id,
+                message_id,
+                button_id,
+                allow_write_access,
+                client_id,
+            )
+            .await
+            {
+                Ok(enums::HttpUrl::HttpUrl(login_url)) => Ok(login_url.url),
+                Err(_) => Ok(url),
+            }
+        }
+        LoginConfirmation::Decline => Ok(url),
+    }
+}
@@ -0,0 +1,233 @@
+// cargo test -p tdlib-rs --test fake_td --features testing,config
+
+use std::time::{Duration, Instant};
+use tdlib_rs::enums::{
+    self, AuthorizationState, ChatList, ChatType, MessageSender, SecretChatState,
+};
+use tdlib_rs::forward;
+use tdlib_rs::profile_photo::{self, PhotoSize};
+use tdlib_rs::rate_limit::{self, Limits, Rate};
+use tdlib_rs::testing::FakeTd;
+use tdlib_rs::{chat_lists, functions, identity, names, secret_chats, types};
+
+#[cfg(feature = "config")]
+#[tokio::test]
+async fn authorization_with_the_config() {
+    use tdlib_rs::config::ClientConfig;
+
+    let td = FakeTd::new()
+        .authentication_code("22222")
+        .password("secret");
+    let client_id = td.client_id();
+    let config = ClientConfig::from_toml_str(
+        r#"
+        api_id = 12345
+        api_hash = "0123456789abcdef0123456789abcdef"
+        database_directory = "fake"
+        "#,
+    )
+    .unwrap();
+
+    config.apply(client_id).await.unwrap();
+    let parameters = td.calls_to("setTdlibParameters");
+    assert_eq!(parameters.len(), 1);
+    assert_eq!(parameters[0]["database_directory"], "fake");
+    assert!(matches!(
+        td.authorization_state(),
+        AuthorizationState::WaitPhoneNumber
+    ));
+
+    functions::set_authentication_phone_number("+1234567890".into(), None, client_id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        td.authorization_state(),
+        AuthorizationState::WaitCode(_)
+    ));
+    functions::check_authentication_code("22222".into(), client_id)
+        .await
+        .unwrap();
+    functions::check_authentication_password("secret".into(), client_id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        td.authorization_state(),
+        AuthorizationState::Ready
+    ));
+
+    // The parameters can't be sent again once authorized
+    assert!(config.set_tdlib_parameters(client_id).await.is_err());
+}
+
+#[tokio::test]
+async fn authorization_errors() {
+    let td = FakeTd::new();
+    let client_id = td.client_id();
+
+    let error = functions::check_authentication_code("12345".into(), client_id)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, 400);
+
+    td.authorize();
+    td.fail("getMe", 401, "Unauthorized");
+    let error = functions::get_me(client_id).await.unwrap_err();
+    assert_eq!(error.code, 401);
+    let enums::User::User(me) = functions::get_me(client_id).await.unwrap();
+    assert_eq!(me.id, td.me().id);
+}
+
+#[tokio::test]
+async fn download_avatar() {
+    let td = FakeTd::new();
+    let client_id = td.client_id();
+    td.authorize();
+    let chat_id = td.add_chat("Alice");
+    chat_lists::load_all_chats(ChatList::Main, client_id)
+        .await
+        .unwrap();
+    let sender = MessageSender::User(types::MessageSenderUser {
+        user_id: td.me().id,
+    });
+
+    // The users of the fake client have no photo
+    let path = profile_photo::download_avatar(&sender, PhotoSize::Small, client_id)
+        .await
+        .unwrap();
+    assert_eq!(path, None);
+    assert!(td.calls_to("downloadFile").is_empty());
+
+    let file = |path: &str, is_downloading_completed| types::File {
+        local: types::LocalFile {
+            path: path.into(),
+            is_downloading_completed,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let user = types::User {
+        profile_photo: Some(types::ProfilePhoto {
+            small: file("", false),
+            big: file("", false),
+            ..Default::default()
+        }),
+        ..td.me()
+    };
+    td.respond("getUser", enums::User::User(user));
+    td.respond("downloadFile", enums::File::File(file("avatar.jpg", true)));
+    let path = profile_photo::download_avatar(&sender, PhotoSize::Small, client_id)
+        .await
+        .unwrap();
+    assert_eq!(path.as_deref(), Some("avatar.jpg"));
+    let downloads = td.calls_to("downloadFile");
+    assert_eq!(downloads.len(), 1);
+    assert_eq!(downloads[0]["synchronous"], true);
+    assert_eq!(
+        names::chat_title(chat_id, client_id).as_deref(),
+        Some("Alice")
+    );
+}
+
+#[tokio::test]
+async fn trackers() {
+    let td = FakeTd::new();
+    let client_id = td.client_id();
+    td.authorize();
+    let alice = td.add_chat("Alice");
+    let bob = td.add_chat("Bob");
+
+    let chats = chat_lists::load_all_chats(ChatList::Main, client_id)
+        .await
+        .unwrap();
+    assert_eq!(chats, [bob, alice]);
+    assert_eq!(
+        names::chat_title(alice, client_id).as_deref(),
+        Some("Alice")
+    );
+
+    // A new message moves its chat to the top of the list
+    td.receive_message(alice, "Hello");
+    assert_eq!(chat_lists::chats(&ChatList::Main, client_id), [alice, bob]);
+
+    // The user is requested once, then kept up to date
+    assert_eq!(identity::me(client_id).await.unwrap().id, td.me().id);
+    assert_eq!(identity::me(client_id).await.unwrap().id, td.me().id);
+    assert_eq!(td.calls_to("getMe").len(), 1);
+    let me = types::User {
+        first_name: "Renamed".into(),
+        ..td.me()
+    };
+    td.emit(enums::Update::User(types::UpdateUser { user: me }));
+    assert_eq!(identity::me(client_id).await.unwrap().first_name, "Renamed");
+    assert_eq!(td.calls_to("getMe").len(), 1);
+}
+
+#[tokio::test]
+async fn forward_waits_for_the_limiter() {
+    let td = FakeTd::new();
+    let client_id = td.client_id();
+    td.authorize();
+    let chat_id = td.add_chat("Alice");
+    let message_ids = (1..=3)
+        .map(|_| td.receive_message(chat_id, "Hello").id)
+        .collect();
+
+    let period = Duration::from_millis(300);
+    rate_limit::enable(Limits {
+        per_chat: Rate::new(2, period),
+        ..Limits::default()
+    });
+    let forwarded = enums::Messages::Messages(types::Messages::default());
+    td.respond("forwardMessages", forwarded);
+    let start = Instant::now();
+    // The third message waits for the first one to leave the window
+    forward::forward(chat_id, message_ids, chat_id)
+        .send(client_id)
+        .await
+        .unwrap();
+    rate_limit::disable();
+    assert!(start.elapsed() >= period);
+    assert_eq!(td.calls_to("forwardMessages").len(), 1);
+}
+
+#[tokio::test]
+async fn wait_for_the_secret_chat() {
+    let td = FakeTd::new();
+    let client_id = td.client_id();
+    td.authorize();
+    let chat_id = td.add_chat("Alice");
+    let enums::Chat::Chat(chat) = functions::get_chat(chat_id, client_id).await.unwrap();
+    let chat = types::Chat {
+        r#type: ChatType::Secret(types::ChatTypeSecret {
+            secret_chat_id: 7,
+            user_id: td.me().id,
+        }),
+        ..chat
+    };
+    td.respond("getChat", enums::Chat::Chat(chat));
+    let secret_chat = |state| {
+        enums::Update::SecretChat(types::UpdateSecretChat {
+            secret_chat: types::SecretChat {
+                id: 7,
+                user_id: td.me().id,
+                state,
+                is_outbound: true,
+                key_hash: String::new(),
+                layer: 0,
+            },
+        })
+    };
+    td.emit(secret_chat(SecretChatState::Pending));
+
+    let ready = async {
+        while td.calls_to("getChat").len() < 2 {
+            tokio::task::yield_now().await;
+        }
+        td.emit(secret_chat(SecretChatState::Ready));
+    };
+    let (is_ready, ()) = tokio::join!(secret_chats::wait_ready(chat_id, client_id), ready);
+    assert!(is_ready.unwrap());
+    // The state is read from the updates, not requested again
+    assert_eq!(td.calls_to("getChat").len(), 2);
+    assert!(td.calls_to("getSecretChat").is_empty());
+}